@@ -3,7 +3,10 @@
 /// How can one verify that two matrices were multiplied correctly.
 /// First,choose a random `r∈Fp`,and let x=(1,r,r2,...,rn−1).
 /// Then compute `y=Cx` and `z=A·Bx`,outputting YES if y = z and NO otherwise.
-mod matrix;
+///
+/// The sumcheck-based interactive proof for matrix multiplication is in `matmult`.
+pub mod matmult;
+pub mod matrix;
 mod prover;
 mod utils;
 mod verifier;
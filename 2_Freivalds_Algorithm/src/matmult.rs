@@ -0,0 +1,311 @@
+use crate::matrix::Matrix;
+use bls12_381::Scalar;
+use rand_core::{OsRng, RngCore};
+use sumcheck::oracle::Oracle;
+use sumcheck::poly::MultivariatePolynomial;
+use sumcheck::sumcheck::{EvaluationClaim, SumCheck};
+use sumcheck::utils::eq_table;
+
+// The sumcheck-based interactive proof for matrix multiplication (Section 4.4 of the book).
+//
+// For n×n matrices (n = 2^m) with C = A · B, view each matrix as a function {0,1}^m × {0,1}^m → F. Then
+//     C̃(r1, r2) = ∑_{k∈{0,1}^m} Ã(r1, k)·B̃(k, r2)
+//
+// 1. V picks random (r1, r2) and evaluates C̃(r1, r2) from the claimed C in O(n²).
+// 2. Apply the sumcheck on g(k) = Ã(r1, k)·B̃(k, r2), which has degree 2 in each variable.
+// 3. At the end, V needs Ã(r1, r*) and B̃(r*, r2), which costs O(n²) from the entries of A and B.
+//
+// In a GKR-style proof, eg: for matrix powers, the claims in step 1 and 3 come from
+// and go to the other sub-steps instead, see `MatMult::with_claim`.
+pub struct MatMult<O: Oracle = MatrixOracle> {
+    claim: EvaluationClaim,
+    sumcheck: SumCheck<MatMultPoly, MatMultOracle<O>>,
+}
+
+impl MatMult {
+    // V knows A, B and the claimed C, and evaluates their MLEs by itself.
+    pub fn new(a: &Matrix, b: &Matrix, c: &Matrix) -> Self {
+        let log_n = log_size(c);
+        let point = (0..2 * log_n)
+            .map(|_| (OsRng.next_u32() % 1000) as usize)
+            .collect::<Vec<_>>();
+        let value = MatrixOracle::new(c).query(&point);
+
+        let claim = EvaluationClaim { point, value };
+        Self::with_claim(a, b, claim, MatrixOracle::new(a), MatrixOracle::new(b))
+    }
+}
+
+impl<O: Oracle> MatMult<O> {
+    // Prove the claim C̃(r1, r2) = value, with C = A · B.
+    // `a` and `b` are V's oracle access to Ã and B̃.
+    pub fn with_claim(
+        a: &Matrix,
+        b: &Matrix,
+        claim: EvaluationClaim,
+        a_oracle: O,
+        b_oracle: O,
+    ) -> Self {
+        let log_n = log_size(a);
+        assert_eq!(log_n, log_size(b));
+        assert_eq!(claim.point.len(), 2 * log_n);
+
+        let (r1, r2) = claim.point.split_at(log_n);
+        let g = MatMultPoly::new(a, b, r1, r2);
+        let oracle = MatMultOracle {
+            r1: r1.to_vec(),
+            r2: r2.to_vec(),
+            a: a_oracle,
+            b: b_oracle,
+        };
+
+        Self {
+            claim,
+            sumcheck: SumCheck::with_oracle(g, oracle),
+        }
+    }
+
+    pub fn run_protocol(&mut self) {
+        // P's claimed sum must be the C̃(r1, r2) known by V.
+        assert_eq!(
+            self.sumcheck.claim(),
+            self.claim.value,
+            "MatMult: the sum doesn't match C̃(r1, r2)"
+        );
+        self.sumcheck.run_protocol();
+    }
+
+    // V's oracles to Ã and B̃, eg: to collect the claims Ã(r1, r*) and B̃(r*, r2) for the next sub-steps.
+    pub fn oracles(&self) -> (&O, &O) {
+        let oracle = self.sumcheck.oracle();
+        (&oracle.a, &oracle.b)
+    }
+}
+
+fn log_size(matrix: &Matrix) -> usize {
+    let n = matrix.rows();
+    assert_eq!(n, matrix.cols(), "Only support square matrix");
+    assert!(
+        n.is_power_of_two(),
+        "The size of matrix should be a power of 2"
+    );
+    n.trailing_zeros() as usize
+}
+
+fn to_scalars(point: &[usize]) -> Vec<Scalar> {
+    point.iter().map(|r| Scalar::from(*r as u64)).collect()
+}
+
+// g(k) = Ã(r1, k)·B̃(k, r2), where the restrictions Ã(r1, ·) and B̃(·, r2) are multilinear in k.
+#[derive(Debug, Clone)]
+pub struct MatMultPoly {
+    log_n: usize,
+    a_r1: Vec<Scalar>, // Ã(r1, k) on k in {0,1}^m
+    b_r2: Vec<Scalar>, // B̃(k, r2) on k in {0,1}^m
+}
+
+impl MatMultPoly {
+    // Ã(r1, k) = ∑_i A[i][k]·χ_i(r1) and B̃(k, r2) = ∑_j B[k][j]·χ_j(r2) on k in {0,1}^m, O(n²) in total.
+    pub fn new(a: &Matrix, b: &Matrix, r1: &[usize], r2: &[usize]) -> Self {
+        let log_n = r1.len();
+        let chi_r1 = eq_table(&to_scalars(r1));
+        let chi_r2 = eq_table(&to_scalars(r2));
+
+        let a_r1 = (0..a.cols())
+            .map(|k| {
+                a.values()
+                    .iter()
+                    .zip(chi_r1.iter())
+                    .map(|(row, chi)| row[k] * chi)
+                    .sum()
+            })
+            .collect::<Vec<Scalar>>();
+        let b_r2 = b
+            .values()
+            .iter()
+            .map(|row| row.iter().zip(chi_r2.iter()).map(|(b, chi)| b * chi).sum())
+            .collect::<Vec<Scalar>>();
+
+        Self { log_n, a_r1, b_r2 }
+    }
+}
+
+impl MultivariatePolynomial for MatMultPoly {
+    fn var_num(&self) -> usize {
+        self.log_n
+    }
+
+    fn degree(&self) -> usize {
+        2
+    }
+
+    // Ã(r1, k)·B̃(k, r2) = (∑_i Ã(r1, i)·χ_i(k))·(∑_i B̃(i, r2)·χ_i(k)),
+    // which is in F, as the challenges multiply out of usize.
+    fn evaluate(&self, domain: &[usize]) -> Scalar {
+        let chi_k = eq_table(&to_scalars(domain));
+        let a: Scalar = self
+            .a_r1
+            .iter()
+            .zip(chi_k.iter())
+            .map(|(a, chi)| a * chi)
+            .sum();
+        let b: Scalar = self
+            .b_r2
+            .iter()
+            .zip(chi_k.iter())
+            .map(|(b, chi)| b * chi)
+            .sum();
+        a * b
+    }
+}
+
+// V's oracle to g(r*) = Ã(r1, r*)·B̃(r*, r2).
+pub struct MatMultOracle<O: Oracle> {
+    r1: Vec<usize>,
+    r2: Vec<usize>,
+    a: O,
+    b: O,
+}
+
+impl<O: Oracle> Oracle for MatMultOracle<O> {
+    fn query(&mut self, point: &[usize]) -> Scalar {
+        let a_point = [self.r1.as_slice(), point].concat();
+        let b_point = [point, self.r2.as_slice()].concat();
+        self.a.query(&a_point) * self.b.query(&b_point)
+    }
+}
+
+// V evaluates the MLE of a matrix from its entries:
+//     M̃(x, y) = ∑_{i,j} M[i][j]·χ_i(x)·χ_j(y), which costs O(n²).
+pub struct MatrixOracle {
+    values: Vec<Vec<Scalar>>,
+}
+
+impl MatrixOracle {
+    pub fn new(matrix: &Matrix) -> Self {
+        Self {
+            values: matrix.values().clone(),
+        }
+    }
+}
+
+impl Oracle for MatrixOracle {
+    fn query(&mut self, point: &[usize]) -> Scalar {
+        let (x, y) = point.split_at(point.len() / 2);
+        let chi_x = eq_table(&to_scalars(x));
+        let chi_y = eq_table(&to_scalars(y));
+
+        self.values
+            .iter()
+            .zip(chi_x)
+            .map(|(row, chi_i)| {
+                let row_sum: Scalar = row
+                    .iter()
+                    .zip(chi_y.iter())
+                    .map(|(m, chi_j)| m * chi_j)
+                    .sum();
+                row_sum * chi_i
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sumcheck::oracle::DelegatedOracle;
+    use sumcheck::poly::multivar_poly::MPolynomial;
+    use sumcheck::utils::convert_to_binary;
+
+    // The coefficient form of M̃, for the delegated claims.
+    fn matrix_mle(matrix: &Matrix) -> MPolynomial {
+        let log_n = log_size(matrix);
+        let evals = matrix
+            .values()
+            .iter()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>();
+        MPolynomial::lagrange(2 * log_n, &evals)
+    }
+
+    #[test]
+    fn test_matrix_oracle() {
+        let matrix = Matrix::random(4, 4);
+        let mle = matrix_mle(&matrix);
+        let mut oracle = MatrixOracle::new(&matrix);
+
+        // agree with the entries on the hypercube.
+        for n in 0..16 {
+            let point = convert_to_binary(&4, n);
+            assert_eq!(oracle.query(&point), matrix.values()[n >> 2][n & 3]);
+        }
+        let point = vec![3, 5, 7, 11];
+        assert_eq!(oracle.query(&point), mle.evaluate(&point));
+    }
+
+    #[test]
+    fn test_matmult() {
+        // n = 128, where the challenges of 7 variables multiply out of usize.
+        for n in [4, 8, 128] {
+            let a = Matrix::random(n, n);
+            let b = Matrix::random(n, n);
+            let c = Matrix::mul(&a, &b);
+
+            let mut protocol = MatMult::new(&a, &b, &c);
+            protocol.run_protocol();
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "MatMult: the sum doesn't match C̃(r1, r2)")]
+    fn test_matmult_wrong_product() {
+        let n = 4;
+        let a = Matrix::random(n, n);
+        let b = Matrix::random(n, n);
+        let mut values = Matrix::mul(&a, &b).values().clone();
+        values[1][2] += Scalar::one();
+        let c = Matrix::new(values);
+
+        let mut protocol = MatMult::new(&a, &b, &c);
+        protocol.run_protocol();
+    }
+
+    // A^4 = A^2 · A^2, as a GKR-style sequence of MatMult sub-steps:
+    //     the claim on Ã^4 is reduced to two claims on Ã^2, and each of them to two claims on Ã.
+    #[test]
+    fn test_matrix_power() {
+        let n = 4;
+        let a = Matrix::random(n, n);
+        let a_2 = Matrix::mul(&a, &a);
+        let a_4 = Matrix::mul(&a_2, &a_2);
+
+        // V picks (r1, r2) and evaluates the claimed output Ã^4 by itself.
+        let point = vec![17, 23, 29, 31];
+        let claim = EvaluationClaim {
+            point: point.clone(),
+            value: MatrixOracle::new(&a_4).query(&point),
+        };
+
+        // Ã^4(r1, r2) -> Ã^2(r1, r*), Ã^2(r*, r2)
+        let mle_a_2 = matrix_mle(&a_2);
+        let mut step = MatMult::with_claim(
+            &a_2,
+            &a_2,
+            claim,
+            DelegatedOracle::new(mle_a_2.clone()),
+            DelegatedOracle::new(mle_a_2.clone()),
+        );
+        step.run_protocol();
+        let (left, right) = step.oracles();
+        let claims = [left.claims(), right.claims()].concat();
+        assert_eq!(claims.len(), 2);
+
+        // Ã^2(·) -> Ã(·), Ã(·), which V checks directly.
+        for claim in claims {
+            let mut step =
+                MatMult::with_claim(&a, &a, claim, MatrixOracle::new(&a), MatrixOracle::new(&a));
+            step.run_protocol();
+        }
+    }
+}
@@ -13,6 +13,29 @@ pub struct Matrix {
 }
 
 impl Matrix {
+    pub fn new(values: Vec<Vec<Scalar>>) -> Self {
+        let rows = values.len();
+        let cols = values.first().map(|row| row.len()).unwrap_or(0);
+        assert!(
+            values.iter().all(|row| row.len() == cols),
+            "rows with different length"
+        );
+
+        Self { rows, cols, values }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn values(&self) -> &Vec<Vec<Scalar>> {
+        &self.values
+    }
+
     pub fn random(rows: usize, cols: usize) -> Self {
         let values = (0..rows)
             .map(|_| (0..cols).map(|_| Scalar::random(OsRng)).collect::<Vec<_>>())
@@ -0,0 +1,49 @@
+use bls12_381::Scalar;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ff::Field;
+use rand_core::OsRng;
+use GKR::arithmetic::layered_circuit::CircuitConfig;
+use GKR::gkr::prove_with_reduction;
+use GKR::gkr_sumcheck::ClaimReduction;
+
+// The non-interactive GKR prover with the line and the random combination reductions,
+// on the same random circuits of depth 4 with 2^k gates at each layer.
+// The proof sizes are printed before the benches.
+fn bench_claim_reduction(c: &mut Criterion) {
+    let min_k: usize = std::env::var("DEGREE")
+        .unwrap_or_else(|_| "6".to_string())
+        .parse()
+        .expect("Cannot parse DEGREE env var as usize");
+
+    const MAX_K: usize = 12;
+
+    let mut group = c.benchmark_group("claim_reduction");
+    group.sample_size(10);
+
+    for k in min_k..=MAX_K {
+        let config = CircuitConfig::random(&[k, k, k, k], &mut OsRng);
+        let inputs = (0..1 << k)
+            .map(|_| Scalar::random(OsRng))
+            .collect::<Vec<_>>();
+
+        for reduction in [ClaimReduction::Line, ClaimReduction::RandomCombination] {
+            let proof = prove_with_reduction(&config, &inputs, reduction);
+            println!(
+                "k = {}, {:?}: {} field elements",
+                k,
+                reduction,
+                proof.scalar_num()
+            );
+
+            let id = BenchmarkId::new(format!("{:?}", reduction), k);
+            group.bench_function(id, |b| {
+                b.iter(|| prove_with_reduction(&config, &inputs, reduction));
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_claim_reduction);
+criterion_main!(benches);
@@ -0,0 +1,51 @@
+use bls12_381::Scalar;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ff::Field;
+use rand_core::OsRng;
+use GKR::arithmetic::layered_circuit::CircuitConfig;
+use GKR::gkr_sumcheck::libra_prover::LibraProver;
+use GKR::gkr_sumcheck::LayerProver;
+
+// The sumcheck prover of one layer, on random circuits with 2^k gates at the layers.
+fn bench_gkr_prover(c: &mut Criterion) {
+    let min_k: usize = std::env::var("DEGREE")
+        .unwrap_or_else(|_| "10".to_string())
+        .parse()
+        .expect("Cannot parse DEGREE env var as usize");
+
+    const MAX_K: usize = 18;
+
+    let mut group = c.benchmark_group("gkr_prover");
+    group.sample_size(10);
+
+    for k in min_k..=MAX_K {
+        let config = CircuitConfig::random(&[k, k], &mut OsRng);
+        let inputs = (0..1 << k)
+            .map(|_| Scalar::random(OsRng))
+            .collect::<Vec<_>>();
+        let (witness, _) = config.witness_to_poly(&inputs);
+        let wiring = config.ops_to_wiring().remove(0);
+        let r_0 = (0..k).map(|_| Scalar::random(OsRng)).collect::<Vec<_>>();
+        let challenges = (0..2 * k)
+            .map(|_| Scalar::random(OsRng))
+            .collect::<Vec<_>>();
+
+        group.bench_function(BenchmarkId::new("k", k), |b| {
+            b.iter(|| {
+                let mut prover =
+                    LibraProver::new((wiring.clone(), witness[1].clone()), r_0.clone());
+                prover.proof();
+                prover.round_1();
+                for j in 2..=2 * k {
+                    prover.recursive_round_j(&challenges[..j - 1]);
+                }
+                prover.evaluate(&challenges);
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_gkr_prover);
+criterion_main!(benches);
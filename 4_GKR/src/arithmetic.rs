@@ -23,7 +23,10 @@
 //
 //  NOTE: wiring predicate(addi, multi) depend only on the circuit C and not on the input x to C
 
+pub mod builder;
+pub mod circuit_file;
+pub mod data_parallel;
 pub mod layered_circuit;
+pub mod r1cs;
 
-// pub mod r1cs
 // pub mod plonk
@@ -0,0 +1,328 @@
+// A front end to write the circuit as a program, instead of the layers, eg:
+//      let mut c = CircuitBuilder::new();
+//      let x = c.input();
+//      let y = c.mul(x, x);
+//      let z = c.add(y, x);
+//      c.output(z);
+//      let circuit = c.build();
+//
+// build() turns it into a layered circuit which GKR can prove:
+//  1. A gate is at level 1 + max(levels of its inputs), the inputs are at level 0, and the constants at level 1.
+//     The outputs are at the top level L, and level l is layer L-l of the layered circuit.
+//  2. A wire from level l-k to level l(k > 1) is relayed by an `id` gate at each level in between.
+//  3. Each layer is padded with `const 0` to a power of 2 gates. The input layer is padded with 0.
+use crate::arithmetic::layered_circuit::Ops::{ADD, CMUL, CONST, ID, MUL, SUB};
+use crate::arithmetic::layered_circuit::{CircuitConfig, Layer, Ops};
+use bls12_381::Scalar;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+// A wire of the circuit, returned by the builder.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Wire(usize);
+
+#[derive(Clone, Debug)]
+enum Node {
+    One,
+    Input,
+    Gate(Ops), // the inputs are the wires, instead of the indexes in layer i+1.
+}
+
+#[derive(Clone, Debug)]
+pub struct CircuitBuilder {
+    nodes: Vec<Node>,
+    levels: Vec<usize>,
+    outputs: Vec<Wire>,
+}
+
+// The layered circuit, and where the inputs and outputs of the builder are in it.
+#[derive(Clone, Debug)]
+pub struct BuiltCircuit {
+    pub config: CircuitConfig,
+    // the index of each input in the input layer, whose index 0 is the constant 1.
+    pub inputs: Vec<usize>,
+    pub outputs: Vec<usize>, // the index of each output in layer 0.
+}
+
+impl Default for CircuitBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CircuitBuilder {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node::One],
+            levels: vec![0],
+            outputs: vec![],
+        }
+    }
+
+    // The constant 1 in the input layer.
+    pub fn one(&self) -> Wire {
+        Wire(0)
+    }
+
+    pub fn input(&mut self) -> Wire {
+        self.push(Node::Input, 0)
+    }
+
+    pub fn add(&mut self, left: Wire, right: Wire) -> Wire {
+        self.gate(ADD(left.0, right.0))
+    }
+
+    pub fn mul(&mut self, left: Wire, right: Wire) -> Wire {
+        self.gate(MUL(left.0, right.0))
+    }
+
+    pub fn sub(&mut self, left: Wire, right: Wire) -> Wire {
+        self.gate(SUB(left.0, right.0))
+    }
+
+    pub fn constant(&mut self, c: Scalar) -> Wire {
+        self.gate(CONST(c))
+    }
+
+    pub fn cmul(&mut self, c: Scalar, input: Wire) -> Wire {
+        self.gate(CMUL(c, input.0))
+    }
+
+    pub fn output(&mut self, wire: Wire) {
+        assert!(wire.0 < self.nodes.len(), "Unknown wire {:?}", wire);
+        self.outputs.push(wire);
+    }
+
+    fn gate(&mut self, op: Ops) -> Wire {
+        let inputs = op.inputs();
+        for input in inputs.iter() {
+            assert!(*input < self.nodes.len(), "Unknown wire {:?}", Wire(*input));
+        }
+        let level = 1 + inputs.iter().map(|i| self.levels[*i]).max().unwrap_or(0);
+        self.push(Node::Gate(op), level)
+    }
+
+    fn push(&mut self, node: Node, level: usize) -> Wire {
+        self.nodes.push(node);
+        self.levels.push(level);
+        Wire(self.nodes.len() - 1)
+    }
+
+    // The gate computing the wire at level l.
+    fn gate_at(&self, wire: Wire, l: usize) -> Option<&Ops> {
+        match &self.nodes[wire.0] {
+            Node::Gate(op) if self.levels[wire.0] == l => Some(op),
+            _ => None,
+        }
+    }
+
+    pub fn build(&self) -> BuiltCircuit {
+        assert!(!self.outputs.is_empty(), "The circuit has no output");
+        // at least one layer of gates, even if the outputs are inputs.
+        let top = self
+            .outputs
+            .iter()
+            .map(|w| self.levels[w.0])
+            .max()
+            .unwrap()
+            .max(1);
+
+        // The wires at each level from L to 1.
+        let mut levels = vec![LevelWires::default()];
+        for w in self.outputs.iter() {
+            levels[0].insert(*w);
+        }
+        for l in (2..=top).rev() {
+            let mut below = LevelWires::default();
+            for w in levels.last().unwrap().wires.iter() {
+                match self.gate_at(*w, l) {
+                    Some(op) => op.inputs().into_iter().for_each(|i| below.insert(Wire(i))),
+                    // relay
+                    None => below.insert(*w),
+                }
+            }
+            levels.push(below);
+        }
+
+        // The input layer(level 0) keeps the 1 and all the inputs, in the order of `input()`.
+        let mut input_layer = LevelWires::default();
+        for (i, node) in self.nodes.iter().enumerate() {
+            if let Node::One | Node::Input = node {
+                input_layer.insert(Wire(i));
+            }
+        }
+        let input_var_num = padded_var_num(input_layer.wires.len());
+
+        // levels[i] is layer i, and its gates take inputs from levels[i+1] or the input layer.
+        let layers = (0..levels.len())
+            .map(|i| {
+                let (level, below) = (&levels[i], levels.get(i + 1).unwrap_or(&input_layer));
+                let index = |input: usize| below.index(Wire(input));
+                let mut gates = level
+                    .wires
+                    .iter()
+                    .map(|w| match self.gate_at(*w, top - i) {
+                        Some(ADD(left, right)) => ADD(index(*left), index(*right)),
+                        Some(MUL(left, right)) => MUL(index(*left), index(*right)),
+                        Some(SUB(left, right)) => SUB(index(*left), index(*right)),
+                        Some(CONST(c)) => CONST(*c),
+                        Some(CMUL(c, input)) => CMUL(*c, index(*input)),
+                        Some(ID(input)) => ID(index(*input)),
+                        None => ID(below.index(*w)),
+                    })
+                    .collect::<Vec<_>>();
+
+                let var_num = padded_var_num(gates.len());
+                gates.resize(1 << var_num, CONST(Scalar::zero()));
+                Layer { gates, var_num }
+            })
+            .collect::<Vec<_>>();
+
+        BuiltCircuit {
+            config: CircuitConfig {
+                depth: layers.len() + 1,
+                layers,
+                input_var_num,
+            },
+            inputs: input_layer.wires[1..]
+                .iter()
+                .map(|w| input_layer.index(*w))
+                .collect(),
+            outputs: self.outputs.iter().map(|w| levels[0].index(*w)).collect(),
+        }
+    }
+}
+
+impl BuiltCircuit {
+    // The input layer from the values of the builder inputs.
+    pub fn assign(&self, inputs: &[Scalar]) -> Vec<Scalar> {
+        assert_eq!(inputs.len(), self.inputs.len(), "Wrong number of inputs");
+        let mut values = vec![Scalar::zero(); 1 << self.config.input_var_num];
+        values[0] = Scalar::one();
+        for (index, value) in self.inputs.iter().zip(inputs) {
+            values[*index] = *value;
+        }
+        values
+    }
+
+    // The values of the builder outputs, from the values of layer 0.
+    pub fn outputs(&self, layer_0: &[Scalar]) -> Vec<Scalar> {
+        self.outputs.iter().map(|i| layer_0[*i]).collect()
+    }
+
+    pub fn evaluate(&self, inputs: &[Scalar]) -> Vec<Scalar> {
+        self.outputs(&self.config.evaluate(&self.assign(inputs)))
+    }
+}
+
+// The distinct wires of a level, in the order of insertion.
+#[derive(Default)]
+struct LevelWires {
+    wires: Vec<Wire>,
+    indices: HashMap<Wire, usize>,
+}
+
+impl LevelWires {
+    fn insert(&mut self, wire: Wire) {
+        if let Entry::Vacant(entry) = self.indices.entry(wire) {
+            entry.insert(self.wires.len());
+            self.wires.push(wire);
+        }
+    }
+
+    fn index(&self, wire: Wire) -> usize {
+        self.indices[&wire]
+    }
+}
+
+// 2^var_num >= len, and a layer has 2 gates at least, as the sumcheck on it needs 2 variables.
+fn padded_var_num(len: usize) -> usize {
+    len.max(2).next_power_of_two().trailing_zeros() as usize
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::gkr::GKR;
+    use ff::Field;
+    use ff::PrimeField;
+    use rand_core::OsRng;
+
+    #[test]
+    fn test_build() {
+        // z = x^2 + x
+        let mut c = CircuitBuilder::new();
+        let x = c.input();
+        let y = c.mul(x, x);
+        let z = c.add(y, x);
+        c.output(z);
+        let circuit = c.build();
+
+        // layer 0: [z, 0], layer 1: [y, x], input: [1, x]
+        let config = &circuit.config;
+        assert_eq!(config.depth, 3);
+        assert_eq!(config.input_var_num, 1);
+        assert_eq!(
+            config.layers[0].gates,
+            vec![ADD(0, 1), CONST(Scalar::zero())]
+        );
+        assert_eq!(config.layers[1].gates, vec![MUL(1, 1), ID(1)]);
+        assert!(config.validate().is_ok());
+        assert_eq!(circuit.inputs, vec![1]);
+        assert_eq!(circuit.outputs, vec![0]);
+
+        assert_eq!(
+            circuit.evaluate(&[Scalar::from_u128(3)]),
+            vec![Scalar::from_u128(12)]
+        );
+    }
+
+    #[test]
+    fn test_skip_layers() {
+        // outputs: (a*b)^4 + x, x, a * 1
+        let mut c = CircuitBuilder::new();
+        let (a, b, x) = (c.input(), c.input(), c.input());
+        let ab = c.mul(a, b);
+        let ab_2 = c.mul(ab, ab);
+        let ab_4 = c.mul(ab_2, ab_2);
+        let y = c.add(ab_4, x);
+        c.output(y);
+        c.output(x);
+        let a = c.mul(a, c.one());
+        c.output(a);
+        let circuit = c.build();
+        assert_eq!(circuit.config.depth, 5);
+        assert!(circuit.config.validate().is_ok());
+
+        let inputs = (0..3).map(|_| Scalar::random(OsRng)).collect::<Vec<_>>();
+        let ab_4 = (inputs[0] * inputs[1]).square().square();
+        assert_eq!(
+            circuit.evaluate(&inputs),
+            vec![ab_4 + inputs[2], inputs[2], inputs[0]]
+        );
+    }
+
+    #[test]
+    fn test_prove() {
+        // x0 * x1 + x2, 3·x2 - 7
+        let mut c = CircuitBuilder::new();
+        let x = [c.input(), c.input(), c.input()];
+        let y = c.mul(x[0], x[1]);
+        let z = c.add(y, x[2]);
+        let u = c.cmul(Scalar::from_u128(3), x[2]);
+        let seven = c.constant(Scalar::from_u128(7));
+        let u = c.sub(u, seven);
+        c.output(z);
+        c.output(u);
+        let circuit = c.build();
+
+        let inputs = [2, 3, 5].map(Scalar::from_u128);
+        let layer_d = circuit.assign(&inputs);
+        let mut gkr = GKR::init(circuit.config.clone());
+        gkr.run_protocol(&layer_d);
+        assert_eq!(
+            circuit.outputs(&gkr.outputs()),
+            vec![Scalar::from_u128(11), Scalar::from_u128(8)]
+        );
+    }
+}
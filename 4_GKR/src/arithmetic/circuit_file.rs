@@ -0,0 +1,526 @@
+// A serialisable description of the layered circuit, so that it can be shared between tools and tests.
+//
+// json: the CircuitConfig itself, eg:
+//      {"layers":[{"gates":[{"mul":[0,1]},{"mul":[2,3]}],"var_num":1}, ...],"input_var_num":2,"depth":3}
+//
+// text: one line per layer, from layer 0(output layer) to layer d-1, and the input layer d.
+//      # Figure 4.12
+//      layer 0: mul 0 1; mul 2 3
+//      layer 1: mul 0 0; mul 1 1; mul 1 2; mul 3 3
+//      input 2
+//  var_num of a layer is log2 of its gates number, and depth = layers number + 1.
+//  The gates are `add l r`, `mul l r`, `sub l r`, `const κ`, `cmul κ x` and `id x`,
+//  where κ is a decimal (maybe negative) or the 0x-prefixed hex of the field element.
+//
+// dot: the Graphviz graph to render, eg: `dot -Tsvg circuit.dot -o circuit.svg`, which is export only.
+//
+// The circuit is validated after loading, as P and V both trust it.
+use crate::arithmetic::layered_circuit::Ops::{ADD, CMUL, CONST, ID, MUL, SUB};
+use crate::arithmetic::layered_circuit::{CircuitConfig, Layer, Ops};
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+impl Display for Ops {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ADD(left, right) => write!(f, "add {} {}", left, right),
+            MUL(left, right) => write!(f, "mul {} {}", left, right),
+            SUB(left, right) => write!(f, "sub {} {}", left, right),
+            CONST(c) => write!(f, "const {}", scalar_str::to_str(c)),
+            CMUL(c, input) => write!(f, "cmul {} {}", scalar_str::to_str(c), input),
+            ID(input) => write!(f, "id {}", input),
+        }
+    }
+}
+
+impl FromStr for Ops {
+    type Err = String;
+
+    // eg: "mul 0 1"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = s.split_whitespace().collect::<Vec<_>>();
+        let index = |token: &str| {
+            token
+                .parse::<usize>()
+                .map_err(|_| format!("Invalid input index `{}` in gate `{}`", token, s))
+        };
+        let constant = |token: &str| {
+            scalar_str::from_str(token)
+                .ok_or_else(|| format!("Invalid constant `{}` in gate `{}`", token, s))
+        };
+        match tokens.as_slice() {
+            ["add", left, right] => Ok(ADD(index(left)?, index(right)?)),
+            ["mul", left, right] => Ok(MUL(index(left)?, index(right)?)),
+            ["sub", left, right] => Ok(SUB(index(left)?, index(right)?)),
+            ["const", c] => Ok(CONST(constant(c)?)),
+            ["cmul", c, input] => Ok(CMUL(constant(c)?, index(input)?)),
+            ["id", input] => Ok(ID(index(input)?)),
+            _ => Err(format!("Invalid gate `{}`", s)),
+        }
+    }
+}
+
+impl CircuitConfig {
+    // Check the circuit is well-formed:
+    //  1. depth = layers number + 1, at least one layer.
+    //  2. each layer has 2^var_num gates.
+    //  3. the inputs of gates in layer i are in layer i+1.
+    //  4. the layers below the output layer have 2 gates at least, as the sumcheck of layer i runs over 2·k_{i+1} variables.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.layers.is_empty() {
+            return Err("The circuit has no layer".to_string());
+        }
+        if self.depth != self.layers.len() + 1 {
+            return Err(format!(
+                "depth {} is inconsistent with {} layers",
+                self.depth,
+                self.layers.len()
+            ));
+        }
+
+        // check var_num before computing 2^var_num.
+        let check_var_num = |name: String, var_num: usize, min: usize| {
+            if var_num < min || var_num >= usize::BITS as usize {
+                Err(format!(
+                    "{} has var_num {}, expect in [{}, {})",
+                    name,
+                    var_num,
+                    min,
+                    usize::BITS
+                ))
+            } else {
+                Ok(())
+            }
+        };
+        check_var_num("The input layer".to_string(), self.input_var_num, 1)?;
+        for (i, layer) in self.layers.iter().enumerate() {
+            check_var_num(format!("layer {}", i), layer.var_num, (i > 0) as usize)?;
+        }
+
+        for (i, layer) in self.layers.iter().enumerate() {
+            if layer.gates.len() != 1 << layer.var_num {
+                return Err(format!(
+                    "layer {} has {} gates, expect 2^{}",
+                    i,
+                    layer.gates.len(),
+                    layer.var_num
+                ));
+            }
+
+            let n_i_plus_1 = match self.layers.get(i + 1) {
+                Some(layer_i_plus_1) => 1 << layer_i_plus_1.var_num,
+                None => 1 << self.input_var_num,
+            };
+            for (j, gate) in layer.gates.iter().enumerate() {
+                if gate.inputs().iter().any(|input| *input >= n_i_plus_1) {
+                    return Err(format!(
+                        "gate {} of layer {} `{}` is out of range, layer {} has {} gates",
+                        j,
+                        i,
+                        gate,
+                        i + 1,
+                        n_i_plus_1
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn from_json(json: &str) -> io::Result<Self> {
+        let config: Self = serde_json::from_str(json)?;
+        config.validate().map_err(invalid_data)?;
+        Ok(config)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("CircuitConfig is always serialisable")
+    }
+
+    pub fn from_text(text: &str) -> io::Result<Self> {
+        let mut layers = vec![];
+        let mut input_var_num = None;
+
+        for (n, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let err = |msg: String| invalid_data(format!("line {}: {}", n + 1, msg));
+            if input_var_num.is_some() {
+                return Err(err("The input layer must be the last one".to_string()));
+            }
+
+            if let Some(var_num) = line.strip_prefix("input ") {
+                let var_num = var_num.trim().parse::<usize>().map_err(|_| {
+                    err(format!(
+                        "Invalid var_num `{}` of input layer",
+                        var_num.trim()
+                    ))
+                })?;
+                input_var_num = Some(var_num);
+            } else if let Some((label, gates)) = line.split_once(':') {
+                let expected = format!("layer {}", layers.len());
+                if label.split_whitespace().collect::<Vec<_>>().join(" ") != expected {
+                    return Err(err(format!("Expect `{}`, got `{}`", expected, label)));
+                }
+
+                let gates = gates
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|gate| !gate.is_empty())
+                    .map(Ops::from_str)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(err)?;
+                if !gates.len().is_power_of_two() {
+                    return Err(err(format!(
+                        "{} has {} gates, which is not a power of 2",
+                        expected,
+                        gates.len()
+                    )));
+                }
+                layers.push(Layer {
+                    var_num: gates.len().trailing_zeros() as usize,
+                    gates,
+                });
+            } else {
+                return Err(err(format!("Invalid line `{}`", line)));
+            }
+        }
+
+        let config = Self {
+            depth: layers.len() + 1,
+            layers,
+            input_var_num: input_var_num
+                .ok_or_else(|| invalid_data("Missing the input layer".to_string()))?,
+        };
+        config.validate().map_err(invalid_data)?;
+        Ok(config)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for (i, layer) in self.layers.iter().enumerate() {
+            let gates = layer
+                .gates
+                .iter()
+                .map(Ops::to_string)
+                .collect::<Vec<_>>()
+                .join("; ");
+            text.push_str(&format!("layer {}: {}\n", i, gates));
+        }
+        text.push_str(&format!("input {}\n", self.input_var_num));
+        text
+    }
+
+    // Each layer is a cluster, from the input layer at the bottom to layer 0 at the top.
+    // The node `l{i}_{a}` is gate a of layer i, labelled by its id and op, and the wires are from its inputs,
+    // labelled by `l` and `r` for the gates with 2 inputs.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph circuit {\n    rankdir=BT;\n    node [shape=box];\n");
+        let d = self.depth - 1;
+        let cluster = |i: usize, label: &str, nodes: Vec<String>| {
+            let nodes = nodes
+                .iter()
+                .enumerate()
+                .map(|(a, node)| format!("        l{}_{} [label=\"{}: {}\"];\n", i, a, a, node))
+                .collect::<String>();
+            format!(
+                "    subgraph cluster_{} {{\n        label=\"{}\";\n{}    }}\n",
+                i, label, nodes
+            )
+        };
+
+        let inputs = vec!["input".to_string(); 1 << self.input_var_num];
+        dot.push_str(&cluster(d, &format!("input layer {}", d), inputs));
+        for (i, layer) in self.layers.iter().enumerate().rev() {
+            let gates = layer.gates.iter().map(Ops::to_string).collect();
+            dot.push_str(&cluster(i, &format!("layer {}", i), gates));
+        }
+
+        for (i, layer) in self.layers.iter().enumerate() {
+            for (a, gate) in layer.gates.iter().enumerate() {
+                let inputs = gate.inputs();
+                for (side, b) in ["l", "r"].iter().zip(&inputs) {
+                    let label = match inputs.len() {
+                        2 => format!(" [label=\"{}\"]", side),
+                        _ => String::new(),
+                    };
+                    dot.push_str(&format!("    l{}_{} -> l{}_{}{};\n", i + 1, b, i, a, label));
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    // The format follows the file extension: json for `.json`, otherwise text.
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let content = fs::read_to_string(&path)?;
+        if is_json(path.as_ref()) {
+            Self::from_json(&content)
+        } else {
+            Self::from_text(&content)
+        }
+    }
+
+    pub fn to_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.validate().map_err(invalid_data)?;
+        let content = if is_json(path.as_ref()) {
+            self.to_json()
+        } else {
+            self.to_text()
+        };
+        fs::write(path, content)
+    }
+}
+
+// Scalar <-> "κ", a decimal (maybe negative) for the small ones, otherwise the 0x-prefixed hex.
+pub(crate) mod scalar_str {
+    use bls12_381::Scalar;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn to_str(scalar: &Scalar) -> String {
+        let small = |s: &Scalar| {
+            let bytes = s.to_bytes();
+            bytes[8..]
+                .iter()
+                .all(|b| *b == 0)
+                .then(|| u64::from_le_bytes(bytes[..8].try_into().unwrap()))
+        };
+        match (small(scalar), small(&-scalar)) {
+            (Some(n), _) => n.to_string(),
+            (None, Some(n)) => format!("-{}", n),
+            // big-endian hex
+            _ => format!("{:?}", scalar),
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Scalar> {
+        if let Some(hex) = s.strip_prefix("0x") {
+            if hex.len() != 64 || !hex.is_ascii() {
+                return None;
+            }
+            let mut bytes = [0u8; 32];
+            for (i, byte) in bytes.iter_mut().rev().enumerate() {
+                *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok()?;
+            }
+            Option::from(Scalar::from_bytes(&bytes))
+        } else if let Some(n) = s.strip_prefix('-') {
+            Some(-Scalar::from(n.parse::<u64>().ok()?))
+        } else {
+            Some(Scalar::from(s.parse::<u64>().ok()?))
+        }
+    }
+
+    pub fn serialize<S: Serializer>(scalar: &Scalar, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&to_str(scalar))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Scalar, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        from_str(&s).ok_or_else(|| D::Error::custom(format!("Invalid scalar: {}", s)))
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(transparent)]
+    struct Str(#[serde(with = "super::scalar_str")] Scalar);
+
+    // Vec<Scalar>, eg: the coeffs of a poly.
+    pub mod vec {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(v: &[Scalar], serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(v.iter().map(to_str))
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Vec<Scalar>, D::Error> {
+            let v = Vec::<Str>::deserialize(deserializer)?;
+            Ok(v.into_iter().map(|s| s.0).collect())
+        }
+    }
+
+    // Vec<Vec<Scalar>>, eg: the round polys of a sumcheck.
+    pub mod vecs {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(
+            v: &[Vec<Scalar>],
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(v.iter().map(|v| v.iter().map(to_str).collect::<Vec<_>>()))
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Vec<Vec<Scalar>>, D::Error> {
+            let v = Vec::<Vec<Str>>::deserialize(deserializer)?;
+            Ok(v.into_iter()
+                .map(|v| v.into_iter().map(|s| s.0).collect())
+                .collect())
+        }
+    }
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().map_or(false, |ext| ext == "json")
+}
+
+pub(crate) fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bls12_381::Scalar;
+    use ff::PrimeField;
+
+    const SIMPLE_CIRCUIT: &str = "
+        # sample from Figure 4.12.
+        layer 0: mul 0 1; mul 2 3
+        layer 1: mul 0 0; mul 1 1; mul 1 2; mul 3 3
+        input 2
+    ";
+
+    #[test]
+    fn test_from_text() {
+        let config = CircuitConfig::from_text(SIMPLE_CIRCUIT).unwrap();
+        assert_eq!(config.depth, 3);
+        assert_eq!(config.layers[0].var_num, 1);
+        assert_eq!(config.layers[1].gates[2], MUL(1, 2));
+
+        let inputs = [1, 2, 1, 4].map(Scalar::from_u128).to_vec();
+        assert_eq!(
+            config.evaluate(&inputs),
+            vec![Scalar::from_u128(4), Scalar::from_u128(32)]
+        );
+    }
+
+    #[test]
+    fn test_file_roundtrip() {
+        let config = CircuitConfig::from_text(SIMPLE_CIRCUIT).unwrap();
+        assert_eq!(CircuitConfig::from_text(&config.to_text()).unwrap(), config);
+        assert_eq!(CircuitConfig::from_json(&config.to_json()).unwrap(), config);
+
+        for ext in ["json", "txt"] {
+            let path =
+                std::env::temp_dir().join(format!("gkr_circuit_{}.{}", std::process::id(), ext));
+            config.to_file(&path).unwrap();
+            let loaded = CircuitConfig::from_file(&path).unwrap();
+            fs::remove_file(&path).unwrap();
+            assert_eq!(loaded, config);
+        }
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let config = CircuitConfig::from_text(SIMPLE_CIRCUIT).unwrap();
+        let dot = config.to_dot();
+        assert!(dot.starts_with("digraph circuit {"));
+        assert!(dot.contains("label=\"input layer 2\""));
+        assert!(dot.contains("l1_2 [label=\"2: mul 1 2\"];"));
+        assert!(dot.contains("l2_2 -> l1_2 [label=\"r\"];"));
+        assert!(dot.contains("l1_3 -> l0_1 [label=\"r\"];"));
+        // one node per gate, and one wire per input.
+        assert_eq!(dot.matches("[label=\"").count(), 4 + 4 + 2 + 4 * 2 + 2 * 2);
+
+        let config = CircuitConfig::from_text("layer 0: const -1; id 1\ninput 1").unwrap();
+        let dot = config.to_dot();
+        assert!(dot.contains("l0_0 [label=\"0: const -1\"];"));
+        assert!(dot.contains("l1_1 -> l0_1;\n"));
+    }
+
+    #[test]
+    fn test_gates() {
+        let text = "layer 0: sub 0 1; const -3; cmul 5 1; id 0\ninput 1\n";
+        let config = CircuitConfig::from_text(text).unwrap();
+        assert_eq!(
+            config.layers[0].gates,
+            vec![
+                SUB(0, 1),
+                CONST(-Scalar::from_u128(3)),
+                CMUL(Scalar::from_u128(5), 1),
+                ID(0)
+            ]
+        );
+        assert_eq!(config.to_text(), text);
+
+        // the big constants are in hex.
+        let mut big = config.clone();
+        big.layers[0].gates[1] = CONST(Scalar::from_u128(u128::MAX));
+        assert!(big.to_text().contains("const 0x"));
+        assert_eq!(CircuitConfig::from_text(&big.to_text()).unwrap(), big);
+        assert_eq!(CircuitConfig::from_json(&big.to_json()).unwrap(), big);
+
+        let inputs = vec![Scalar::from_u128(7), Scalar::from_u128(2)];
+        assert_eq!(
+            config.evaluate(&inputs),
+            vec![
+                Scalar::from_u128(5),
+                -Scalar::from_u128(3),
+                Scalar::from_u128(10),
+                Scalar::from_u128(7)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut config = CircuitConfig::from_text(SIMPLE_CIRCUIT).unwrap();
+        assert!(config.validate().is_ok());
+
+        config.depth = 4;
+        assert!(config.validate().unwrap_err().contains("depth"));
+        config.depth = 3;
+
+        config.layers[0].var_num = 2;
+        assert!(config.validate().unwrap_err().contains("gates"));
+        config.layers[0].var_num = 1;
+
+        // the input layer only has 4 values.
+        config.layers[1].gates[3] = ADD(3, 4);
+        assert!(config.validate().unwrap_err().contains("out of range"));
+
+        // a layer of one gate only could be the output layer.
+        let text = "layer 0: mul 0 1\nlayer 1: mul 0 1\ninput 1";
+        let err = CircuitConfig::from_text(text).unwrap_err().to_string();
+        assert!(err.contains("layer 1 has var_num 0"));
+        assert!(CircuitConfig::from_text("layer 0: id 0\nlayer 1: id 0; id 1\ninput 1").is_ok());
+
+        // and the input layer has 2 values at least.
+        let err = CircuitConfig::from_text("layer 0: id 0\ninput 0").unwrap_err();
+        assert!(err.to_string().contains("The input layer has var_num 0"));
+
+        // a huge var_num is rejected instead of overflowing 2^var_num.
+        config.layers[0].var_num = usize::BITS as usize;
+        assert!(config.validate().unwrap_err().contains("var_num"));
+        config.layers[0].var_num = 1;
+        config.input_var_num = usize::MAX;
+        assert!(config.validate().unwrap_err().contains("var_num"));
+        config.input_var_num = 2;
+
+        // and the bad circuits are rejected when loading.
+        for text in [
+            "layer 0: mul 0 1; mul 2 3; add 0 0\ninput 2",
+            "layer 0: mul 0 1\nlayer 1: mul 0 4; mul 1 1\ninput 2",
+            "layer 1: mul 0 1\ninput 1",
+            "layer 0: mul 0 1; div 2 3\ninput 2",
+            "layer 0: id 0; cmul x 1\ninput 1",
+            "layer 0: id 0; id 2\ninput 1",
+            "layer 0: mul 0 1",
+            "input 1\nlayer 0: mul 0 1",
+        ] {
+            let err = CircuitConfig::from_text(text).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+        let json = config.to_json().replace("\"depth\": 3", "\"depth\": 2");
+        assert!(CircuitConfig::from_json(&json).is_err());
+    }
+}
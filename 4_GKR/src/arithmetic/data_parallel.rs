@@ -0,0 +1,153 @@
+use crate::arithmetic::layered_circuit::Ops::{ADD, CMUL, CONST, ID, MUL, SUB};
+use crate::arithmetic::layered_circuit::{CircuitConfig, Layer, Ops};
+use crate::poly::MPolynomial;
+use bls12_381::Scalar;
+use rayon::prelude::*;
+
+// A data-parallel circuit, with 2^p copies of the same sub-circuit C' on different inputs.
+// Gate a' of copy j at layer i is labelled by (j, a') in {0,1}^(p+k'_i), where j is the high bits,
+// ie: the layer i is the layer i of the copies one by one.
+//
+// As each copy only reads its own layer i+1, the wiring predicates of layer i factor as
+//      add_i((j_a, a'), (j_b, b'), (j_c, c')) = eq(j_a, j_b, j_c)·add'_i(a', b', c')
+// and so do mult_i and sub_i, so that
+//      W_i(j, a') = ∑f'_a'(b',c') over (b',c') in {0,1}^(2·k'_i+1), with W_i+1(j, ·) in f'.
+// Then the MLE of W_i is
+//      W_i(r_j, r') = ∑eq(r_j, j)·f'_{r'}(j,b',c'), (j,b',c') in {0,1}^(p+2·k'_i+1)
+// where only the small sub-circuit predicates are needed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DataParallelCircuit {
+    pub sub: CircuitConfig,
+    pub copies: usize, // 2^p, a power of 2.
+}
+
+impl DataParallelCircuit {
+    pub fn new(sub: CircuitConfig, copies: usize) -> Self {
+        assert!(copies.is_power_of_two(), "copies should be a power of 2");
+        Self { sub, copies }
+    }
+
+    // p, the var_num of the copy index.
+    pub fn copy_var_num(&self) -> usize {
+        self.copies.trailing_zeros() as usize
+    }
+
+    // inputs: the inputs of the copies one by one.
+    pub fn evaluate(&self, inputs: &[Scalar]) -> Vec<Scalar> {
+        self.evaluate_with_trace(inputs).swap_remove(0)
+    }
+
+    // The witness of the whole circuit, where the copies are evaluated in parallel.
+    pub fn witness_to_poly(&self, inputs: &[Scalar]) -> (Vec<MPolynomial>, Vec<Scalar>) {
+        let p = self.copy_var_num();
+        let mut values = self.evaluate_with_trace(inputs);
+        let result = values
+            .par_iter()
+            .enumerate()
+            .map(|(i, evals)| MPolynomial::from_evals(p + self.sub.var_num(i), evals))
+            .collect::<Vec<_>>();
+
+        let outputs = values.swap_remove(0);
+        (result, outputs)
+    }
+
+    // Every gate's value from layer 0 to d-1, with the copies one by one in each layer.
+    pub fn evaluate_with_trace(&self, inputs: &[Scalar]) -> Vec<Vec<Scalar>> {
+        let n = 1 << self.sub.input_var_num;
+        assert_eq!(inputs.len(), self.copies * n);
+
+        let copies = inputs
+            .par_chunks(n)
+            .map(|inputs| self.sub.evaluate_with_trace(inputs))
+            .collect::<Vec<_>>();
+        (0..self.sub.depth)
+            .map(|i| copies.iter().flat_map(|copy| copy[i].clone()).collect())
+            .collect()
+    }
+
+    // The same circuit as a plain layered one, whose gates of copy j read the layer i+1 of copy j.
+    pub fn to_circuit(&self) -> CircuitConfig {
+        let p = self.copy_var_num();
+        let layers = self
+            .sub
+            .layers
+            .iter()
+            .enumerate()
+            .map(|(i, layer)| {
+                // the offset of copy j in layer i+1
+                let n = 1 << self.sub.var_num(i + 1);
+                let gates = (0..self.copies)
+                    .flat_map(|j| layer.gates.iter().map(move |gate| shift(gate, j * n)))
+                    .collect();
+                Layer {
+                    gates,
+                    var_num: p + layer.var_num,
+                }
+            })
+            .collect();
+
+        CircuitConfig {
+            layers,
+            input_var_num: p + self.sub.input_var_num,
+            depth: self.sub.depth,
+        }
+    }
+}
+
+// The gate reading layer i+1 from the offset.
+fn shift(gate: &Ops, offset: usize) -> Ops {
+    match gate {
+        ADD(left, right) => ADD(left + offset, right + offset),
+        MUL(left, right) => MUL(left + offset, right + offset),
+        SUB(left, right) => SUB(left + offset, right + offset),
+        CONST(c) => CONST(*c),
+        CMUL(c, input) => CMUL(*c, input + offset),
+        ID(input) => ID(input + offset),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ff::Field;
+    use rand_core::OsRng;
+
+    #[test]
+    fn test_data_parallel_circuit() {
+        let sub = CircuitConfig::random(&[1, 2, 2], &mut OsRng);
+        let circuit = DataParallelCircuit::new(sub.clone(), 4);
+        let inputs = (0..16).map(|_| Scalar::random(OsRng)).collect::<Vec<_>>();
+
+        // the outputs are the ones of the copies
+        let outputs = circuit.evaluate(&inputs);
+        let expected = inputs
+            .chunks(4)
+            .flat_map(|inputs| sub.evaluate(&inputs.to_vec()))
+            .collect::<Vec<_>>();
+        assert_eq!(outputs, expected);
+        assert_eq!(circuit.to_circuit().evaluate(&inputs), expected);
+
+        // add_i, mult_i and sub_i factor as eq(j_a, j_b, j_c)·add'_i(a', b', c')
+        let flat = circuit.to_circuit().ops_to_wiring();
+        for (i, wiring) in sub.ops_to_wiring().iter().enumerate() {
+            let (k_a, k_b) = wiring.var_nums;
+            let random = |n: usize| (0..n).map(|_| Scalar::random(OsRng)).collect::<Vec<_>>();
+            let (j_a, j_b, j_c) = (random(2), random(2), random(2));
+            let (a, b, c) = (random(k_a), random(k_b), random(k_b));
+
+            let eq: Scalar = (0..2)
+                .map(|t| {
+                    j_a[t] * j_b[t] * j_c[t]
+                        + (Scalar::one() - j_a[t])
+                            * (Scalar::one() - j_b[t])
+                            * (Scalar::one() - j_c[t])
+                })
+                .product();
+            let expected = wiring.evaluate(&[a.clone(), b.clone(), c.clone()].concat());
+            let actual = flat[i].evaluate(&[j_a, a, j_b, b, j_c, c].concat());
+            for t in 0..3 {
+                assert_eq!(actual[t], eq * expected[t]);
+            }
+        }
+    }
+}
@@ -1,27 +1,59 @@
-use crate::arithmetic::layered_circuit::Ops::{ADD, MUL};
+use crate::arithmetic::circuit_file::scalar_str;
+use crate::arithmetic::layered_circuit::Ops::{ADD, CMUL, CONST, ID, MUL, SUB};
 use crate::poly::MPolynomial;
+use crate::utils::eq_table;
 use bls12_381::Scalar;
 use ff::Field;
+use rand_core::RngCore;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::env::var;
 use std::net::Shutdown::Read;
 
-// Operators. for now, they are add and mul.
-// Left and right  input index from layer i+1.
-#[derive(Clone, Debug)]
+// Operators. The input indexes are from layer i+1.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Ops {
-    ADD(usize, usize),
-    MUL(usize, usize),
+    ADD(usize, usize),                                 // left + right
+    MUL(usize, usize),                                 // left * right
+    SUB(usize, usize),                                 // left - right
+    CONST(#[serde(with = "scalar_str")] Scalar),       // a constant, without input
+    CMUL(#[serde(with = "scalar_str")] Scalar, usize), // constant * input
+    ID(usize),                                         // copy the input from layer i+1
 }
 
-#[derive(Clone)]
+impl Ops {
+    // The output of the gate, with the values of layer i+1.
+    pub fn evaluate(&self, layer_i_plus_1: &[Scalar]) -> Scalar {
+        match self {
+            ADD(left, right) => layer_i_plus_1[*left] + layer_i_plus_1[*right],
+            MUL(left, right) => layer_i_plus_1[*left] * layer_i_plus_1[*right],
+            SUB(left, right) => layer_i_plus_1[*left] - layer_i_plus_1[*right],
+            CONST(c) => *c,
+            CMUL(c, input) => c * layer_i_plus_1[*input],
+            ID(input) => layer_i_plus_1[*input],
+        }
+    }
+
+    // The input indexes in layer i+1.
+    pub fn inputs(&self) -> Vec<usize> {
+        match self {
+            ADD(left, right) | MUL(left, right) | SUB(left, right) => vec![*left, *right],
+            CONST(_) => vec![],
+            CMUL(_, input) | ID(input) => vec![*input],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Layer {
     pub gates: Vec<Ops>,
     pub var_num: usize, // 2^var_num = gates.len()
 }
 
 // Configure Circuit Constraints. We assume circuit is layered one, whose gates have fan-in-2 and fan-out-1.
-#[derive(Clone, Default)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct CircuitConfig {
     pub layers: Vec<Layer>,   // from layer 0 to d-1.
     pub input_var_num: usize, // input_layer_len = 2^input_var_num. input is layer-d
@@ -48,11 +80,7 @@ impl CircuitConfig {
 
             // iter each gate in layer_i
             for gate in gates {
-                let gate_output = match gate {
-                    ADD(left, right) => layer_i_plus_1[*left] + layer_i_plus_1[*right],
-                    MUL(left, right) => layer_i_plus_1[*left] * layer_i_plus_1[*right],
-                };
-                layer_i_outputs.push(gate_output);
+                layer_i_outputs.push(gate.evaluate(&layer_i_plus_1));
             }
             assert_eq!(layer_i_outputs.len(), gates_num);
 
@@ -64,52 +92,96 @@ impl CircuitConfig {
         layer_i_plus_1.clone()
     }
 
+    // A layered circuit with random gates and random wiring, eg: for tests and benches.
+    // var_nums: from layer 0 (output layer) to the input layer.
+    pub fn random(var_nums: &[usize], rng: &mut impl RngCore) -> Self {
+        let depth = var_nums.len();
+        assert!(depth >= 2, "At least one layer besides the input");
+        let layers = (0..depth - 1)
+            .map(|i| {
+                let n_i_plus_1 = 1 << var_nums[i + 1];
+                let gates = (0..1 << var_nums[i])
+                    .map(|_| {
+                        let left = rng.next_u32() as usize % n_i_plus_1;
+                        let right = rng.next_u32() as usize % n_i_plus_1;
+                        let c = Scalar::random(&mut *rng);
+                        match rng.next_u32() % 6 {
+                            0 => ADD(left, right),
+                            1 => MUL(left, right),
+                            2 => SUB(left, right),
+                            3 => CONST(c),
+                            4 => CMUL(c, left),
+                            _ => ID(left),
+                        }
+                    })
+                    .collect();
+                Layer {
+                    gates,
+                    var_num: var_nums[i],
+                }
+            })
+            .collect();
+
+        Self {
+            layers,
+            input_var_num: var_nums[depth - 1],
+            depth,
+        }
+    }
+
     pub fn witness_to_poly(&self, inputs: &Vec<Scalar>) -> (Vec<MPolynomial>, Vec<Scalar>) {
+        let mut values = self.evaluate_with_trace(inputs);
+
+        // The MLE of each layer only depends on its own values, so the layers are encoded in parallel.
+        let result = values
+            .par_iter()
+            .enumerate()
+            .map(|(i, evals)| MPolynomial::from_evals(self.var_num(i), evals))
+            .collect::<Vec<_>>();
+
+        // after iter, will calculate output.
+        let outputs = values.swap_remove(0);
+        (result, outputs)
+    }
+
+    // Every gate's value, from layer 0 (output layer) to layer d-1 (input layer),
+    // eg: to compare with the witness of P, and find the first layer which goes wrong.
+    pub fn evaluate_with_trace(&self, inputs: &[Scalar]) -> Vec<Vec<Scalar>> {
         assert_eq!(self.layers.len(), self.depth - 1);
         let max_n = 1 << self.input_var_num;
         assert_eq!(inputs.len(), max_n);
 
-        // At start, the mpoly in result start from layer d to 0.
+        // At start, the values in result start from layer d to 0.
         // However, we'll inverse it to adopt from layer 0 to d.
-        let mut result = vec![];
+        let mut values = vec![inputs.to_vec()];
 
-        // start with layer d(input layer)
-        let input_mpoly = MPolynomial::lagrange(self.input_var_num, inputs);
-        result.push(input_mpoly);
-
-        let mut layer_i_plus_1 = inputs.clone();
-
-        // from layer d-1 to layer 0(output layer).
+        // from layer d-1 to layer 0(output layer), the gates in a layer are evaluated in parallel.
         for i in (0..(self.depth - 1)).rev() {
             let layer_i = self.layers.get(i).expect("Can't capture layer_i");
             let gates_num = 1 << layer_i.var_num;
             let gates = &layer_i.gates;
             assert_eq!(gates.len(), gates_num);
 
-            let mut layer_i_outputs = vec![];
-
-            // iter each gate in layer_i
-            for gate in gates {
-                let gate_output = match gate {
-                    ADD(left, right) => layer_i_plus_1[*left] + layer_i_plus_1[*right],
-                    MUL(left, right) => layer_i_plus_1[*left] * layer_i_plus_1[*right],
-                };
-                layer_i_outputs.push(gate_output);
-            }
+            let layer_i_plus_1 = values.last().unwrap();
+            let layer_i_outputs = gates
+                .par_iter()
+                .map(|gate| gate.evaluate(layer_i_plus_1))
+                .collect::<Vec<_>>();
             assert_eq!(layer_i_outputs.len(), gates_num);
 
-            let layer_i_mpoly = MPolynomial::lagrange(layer_i.var_num, &layer_i_outputs);
-            result.push(layer_i_mpoly);
-
-            // prepare for next iter.
-            // Last round -  layer_i_plus_1=output.
-            layer_i_plus_1 = layer_i_outputs.clone();
+            values.push(layer_i_outputs);
         }
+        assert_eq!(values.len(), self.depth);
+        values.reverse();
+        values
+    }
 
-        assert_eq!(result.len(), self.depth);
-        // after iter, will calculate output.
-        result.reverse();
-        (result, layer_i_plus_1.clone())
+    // The var_num of layer i, including the input layer d-1.
+    pub fn var_num(&self, i: usize) -> usize {
+        match i == self.depth - 1 {
+            true => self.input_var_num,
+            false => self.layers[i].var_num,
+        }
     }
 
     //  A layered arithmetic circuit C with S gates, depth d, and fan-in two (C may have more than one output gate).
@@ -119,59 +191,116 @@ impl CircuitConfig {
     //  As wiring predicate(addi, multi) depend only on the circuit C and not on the input x to C, so that we use that here.
     //
     //
-    // Obtain the addi and multi mpoly from the circuit.
+    // Obtain the wiring predicates from the circuit, with one entry per gate.
     // eg:  mult0 is the function defined over domain {0,1}×{0,1}2 ×{0,1}2 as follows. mult0 evaluates
     //      to 1 on the following two inputs: (0,(0,0),(0,1)) and (1,(1,0),(1,1)). On all other inputs,
     //      mult0 evaluates to zero.
-    pub(crate) fn ops_to_mpoly(&self) -> Vec<(MPolynomial, MPolynomial)> {
-        // result ares vector of (addi_mpoly, multi_mpoly).
-        let mut result = Vec::with_capacity(self.depth - 1);
-
+    pub fn ops_to_wiring(&self) -> Vec<Wiring> {
+        // The wiring predicates of the layers are independent, so they're built in parallel.
         // from layer 0(output layer) to layer d-1.
-        for i in 0..self.depth - 1 {
-            let layer_i = self.layers.get(i).expect("Can't capture layer_i");
-            let var_num_i = layer_i.var_num;
-            let var_num_i_plus_1 = if i == self.depth - 2 {
-                // layer d-1's input is input layer.
-                self.input_var_num
-            } else {
-                let layer_i_plus_1 = self
-                    .layers
-                    .get(i + 1)
-                    .expect("Can't capture layer_i_plus_1");
-                layer_i_plus_1.var_num
-            };
-            let n_i_plus_1 = 1 << var_num_i_plus_1;
-
-            let mpoly_var_num = var_num_i + 2 * var_num_i_plus_1;
-            let mpoly_size = 1 << mpoly_var_num;
-            let mut addi_mpoly_eval = vec![Scalar::zero(); mpoly_size];
-            let mut multi_mpoly_eval = vec![Scalar::zero(); mpoly_size];
-
-            // iter each gate in layer_i
-            for (j, gate) in layer_i.gates.iter().enumerate() {
-                match gate {
-                    ADD(left, right) => {
-                        assert!(n_i_plus_1 > *left && n_i_plus_1 > *right);
-                        // turn binary index (0,(0,1),(1,1)) into vec array.
-                        let mpoly_index =
-                            (j << 2 * var_num_i_plus_1) + (left << var_num_i_plus_1) + right;
-                        addi_mpoly_eval[mpoly_index] = Scalar::one();
-                    }
-                    MUL(left, right) => {
-                        assert!(n_i_plus_1 > *left && n_i_plus_1 > *right);
-                        let mpoly_index =
-                            (j << 2 * var_num_i_plus_1) + (left << var_num_i_plus_1) + right;
-                        multi_mpoly_eval[mpoly_index] = Scalar::one();
-                    }
+        (0..self.depth - 1)
+            .into_par_iter()
+            .map(|i| {
+                let layer_i = self.layers.get(i).expect("Can't capture layer_i");
+                let var_num_i = layer_i.var_num;
+                let var_num_i_plus_1 = if i == self.depth - 2 {
+                    // layer d-1's input is input layer.
+                    self.input_var_num
+                } else {
+                    let layer_i_plus_1 = self
+                        .layers
+                        .get(i + 1)
+                        .expect("Can't capture layer_i_plus_1");
+                    layer_i_plus_1.var_num
                 };
-            }
+                let n_i_plus_1 = 1 << var_num_i_plus_1;
+
+                let mut wiring = Wiring::new(var_num_i, var_num_i_plus_1);
+                // iter each gate in layer_i
+                for (a, gate) in layer_i.gates.iter().enumerate() {
+                    assert!(gate.inputs().iter().all(|input| n_i_plus_1 > *input));
+                    let one = Scalar::one();
+                    let (predicate, entry) = match gate {
+                        ADD(b, c) => (&mut wiring.add, (a, *b, *c, one)),
+                        MUL(b, c) => (&mut wiring.mult, (a, *b, *c, one)),
+                        SUB(b, c) => (&mut wiring.sub, (a, *b, *c, one)),
+                        CONST(k) => (&mut wiring.cnst, (a, 0, 0, *k)),
+                        CMUL(k, b) => (&mut wiring.cmul, (a, *b, 0, *k)),
+                        ID(b) => (&mut wiring.id, (a, *b, 0, one)),
+                    };
+                    predicate.entries.push(entry);
+                }
+                wiring
+            })
+            .collect()
+    }
+}
+
+// A wiring predicate over (a,b,c) in {0,1}^(k_i+2·k_i+1), with its non-zero points and the values there.
+// Each gate of layer i gives one entry at most, so it takes O(S_i) instead of 2^(k_i+2·k_i+1) space.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SparsePredicate {
+    pub entries: Vec<(usize, usize, usize, Scalar)>, // (a, b, c, value)
+}
 
-            let addi_mpoly = MPolynomial::lagrange(mpoly_var_num, &addi_mpoly_eval);
-            let multi_mpoly = MPolynomial::lagrange(mpoly_var_num, &multi_mpoly_eval);
-            result.push((addi_mpoly, multi_mpoly));
+impl SparsePredicate {
+    // The MLE at (r_a, r_b, r_c) is ∑value·χ_a(r_a)·χ_b(r_b)·χ_c(r_c) over the entries,
+    // with the eq tables of r_a, r_b and r_c.
+    pub fn evaluate(&self, eq_a: &[Scalar], eq_b: &[Scalar], eq_c: &[Scalar]) -> Scalar {
+        self.entries
+            .iter()
+            .map(|(a, b, c, value)| value * eq_a[*a] * eq_b[*b] * eq_c[*c])
+            .sum()
+    }
+}
+
+// The wiring predicates of layer i:
+//      add_i, mult_i, sub_i: 1 iff gate a is the op on (b,c).
+//      cnst_i: κ iff gate a is the constant κ, and (b,c) = (0,0).
+//      cmul_i: κ iff gate a is κ times b, and c = 0.
+//      id_i: 1 iff gate a copies b, and c = 0.
+// so that W_i(a) = ∑f_a(b,c), (b,c) in {0,1}^(2·k_i+1), where
+//      f_a(b,c) = add_i·(W_i+1(b) + W_i+1(c)) + mult_i·W_i+1(b)·W_i+1(c) + sub_i·(W_i+1(b) - W_i+1(c))
+//          + cnst_i + (cmul_i + id_i)·W_i+1(b)
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Wiring {
+    pub var_nums: (usize, usize), // (k_i, k_i+1)
+    pub add: SparsePredicate,
+    pub mult: SparsePredicate,
+    pub sub: SparsePredicate,
+    pub cnst: SparsePredicate,
+    pub cmul: SparsePredicate,
+    pub id: SparsePredicate,
+}
+
+impl Wiring {
+    pub fn new(var_num_i: usize, var_num_i_plus_1: usize) -> Self {
+        Self {
+            var_nums: (var_num_i, var_num_i_plus_1),
+            ..Default::default()
         }
-        result
+    }
+
+    // k_i + 2·k_i+1
+    pub fn var_num(&self) -> usize {
+        self.var_nums.0 + 2 * self.var_nums.1
+    }
+
+    pub fn predicates(&self) -> [&SparsePredicate; 6] {
+        [
+            &self.add, &self.mult, &self.sub, &self.cnst, &self.cmul, &self.id,
+        ]
+    }
+
+    // The MLEs of add_i, mult_i, sub_i, cnst_i, cmul_i, id_i at (r_a, r_b, r_c),
+    // in O(S_i + 2^k_i + 2^k_i+1) time.
+    pub fn evaluate(&self, point: &[Scalar]) -> [Scalar; 6] {
+        assert_eq!(point.len(), self.var_num());
+        let (r_a, r_bc) = point.split_at(self.var_nums.0);
+        let (r_b, r_c) = r_bc.split_at(self.var_nums.1);
+        let (eq_a, eq_b, eq_c) = (eq_table(r_a), eq_table(r_b), eq_table(r_c));
+        self.predicates()
+            .map(|predicate| predicate.evaluate(&eq_a, &eq_b, &eq_c))
     }
 }
 
@@ -183,8 +312,9 @@ impl CircuitConfig {
 mod test {
     use super::*;
     use crate::arithmetic::layered_circuit::Ops::MUL;
-    use crate::utils::convert_from_binary;
+    use crate::utils::{convert_from_binary, convert_to_binary, thread_pool};
     use ff::PrimeField;
+    use rand_core::OsRng;
 
     // sample from Figure 4.12.
     fn simple_circuit() -> CircuitConfig {
@@ -225,6 +355,16 @@ mod test {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn test_evaluate_with_trace() {
+        let inputs = [1, 2, 1, 4].map(Scalar::from_u128).to_vec();
+        let trace = simple_circuit().evaluate_with_trace(&inputs);
+
+        let expected = [vec![4, 32], vec![1, 4, 2, 16], vec![1, 2, 1, 4]]
+            .map(|layer| layer.into_iter().map(Scalar::from_u128).collect::<Vec<_>>());
+        assert_eq!(trace, expected.to_vec());
+    }
+
     #[test]
     fn test_witness_to_poly() {
         let inputs = vec![
@@ -258,9 +398,36 @@ mod test {
     }
 
     #[test]
-    fn test_op_to_mpoly() {
+    fn test_thread_pool() {
+        let inputs = vec![
+            Scalar::one(),
+            Scalar::from_u128(2),
+            Scalar::one(),
+            Scalar::from_u128(4),
+        ];
+        let circuit = simple_circuit();
+
+        // the same witness and wiring predicates with 1 and 4 threads.
+        let (serial, parallel) = (thread_pool(1), thread_pool(4));
+        let (witness, outputs) = serial.install(|| circuit.witness_to_poly(&inputs));
+        assert_eq!(
+            (witness.clone(), outputs.clone()),
+            parallel.install(|| circuit.witness_to_poly(&inputs))
+        );
+        assert_eq!(
+            serial.install(|| circuit.ops_to_wiring()),
+            parallel.install(|| circuit.ops_to_wiring())
+        );
+
+        // and the same as the plain evaluation.
+        assert_eq!(outputs, circuit.evaluate(&inputs));
+        assert_eq!(witness[2], MPolynomial::lagrange(2, &inputs));
+    }
+
+    #[test]
+    fn test_op_to_wiring() {
         let circuit = simple_circuit();
-        let actual = circuit.ops_to_mpoly();
+        let actual = circuit.ops_to_wiring();
 
         // layer 1, add and mult mpoly
         let var_num_1 = 2 + 2 * 2;
@@ -294,9 +461,77 @@ mod test {
             MPolynomial::lagrange(var_num_0, &mult_0_evals),
         );
 
-        let expected = vec![mpoly_0, mpoly_1];
+        // one entry per gate.
+        assert_eq!(actual.len(), 2);
+        assert_eq!(actual[0].var_nums, (1, 2));
+        assert_eq!(actual[0].mult.entries.len(), 2);
+        assert_eq!(actual[1].mult.entries.len(), 4);
+        assert_eq!(actual[1].mult.entries[2], (2, 1, 2, Scalar::one()));
+
+        // the sparse MLEs are the dense ones, and there're no other gates.
+        for (wiring, (add, mult)) in actual.iter().zip([mpoly_0, mpoly_1]) {
+            let point = (0..wiring.var_num())
+                .map(|_| Scalar::random(OsRng))
+                .collect::<Vec<_>>();
+            let [add_r, mult_r, others @ ..] = wiring.evaluate(&point);
+            assert_eq!(add_r, add.evaluate_at(&point));
+            assert_eq!(mult_r, mult.evaluate_at(&point));
+            assert!(others.iter().all(|v| *v == Scalar::zero()));
+        }
+    }
+
+    #[test]
+    fn test_wiring() {
+        let inputs = [3, 5, 7, 11].map(Scalar::from_u128).to_vec();
+        let circuit = CircuitConfig {
+            layers: vec![
+                Layer {
+                    gates: vec![MUL(0, 1), SUB(2, 3)],
+                    var_num: 1,
+                },
+                Layer {
+                    gates: vec![
+                        SUB(1, 0),
+                        CONST(Scalar::from_u128(9)),
+                        CMUL(Scalar::from_u128(2), 3),
+                        ID(2),
+                    ],
+                    var_num: 2,
+                },
+            ],
+            input_var_num: 2,
+            depth: 3,
+        };
+        // layer 1: [2, 9, 22, 7], layer 0: [18, 15]
+        assert_eq!(
+            circuit.evaluate(&inputs),
+            vec![Scalar::from_u128(18), Scalar::from_u128(15)]
+        );
 
-        assert_eq!(actual, expected);
+        // W_i(a) = ∑f_a(b,c) on the hypercube.
+        let (witness, _) = circuit.witness_to_poly(&inputs);
+        for (i, wiring) in circuit.ops_to_wiring().iter().enumerate() {
+            let (w_i, w_i_plus_1) = (witness[i].evals(), witness[i + 1].evals());
+            let k = witness[i + 1].var_num;
+            for (a, w_i_a) in w_i.iter().enumerate() {
+                let sum: Scalar = (0..1 << (2 * k))
+                    .map(|bc| {
+                        let point = convert_to_binary(&wiring.var_num(), (a << (2 * k)) + bc)
+                            .iter()
+                            .map(|bit| Scalar::from(*bit as u64))
+                            .collect::<Vec<_>>();
+                        let [add, mult, sub, cnst, cmul, id] = wiring.evaluate(&point);
+                        let (w_b, w_c) = (w_i_plus_1[bc >> k], w_i_plus_1[bc & ((1 << k) - 1)]);
+                        add * (w_b + w_c)
+                            + mult * w_b * w_c
+                            + sub * (w_b - w_c)
+                            + cnst
+                            + (cmul + id) * w_b
+                    })
+                    .sum();
+                assert_eq!(sum, *w_i_a);
+            }
+        }
     }
 
     #[test]
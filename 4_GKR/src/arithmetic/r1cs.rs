@@ -0,0 +1,370 @@
+// Rank-1 constraint system(R1CS), ie: the sparse matrices A, B, C over F, and z = (1, x, w) such that
+//      (A·z) ∘ (B·z) = C·z
+// where x is the public instance, w is the private witness, and ∘ is the entry-wise product.
+// So that each constraint i is <A_i, z>·<B_i, z> = <C_i, z>, with a product of two linear combinations.
+//
+// A layered circuit is turned into R1CS, with a constraint per MUL gate:
+//      ADD, SUB, CONST, CMUL and ID are linear, so their wires are linear combinations of the vars,
+//      eg: an ADD chain is folded into one linear combination, without any constraint.
+//      MUL gets a new var v for its value, and the constraint LC_l·LC_r = v.
+//      The outputs are in x, and a non-MUL output y gets the constraint LC·1 = y.
+// So that the constraints number is the MUL gates number, plus the non-MUL outputs number.
+use crate::arithmetic::circuit_file::{invalid_data, scalar_str};
+use crate::arithmetic::layered_circuit::CircuitConfig;
+use crate::arithmetic::layered_circuit::Ops::{ADD, CMUL, CONST, ID, MUL, SUB};
+use bls12_381::Scalar;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io;
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SparseMatrix {
+    pub num_rows: usize,
+    pub num_cols: usize,
+    #[serde(with = "entries")]
+    pub entries: Vec<(usize, usize, Scalar)>, // (row, col, value), with the non-zero values only.
+}
+
+impl SparseMatrix {
+    pub fn new(num_rows: usize, num_cols: usize) -> Self {
+        Self {
+            num_rows,
+            num_cols,
+            entries: vec![],
+        }
+    }
+
+    // M·z
+    pub fn mul_vec(&self, z: &[Scalar]) -> Vec<Scalar> {
+        assert_eq!(z.len(), self.num_cols);
+        let mut result = vec![Scalar::zero(); self.num_rows];
+        for (row, col, value) in self.entries.iter() {
+            result[*row] += value * z[*col];
+        }
+        result
+    }
+
+    // Append a row of the linear combination.
+    fn push_row(&mut self, lc: &LinearCombination) {
+        let row = self.num_rows;
+        self.entries
+            .extend(lc.iter().map(|(col, value)| (row, *col, *value)));
+        self.num_rows += 1;
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct R1CS {
+    pub a: SparseMatrix,
+    pub b: SparseMatrix,
+    pub c: SparseMatrix,
+    pub num_instance: usize, // |x|
+    pub num_witness: usize,  // |w|
+}
+
+impl R1CS {
+    pub fn num_constraints(&self) -> usize {
+        self.a.num_rows
+    }
+
+    // |z| = 1 + |x| + |w|
+    pub fn num_vars(&self) -> usize {
+        1 + self.num_instance + self.num_witness
+    }
+
+    // z = (1, x, w)
+    pub fn z(&self, x: &[Scalar], w: &[Scalar]) -> Vec<Scalar> {
+        assert_eq!(x.len(), self.num_instance);
+        assert_eq!(w.len(), self.num_witness);
+        [&[Scalar::one()], x, w].concat()
+    }
+
+    // (A·z) ∘ (B·z) = C·z, with z = (1, x, w).
+    pub fn is_satisfied(&self, z: &[Scalar]) -> bool {
+        if z.len() != self.num_vars() || z[0] != Scalar::one() {
+            return false;
+        }
+        let (az, bz, cz) = (self.a.mul_vec(z), self.b.mul_vec(z), self.c.mul_vec(z));
+        (0..self.num_constraints()).all(|i| az[i] * bz[i] == cz[i])
+    }
+
+    // Check the shapes of A, B, C, and that the entries are in them.
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, m) in [("A", &self.a), ("B", &self.b), ("C", &self.c)] {
+            if m.num_rows != self.num_constraints() {
+                return Err(format!(
+                    "{}: {} rows, expected {}",
+                    name,
+                    m.num_rows,
+                    self.num_constraints()
+                ));
+            }
+            if m.num_cols != self.num_vars() {
+                return Err(format!(
+                    "{}: {} cols, expected {}",
+                    name,
+                    m.num_cols,
+                    self.num_vars()
+                ));
+            }
+            if let Some((row, col, _)) =
+                (m.entries.iter()).find(|(row, col, _)| *row >= m.num_rows || *col >= m.num_cols)
+            {
+                return Err(format!("{}: entry ({}, {}) out of range", name, row, col));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn from_json(json: &str) -> io::Result<Self> {
+        let r1cs: Self = serde_json::from_str(json)?;
+        r1cs.validate().map_err(invalid_data)?;
+        Ok(r1cs)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("R1CS is always serialisable")
+    }
+}
+
+// var -> coeff, without zeros. The var 0 is the constant 1.
+type LinearCombination = BTreeMap<usize, Scalar>;
+
+fn var(i: usize) -> LinearCombination {
+    LinearCombination::from([(i, Scalar::one())])
+}
+
+// l + c·r
+fn add_scaled(l: &LinearCombination, c: Scalar, r: &LinearCombination) -> LinearCombination {
+    let mut result = l.clone();
+    for (i, value) in r.iter() {
+        let sum = result.get(i).copied().unwrap_or_default() + c * value;
+        match sum == Scalar::zero() {
+            true => result.remove(i),
+            false => result.insert(*i, sum),
+        };
+    }
+    result
+}
+
+// The R1CS of a layered circuit, with where the circuit values go in (x, w).
+//      x = (inputs, if they're public) ++ outputs
+//      w = (inputs, if they're private) ++ the values of the MUL gates in layer 1 to d-2
+#[derive(Clone, Debug)]
+pub struct CircuitR1CS {
+    pub r1cs: R1CS,
+    pub public_inputs: bool,
+    circuit: CircuitConfig,
+    mul_gates: Vec<(usize, usize)>, // (layer, gate) of the MUL gates in w, one by one.
+}
+
+impl CircuitR1CS {
+    pub fn new(circuit: &CircuitConfig, public_inputs: bool) -> Self {
+        let d = circuit.depth;
+        let (n_in, n_out) = (1 << circuit.input_var_num, 1 << circuit.var_num(0));
+        let (input_offset, output_offset) = match public_inputs {
+            true => (1, 1 + n_in),
+            false => (1 + n_out, 1),
+        };
+        // the vars of the MUL gates start after x and the inputs.
+        let mul_offset = 1 + n_in + n_out;
+        let mul_gates = (1..d - 1)
+            .rev()
+            .flat_map(|i| {
+                let gates = circuit.layers[i].gates.iter().enumerate();
+                gates.filter_map(move |(a, gate)| matches!(gate, MUL(..)).then_some((i, a)))
+            })
+            .collect::<Vec<_>>();
+        let num_vars = mul_offset + mul_gates.len();
+        let mut a = SparseMatrix::new(0, num_vars);
+        let mut b = SparseMatrix::new(0, num_vars);
+        let mut c = SparseMatrix::new(0, num_vars);
+
+        // the wires of layer i+1 as linear combinations, from the inputs to the outputs.
+        let mut wires = (0..n_in).map(|j| var(input_offset + j)).collect::<Vec<_>>();
+        let mut next_mul = mul_offset;
+        for i in (0..d - 1).rev() {
+            wires = (circuit.layers[i].gates.iter().enumerate())
+                .map(|(a_i, gate)| {
+                    let lc = match gate {
+                        ADD(l, r) => add_scaled(&wires[*l], Scalar::one(), &wires[*r]),
+                        SUB(l, r) => add_scaled(&wires[*l], -Scalar::one(), &wires[*r]),
+                        CONST(k) => add_scaled(&LinearCombination::new(), *k, &var(0)),
+                        CMUL(k, x) => add_scaled(&LinearCombination::new(), *k, &wires[*x]),
+                        ID(x) => wires[*x].clone(),
+                        MUL(l, r) => {
+                            // LC_l·LC_r = v, where v is the output at layer 0.
+                            let v = match i {
+                                0 => output_offset + a_i,
+                                _ => {
+                                    next_mul += 1;
+                                    next_mul - 1
+                                }
+                            };
+                            a.push_row(&wires[*l]);
+                            b.push_row(&wires[*r]);
+                            c.push_row(&var(v));
+                            return var(v);
+                        }
+                    };
+                    // LC·1 = y, for a non-MUL output y.
+                    if i == 0 {
+                        a.push_row(&lc);
+                        b.push_row(&var(0));
+                        c.push_row(&var(output_offset + a_i));
+                    }
+                    lc
+                })
+                .collect();
+        }
+        assert_eq!(next_mul, num_vars);
+
+        let num_instance = n_out + if public_inputs { n_in } else { 0 };
+        let r1cs = R1CS {
+            a,
+            b,
+            c,
+            num_instance,
+            num_witness: num_vars - 1 - num_instance,
+        };
+        Self {
+            r1cs,
+            public_inputs,
+            circuit: circuit.clone(),
+            mul_gates,
+        }
+    }
+
+    // (x, w) from the inputs, by evaluating the circuit.
+    pub fn assign(&self, inputs: &[Scalar]) -> (Vec<Scalar>, Vec<Scalar>) {
+        let values = self.circuit.evaluate_with_trace(inputs);
+        let products = self.mul_gates.iter().map(|(i, a)| values[*i][*a]);
+        let outputs = values[0].clone();
+        match self.public_inputs {
+            true => ([inputs, &outputs].concat(), products.collect()),
+            false => (outputs, inputs.iter().copied().chain(products).collect()),
+        }
+    }
+}
+
+// Vec<(row, col, value)>, with the value as a scalar string.
+mod entries {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct Entry(usize, usize, #[serde(with = "scalar_str")] Scalar);
+
+    pub fn serialize<S: Serializer>(
+        entries: &[(usize, usize, Scalar)],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(entries.iter().map(|(row, col, v)| Entry(*row, *col, *v)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<(usize, usize, Scalar)>, D::Error> {
+        let entries = Vec::<Entry>::deserialize(deserializer)?;
+        Ok(entries.into_iter().map(|e| (e.0, e.1, e.2)).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ff::{Field, PrimeField};
+    use rand_core::OsRng;
+
+    const SIMPLE_CIRCUIT: &str = "
+        # sample from Figure 4.12.
+        layer 0: mul 0 1; mul 2 3
+        layer 1: mul 0 0; mul 1 1; mul 1 2; mul 3 3
+        input 2
+    ";
+
+    fn random_inputs(var_num: usize) -> Vec<Scalar> {
+        (0..1 << var_num).map(|_| Scalar::random(OsRng)).collect()
+    }
+
+    #[test]
+    fn test_simple_circuit_r1cs() {
+        let config = CircuitConfig::from_text(SIMPLE_CIRCUIT).unwrap();
+        let inputs = [1, 2, 1, 4].map(Scalar::from_u128).to_vec();
+
+        for public_inputs in [true, false] {
+            let circuit = CircuitR1CS::new(&config, public_inputs);
+            let r1cs = &circuit.r1cs;
+            // a constraint per MUL gate
+            assert_eq!(r1cs.num_constraints(), 6);
+            assert_eq!(r1cs.num_vars(), 1 + 4 + 2 + 4);
+
+            let (x, w) = circuit.assign(&inputs);
+            let outputs = [4, 32].map(Scalar::from_u128);
+            assert!(x.ends_with(&outputs));
+            assert_eq!(x.len(), r1cs.num_instance);
+            assert!(r1cs.is_satisfied(&r1cs.z(&x, &w)));
+        }
+    }
+
+    #[test]
+    fn test_circuit_r1cs() {
+        for var_nums in [vec![1, 1], vec![1, 2, 3], vec![2, 3, 2, 4]] {
+            let config = CircuitConfig::random(&var_nums, &mut OsRng);
+            let circuit = CircuitR1CS::new(&config, false);
+            let r1cs = &circuit.r1cs;
+
+            // the MUL gates, and the non-MUL outputs
+            let muls = (config.layers.iter().flat_map(|layer| &layer.gates))
+                .filter(|gate| matches!(gate, MUL(..)))
+                .count();
+            let outputs = (config.layers[0].gates.iter())
+                .filter(|gate| !matches!(gate, MUL(..)))
+                .count();
+            assert_eq!(r1cs.num_constraints(), muls + outputs);
+
+            let inputs = random_inputs(config.input_var_num);
+            let (x, w) = circuit.assign(&inputs);
+            assert_eq!(x, config.evaluate(&inputs));
+            let z = r1cs.z(&x, &w);
+            assert!(r1cs.is_satisfied(&z));
+
+            // wrong outputs
+            let mut wrong = z.clone();
+            wrong[1] += Scalar::one();
+            assert!(!r1cs.is_satisfied(&wrong));
+
+            // z_0 should be 1
+            let mut wrong = z.clone();
+            wrong[0] = Scalar::zero();
+            assert!(!r1cs.is_satisfied(&wrong));
+            assert!(!r1cs.is_satisfied(&z[1..]));
+        }
+    }
+
+    #[test]
+    fn test_tampered_witness() {
+        let config = CircuitConfig::from_text(SIMPLE_CIRCUIT).unwrap();
+        let circuit = CircuitR1CS::new(&config, false);
+        let r1cs = &circuit.r1cs;
+        let (x, w) = circuit.assign(&random_inputs(2));
+
+        for j in 0..w.len() {
+            let mut wrong = w.clone();
+            wrong[j] += Scalar::one();
+            assert!(!r1cs.is_satisfied(&r1cs.z(&x, &wrong)));
+        }
+    }
+
+    #[test]
+    fn test_r1cs_json() {
+        let config = CircuitConfig::random(&[1, 2, 2], &mut OsRng);
+        let r1cs = CircuitR1CS::new(&config, true).r1cs;
+        assert_eq!(R1CS::from_json(&r1cs.to_json()).unwrap(), r1cs);
+
+        // an entry out of range
+        let mut wrong = r1cs.clone();
+        wrong.a.entries.push((0, r1cs.num_vars(), Scalar::one()));
+        assert!(R1CS::from_json(&wrong.to_json()).is_err());
+    }
+}
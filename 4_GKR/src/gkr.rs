@@ -2,20 +2,32 @@
 // when applied to a layered arithmetic circuit C of depth d and fan-in two on input x ∈ Fn.
 // Throughout, ki denotes log2(Si) where Si is the number of gates at layer i of C.
 
+use crate::arithmetic::data_parallel::DataParallelCircuit;
 use crate::arithmetic::layered_circuit::CircuitConfig;
 use crate::gkr::prover::Prover;
 use crate::gkr::verifier::Verifier;
-use crate::gkr_sumcheck::GkrSumCheck;
+use crate::gkr_sumcheck::{ClaimReduction, GkrSumCheck, LayerProof};
+use crate::utils::{append_scalars, install};
 use bls12_381::Scalar;
+use rayon::ThreadPool;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use Fiat_Shamir::default::Keccak256Transcript;
+use Fiat_Shamir::Transcript;
 
 mod prover;
+pub mod succinct;
 mod verifier;
+pub mod zk;
 
 pub struct GKR {
     prover: Prover,
     verifier: Verifier,
     d: usize,
     input_var_num: usize,
+    p: usize, // the var_num of the copy index in a data-parallel circuit, 0 otherwise.
+    reduction: ClaimReduction,
+    pool: Option<Arc<ThreadPool>>, // where P runs, the global rayon pool by default.
 }
 
 impl GKR {
@@ -30,40 +42,91 @@ impl GKR {
             verifier: Verifier::default(),
             d,
             input_var_num,
+            p: 0,
+            reduction: ClaimReduction::default(),
+            pool: None,
         }
     }
 
+    // Init with a data-parallel circuit, where the sumcheck of each layer binds the copy index first,
+    // so that P costs O(copies·S') per layer, and V only evaluates the wiring predicates of the sub-circuit.
+    pub fn data_parallel(circuit: DataParallelCircuit) -> Self {
+        let d = circuit.sub.depth;
+        let p = circuit.copy_var_num();
+        let input_var_num = p + circuit.sub.input_var_num;
+
+        Self {
+            prover: Prover::init_data_parallel(circuit),
+            verifier: Verifier::default(),
+            d,
+            input_var_num,
+            p,
+            reduction: ClaimReduction::default(),
+            pool: None,
+        }
+    }
+
+    // Reduce the two claims at the end of each layer by the given strategy, the line by default.
+    pub fn with_reduction(mut self, reduction: ClaimReduction) -> Self {
+        assert!(
+            self.p == 0 || reduction == ClaimReduction::Line,
+            "The data-parallel circuit only supports the line reduction"
+        );
+        self.reduction = reduction;
+        self
+    }
+
+    // P builds the wiring predicates, the witness and the round polys in the given pool.
+    // The proof is the same for any number of threads.
+    pub fn with_thread_pool(config: CircuitConfig, pool: Arc<ThreadPool>) -> Self {
+        let mut gkr = pool.install(|| Self::init(config));
+        gkr.pool = Some(pool);
+        gkr
+    }
+
     // $f_{r_i}^{i}(b,c):=\widetilde{add_{i}}(r_{i},b,c)(\widetilde{W_{i+1}}(b)+\widetilde{W_{i+1}}(c))+\widetilde{mult_i}(r_i,b,c)(\widetilde{W_{i+1}}(b)\cdot \widetilde{W_{i+1}}(c))$
-    fn run_protocol(&mut self, inputs: &Vec<Scalar>) {
+    pub fn run_protocol(&mut self, inputs: &Vec<Scalar>) {
+        // P evaluates the circuit on the inputs.
+        install(&self.pool, || self.prover.synthesize(inputs));
+        self.prove_and_verify(inputs);
+    }
+
+    // The outputs of the circuit, evaluated by P.
+    pub fn outputs(&self) -> Vec<Scalar> {
+        self.prover.outputs()
+    }
+
+    // The interaction on the witness synthesized by P.
+    fn prove_and_verify(&mut self, inputs: &Vec<Scalar>) {
         // 1. Prepare at the start of the protocol,
         //    The remainder of the protocol is devoted to confirming that $m0 =\widetilde{W^0}(r0)$ .
         //    <==> check $m0 = \sum_{b,c\in{0,1}^{i+1}} f_{r_i}(b,c)$
-        self.prover.synthesize(inputs);
         //  1.1 P sends a function $D: {0,1}^k_0 → F$ claimed to equal W_0 (the function mapping output gate labels to output values).
         let D_poly = self.prover.D_poly();
         //  1.2 V pick a challenge r_0( $r0∈Fk0$ ) and let $m_{0}=\widetilde{D}(r_0)$
         let (r_0, m_0) = self.verifier.init(D_poly, inputs, self.input_var_num);
 
-        // 2. start the d rounds gkr_sumcheck
+        // 2. start the gkr_sumcheck on layer 0 to d-1 (the layers except the input one)
         //    check $m_i = \sum_{b,c\in{0,1}^{i+1}} f_{r_i}(b,c)$
-        let mut r_i = r_0;
+        let mut r_i = vec![(Scalar::one(), r_0)];
         let mut m_i = m_0;
-        for i in 0..self.d {
+        for i in 0..self.d - 1 {
             // the ops and witness used in current layer.
-            let (add_i, mult_i) = self.prover.ops.get(i).unwrap();
+            let wiring_i = self.prover.ops.get(i).unwrap();
             let w_i_plus_1 = self.prover.witness.get(i + 1).unwrap();
-            let g = (add_i.clone(), mult_i.clone(), w_i_plus_1.clone());
+            let g = (wiring_i.clone(), w_i_plus_1.clone());
 
-            let mut sumcheck = GkrSumCheck::init(g, r_i.clone(), m_i.clone());
+            let mut sumcheck =
+                GkrSumCheck::new(g, r_i.clone(), m_i, self.p).with_reduction(self.reduction);
 
             // we support the sumcheck prover is from GKR::prover! So does verifier.
-            let (r_i_plus_1, m_i_plus_1) = sumcheck.run_protocol();
+            let (r_i_plus_1, m_i_plus_1) = install(&self.pool, || sumcheck.run_protocol());
 
             r_i = r_i_plus_1;
             m_i = m_i_plus_1;
         }
 
-        // 3. V checks directly that md = W_d(rd ) using Lemma 3.8.
+        // 3. V checks directly that md = W_d(rd ) using Lemma 3.8, or the combination of W_d at 2 points.
         let r_d = r_i;
         let m_d = m_i;
         self.verifier.check(&r_d, m_d);
@@ -74,13 +137,77 @@ impl GKR {
     }
 }
 
+// The non-interactive GKR proof, with the sumcheck proof of layer 0 to d-1.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GkrProof {
+    pub layers: Vec<LayerProof>,
+    #[serde(default)]
+    pub reduction: ClaimReduction,
+}
+
+impl GkrProof {
+    // The number of field elements in the proof.
+    pub fn scalar_num(&self) -> usize {
+        self.layers
+            .iter()
+            .map(|layer| layer.round_polys.iter().map(Vec::len).sum::<usize>() + layer.q.len())
+            .sum()
+    }
+}
+
+// P proves C(inputs) = outputs by Fiat-Shamir, where the challenges are derived from a transcript
+// absorbing the circuit, the inputs, the outputs and each message of P.
+pub fn prove(circuit: &CircuitConfig, inputs: &[Scalar]) -> GkrProof {
+    prove_with_reduction(circuit, inputs, ClaimReduction::default())
+}
+
+// The same as `prove`, with the given claim reduction, which is recorded in the proof for V.
+pub fn prove_with_reduction(
+    circuit: &CircuitConfig,
+    inputs: &[Scalar],
+    reduction: ClaimReduction,
+) -> GkrProof {
+    let mut prover = Prover::init(circuit.clone());
+    prover.synthesize(inputs);
+    let mut transcript = statement_transcript(circuit, inputs, &prover.outputs(), reduction);
+    prover.prove(reduction, &mut transcript).0
+}
+
+// V checks C(inputs) = outputs with the proof only, replaying the transcript of P.
+pub fn verify(
+    circuit: &CircuitConfig,
+    inputs: &[Scalar],
+    outputs: &[Scalar],
+    proof: &GkrProof,
+) -> Result<(), String> {
+    let mut transcript = statement_transcript(circuit, inputs, outputs, proof.reduction);
+    Verifier::verify(circuit, inputs, outputs, proof, &mut transcript)
+}
+
+// The transcript starts from the statement, and the claim reduction which P and V follow.
+fn statement_transcript(
+    circuit: &CircuitConfig,
+    inputs: &[Scalar],
+    outputs: &[Scalar],
+    reduction: ClaimReduction,
+) -> Keccak256Transcript {
+    let mut transcript = Keccak256Transcript::default();
+    transcript.append(circuit.to_text().as_bytes());
+    append_scalars(&mut transcript, inputs);
+    append_scalars(&mut transcript, outputs);
+    transcript.append(reduction.label());
+    transcript
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::arithmetic::layered_circuit::Layer;
     use crate::arithmetic::layered_circuit::Ops::MUL;
-    use crate::utils::convert_from_binary;
-    use ff::PrimeField;
+    use crate::poly::MPolynomial;
+    use crate::utils::challenge_scalar;
+    use ff::{Field, PrimeField};
+    use rand_core::OsRng;
 
     // sample from Figure 4.12.
     fn simple_circuit() -> CircuitConfig {
@@ -104,19 +231,214 @@ mod test {
         }
     }
 
-    #[test]
-    fn test_GKR() {
-        // todo-bugfix later.
-        let inputs = vec![
+    fn simple_inputs() -> Vec<Scalar> {
+        vec![
             Scalar::one(),
             Scalar::from_u128(2),
             Scalar::one(),
             Scalar::from_u128(4),
-        ];
+        ]
+    }
+
+    #[test]
+    fn test_GKR() {
+        let inputs = simple_inputs();
 
         let config = simple_circuit();
         // evaluate the circuit with input
         let mut gkr = GKR::init(config);
         gkr.run_protocol(&inputs);
+        assert_eq!(
+            gkr.prover.outputs(),
+            vec![Scalar::from_u128(4), Scalar::from_u128(32)]
+        );
+    }
+
+    #[test]
+    fn test_GKR_random_circuits() {
+        for var_nums in [
+            vec![1, 1],
+            vec![0, 2, 2],
+            vec![2, 1, 2],
+            vec![1, 2, 1, 2],
+            // wider than the dense wiring predicates could afford.
+            vec![2, 5, 5, 4],
+        ] {
+            let config = CircuitConfig::random(&var_nums, &mut OsRng);
+            let inputs = (0..1 << var_nums[var_nums.len() - 1])
+                .map(|_| Scalar::random(OsRng))
+                .collect::<Vec<_>>();
+            let expected = config.evaluate(&inputs);
+
+            let mut gkr = GKR::init(config);
+            gkr.run_protocol(&inputs);
+            assert_eq!(gkr.prover.outputs(), expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "No-equal in round_1")]
+    fn test_GKR_flipped_witness() {
+        let inputs = simple_inputs();
+        let mut gkr = GKR::init(simple_circuit());
+        gkr.prover.synthesize(&inputs);
+
+        // P lies about the gate 0 of layer 1, which feeds the output gate 0.
+        let mut layer_1 = gkr.prover.witness[1].evals();
+        layer_1[0] += Scalar::one();
+        gkr.prover.witness[1] = MPolynomial::lagrange(2, &layer_1);
+
+        gkr.prove_and_verify(&inputs);
+    }
+
+    #[test]
+    fn test_data_parallel_gkr() {
+        for (var_nums, copies) in [(vec![1, 1], 2), (vec![1, 2, 2], 4), (vec![0, 2, 1, 2], 8)] {
+            let sub = CircuitConfig::random(&var_nums, &mut OsRng);
+            let circuit = DataParallelCircuit::new(sub, copies);
+            let inputs = (0..copies << var_nums[var_nums.len() - 1])
+                .map(|_| Scalar::random(OsRng))
+                .collect::<Vec<_>>();
+            let expected = circuit.to_circuit().evaluate(&inputs);
+
+            let mut gkr = GKR::data_parallel(circuit);
+            gkr.run_protocol(&inputs);
+            assert_eq!(gkr.outputs(), expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "No-equal in round_1")]
+    fn test_data_parallel_gkr_flipped_witness() {
+        let inputs = [simple_inputs(), simple_inputs()].concat();
+        let mut gkr = GKR::data_parallel(DataParallelCircuit::new(simple_circuit(), 2));
+        gkr.prover.synthesize(&inputs);
+
+        // P lies about the gate 0 of layer 1 in copy 1.
+        let mut layer_1 = gkr.prover.witness[1].evals();
+        layer_1[4] += Scalar::one();
+        gkr.prover.witness[1] = MPolynomial::from_evals(3, &layer_1);
+
+        gkr.prove_and_verify(&inputs);
+    }
+
+    #[test]
+    fn test_ni_gkr() {
+        let (config, inputs) = (simple_circuit(), simple_inputs());
+        let outputs = config.evaluate(&inputs);
+
+        let proof = prove(&config, &inputs);
+        assert_eq!(proof.layers.len(), 2);
+        assert_eq!(verify(&config, &inputs, &outputs, &proof), Ok(()));
+
+        // the proof is deterministic, and serialisable.
+        assert_eq!(prove(&config, &inputs), proof);
+        let json = serde_json::to_string(&proof).unwrap();
+        let decoded: GkrProof = serde_json::from_str(&json).unwrap();
+        assert_eq!(verify(&config, &inputs, &outputs, &decoded), Ok(()));
+
+        let config = CircuitConfig::random(&[2, 3, 2], &mut OsRng);
+        let inputs = (0..4).map(|_| Scalar::random(OsRng)).collect::<Vec<_>>();
+        let outputs = config.evaluate(&inputs);
+        assert!(verify(&config, &inputs, &outputs, &prove(&config, &inputs)).is_ok());
+    }
+
+    #[test]
+    fn test_ni_gkr_rejects() {
+        let (config, inputs) = (simple_circuit(), simple_inputs());
+        let outputs = config.evaluate(&inputs);
+        let proof = prove(&config, &inputs);
+
+        // wrong outputs
+        let mut wrong_outputs = outputs.clone();
+        wrong_outputs[1] += Scalar::one();
+        assert_eq!(
+            verify(&config, &inputs, &wrong_outputs, &proof),
+            Err("layer 0: Not-equal in round_1".to_string())
+        );
+
+        // a round poly of layer 1 is changed.
+        let mut wrong_proof = proof.clone();
+        wrong_proof.layers[1].round_polys[2][0] += Scalar::one();
+        assert_eq!(
+            verify(&config, &inputs, &outputs, &wrong_proof),
+            Err("layer 1: Not-equal in round_3".to_string())
+        );
+
+        // the claimed W_2 values are changed.
+        let mut wrong_proof = proof.clone();
+        wrong_proof.layers[1].q[0] += Scalar::one();
+        assert_eq!(
+            verify(&config, &inputs, &outputs, &wrong_proof),
+            Err("layer 1: Verifier rejected the proof".to_string())
+        );
+
+        // the proof for other inputs.
+        let other_inputs = vec![Scalar::one(); 4];
+        assert!(verify(&config, &other_inputs, &outputs, &proof).is_err());
+    }
+
+    #[test]
+    fn test_GKR_random_combination() {
+        for var_nums in [
+            vec![1, 1],
+            vec![2, 1, 2],
+            vec![1, 2, 1, 2],
+            vec![2, 5, 5, 4],
+        ] {
+            let config = CircuitConfig::random(&var_nums, &mut OsRng);
+            let inputs = (0..1 << var_nums[var_nums.len() - 1])
+                .map(|_| Scalar::random(OsRng))
+                .collect::<Vec<_>>();
+            let expected = config.evaluate(&inputs);
+
+            let mut gkr = GKR::init(config).with_reduction(ClaimReduction::RandomCombination);
+            gkr.run_protocol(&inputs);
+            assert_eq!(gkr.outputs(), expected);
+        }
+    }
+
+    #[test]
+    fn test_ni_gkr_random_combination() {
+        let config = CircuitConfig::random(&[2, 4, 3, 4], &mut OsRng);
+        let inputs = (0..16).map(|_| Scalar::random(OsRng)).collect::<Vec<_>>();
+        let outputs = config.evaluate(&inputs);
+
+        let proof = prove_with_reduction(&config, &inputs, ClaimReduction::RandomCombination);
+        assert_eq!(verify(&config, &inputs, &outputs, &proof), Ok(()));
+        let json = serde_json::to_string(&proof).unwrap();
+        let decoded: GkrProof = serde_json::from_str(&json).unwrap();
+        assert_eq!(verify(&config, &inputs, &outputs, &decoded), Ok(()));
+
+        // q is W_i+1(u), W_i+1(v) only, instead of k_i+1 + 1 coeffs.
+        let line_proof = prove(&config, &inputs);
+        assert_eq!(
+            line_proof.scalar_num() - proof.scalar_num(),
+            (4 + 1) + (3 + 1) + (4 + 1) - 3 * 2
+        );
+
+        // the claimed W_3 values are changed.
+        let mut wrong_proof = proof.clone();
+        wrong_proof.layers[2].q[1] += Scalar::one();
+        assert_eq!(
+            verify(&config, &inputs, &outputs, &wrong_proof),
+            Err("layer 2: Verifier rejected the proof".to_string())
+        );
+
+        // q of the line is rejected.
+        let mut wrong_proof = line_proof.clone();
+        wrong_proof.reduction = ClaimReduction::RandomCombination;
+        assert!(verify(&config, &inputs, &outputs, &wrong_proof).is_err());
+
+        // the challenges are bound to the reduction.
+        let challenge = |reduction| {
+            challenge_scalar(&mut statement_transcript(
+                &config, &inputs, &outputs, reduction,
+            ))
+        };
+        assert_ne!(
+            challenge(ClaimReduction::Line),
+            challenge(ClaimReduction::RandomCombination)
+        );
     }
 }
@@ -1,33 +1,43 @@
-use crate::arithmetic::layered_circuit::CircuitConfig;
+use crate::arithmetic::data_parallel::DataParallelCircuit;
+use crate::arithmetic::layered_circuit::{CircuitConfig, Wiring};
+use crate::gkr::GkrProof;
+use crate::gkr_sumcheck::{ClaimReduction, GkrSumCheck, Points};
 use crate::poly::MPolynomial;
+use crate::utils::challenge_scalars;
 use bls12_381::Scalar;
+use Fiat_Shamir::Transcript;
 
 pub struct Prover {
     pub inputs: Vec<Scalar>,
     pub witness: Vec<MPolynomial>, // witness, start from 0 to d (include the input layer(layer_d). len = d+1
     outputs: Vec<Scalar>,
-    pub ops: Vec<(MPolynomial, MPolynomial)>, // (add,mult) gate mpoly of each layer, start from 0 to d-1. len = d+1
+    pub ops: Vec<Wiring>, // wiring predicates of each layer, start from 0 to d-1. len = d-1
     depth: usize,
-    config: CircuitConfig,
+    circuit: DataParallelCircuit, // a plain circuit is the one with 1 copy.
 }
 
 impl Prover {
     // actual, this is the config.
     pub fn init(config: CircuitConfig) -> Self {
-        let ops = config.ops_to_mpoly();
+        Self::init_data_parallel(DataParallelCircuit::new(config, 1))
+    }
+
+    // P only keeps the wiring predicates of the sub-circuit, as the copies share them.
+    pub fn init_data_parallel(circuit: DataParallelCircuit) -> Self {
+        let ops = circuit.sub.ops_to_wiring();
         Self {
             inputs: vec![],
             witness: vec![],
             outputs: vec![],
             ops,
-            depth: config.depth,
-            config,
+            depth: circuit.sub.depth,
+            circuit,
         }
     }
 
     // synthesize with inputs to gen witness/advices.
-    pub(crate) fn synthesize(&mut self, input: &Vec<Scalar>) {
-        let (witness, outputs) = self.config.witness_to_poly(&input);
+    pub(crate) fn synthesize(&mut self, input: &[Scalar]) {
+        let (witness, outputs) = self.circuit.witness_to_poly(input);
         self.witness = witness;
         self.outputs = outputs;
     }
@@ -40,4 +50,24 @@ impl Prover {
     pub fn outputs(&self) -> Vec<Scalar> {
         self.outputs.clone()
     }
+
+    // P runs the sumcheck of each layer by itself, with r_0 and the challenges from the transcript.
+    // Return the proof, and the points of the claim on the inputs, eg: [(1, r_d)] for W_d(r_d).
+    pub(crate) fn prove(
+        &self,
+        reduction: ClaimReduction,
+        transcript: &mut impl Transcript,
+    ) -> (GkrProof, Points) {
+        let r_0 = challenge_scalars(transcript, self.witness[0].var_num);
+        let mut r_i = vec![(Scalar::one(), r_0)];
+        let layers = (0..self.depth - 1)
+            .map(|i| {
+                let g = (self.ops[i].clone(), self.witness[i + 1].clone());
+                let (proof, r_i_plus_1) = GkrSumCheck::prove(g, r_i.clone(), reduction, transcript);
+                r_i = r_i_plus_1;
+                proof
+            })
+            .collect();
+        (GkrProof { layers, reduction }, r_i)
+    }
 }
@@ -0,0 +1,191 @@
+// The succinct GKR argument, where V doesn't read the inputs.
+//
+// In the GKR protocol, V evaluates W_d(r_d) from the inputs by itself, which is linear in the input size.
+// Instead, P commits to W_d with a multilinear polynomial commitment before any challenge,
+// and opens W_d at r_d in the end:
+//  1. P commits to W_d, and the commitment is absorbed by the transcript, along with the circuit and outputs.
+//  2. P and V run the non-interactive GKR from the outputs to the claim m_d = W_d(r_d).
+//  3. P opens W_d at r_d, and V checks the opening against the commitment, and m_d.
+// So that the inputs, eg: a private witness, are never sent to V, and V costs O(S) for the wiring predicates,
+// plus the verification of the opening, which is O(√n) for Hyrax.
+//
+// NOTE: it's succinct but not zero-knowledge, as the round polys leak the witness. See `zk` for that.
+
+use crate::arithmetic::layered_circuit::CircuitConfig;
+use crate::gkr::prover::Prover;
+use crate::gkr::verifier::Verifier;
+use crate::gkr::zk::pedersen::append_point;
+use crate::gkr::GkrProof;
+use crate::gkr_sumcheck::ClaimReduction;
+use crate::utils::append_scalars;
+use bls12_381::Scalar;
+use sumcheck::commitment::hyrax::HyraxCommitment;
+use sumcheck::commitment::PolynomialCommitment;
+use Fiat_Shamir::default::Keccak256Transcript;
+use Fiat_Shamir::Transcript;
+
+// The commitment absorbed by the transcript, so that the challenges are bound to it.
+pub trait TranscriptCommitment {
+    fn append_to(&self, transcript: &mut impl Transcript);
+}
+
+impl TranscriptCommitment for HyraxCommitment {
+    fn append_to(&self, transcript: &mut impl Transcript) {
+        self.row_commitments()
+            .iter()
+            .for_each(|c| append_point(transcript, c));
+    }
+}
+
+pub struct SuccinctProof<PCS: PolynomialCommitment> {
+    pub commitment: PCS::Commitment, // the commitment to W_d
+    pub gkr: GkrProof,
+    pub value: Scalar, // W_d(r_d)
+    pub opening: PCS::Proof,
+}
+
+// P proves C(inputs) = outputs, with the inputs committed by the given PCS, which is set up for input_var_num.
+// Return the proof and the outputs.
+pub fn prove<PCS>(
+    pcs: &PCS,
+    circuit: &CircuitConfig,
+    inputs: &[Scalar],
+) -> (SuccinctProof<PCS>, Vec<Scalar>)
+where
+    PCS: PolynomialCommitment,
+    PCS::Commitment: TranscriptCommitment,
+{
+    let mut prover = Prover::init(circuit.clone());
+    prover.synthesize(inputs);
+    let w_d = &prover.witness[circuit.depth - 1];
+    let commitment = pcs.commit(w_d);
+
+    let outputs = prover.outputs();
+    let mut transcript = statement_transcript(circuit, &commitment, &outputs);
+    let (gkr, r_d) = prover.prove(ClaimReduction::Line, &mut transcript);
+    let (value, opening) = pcs.open(w_d, &r_d[0].1);
+
+    let proof = SuccinctProof {
+        commitment,
+        gkr,
+        value,
+        opening,
+    };
+    (proof, outputs)
+}
+
+// V checks C(inputs) = outputs, with the commitment to the inputs only.
+pub fn verify<PCS>(
+    pcs: &PCS,
+    circuit: &CircuitConfig,
+    outputs: &[Scalar],
+    proof: &SuccinctProof<PCS>,
+) -> Result<(), String>
+where
+    PCS: PolynomialCommitment,
+    PCS::Commitment: TranscriptCommitment,
+{
+    // W_d is opened at one point, so that the claims are reduced by the line.
+    if proof.gkr.reduction != ClaimReduction::Line {
+        return Err("Only the line reduction is supported".to_string());
+    }
+    let mut transcript = statement_transcript(circuit, &proof.commitment, outputs);
+    let (r_d, m_d) = Verifier::verify_layers(circuit, outputs, &proof.gkr, &mut transcript)?;
+    let r_d = &r_d[0].1;
+
+    // m_d = W_d(r_d), by the opening of the commitment.
+    if proof.value != m_d {
+        return Err("GKR verifier: final check failed".to_string());
+    }
+    pcs.verify(&proof.commitment, r_d, &proof.value, &proof.opening)
+}
+
+fn statement_transcript(
+    circuit: &CircuitConfig,
+    commitment: &impl TranscriptCommitment,
+    outputs: &[Scalar],
+) -> Keccak256Transcript {
+    let mut transcript = Keccak256Transcript::default();
+    transcript.append(circuit.to_text().as_bytes());
+    commitment.append_to(&mut transcript);
+    append_scalars(&mut transcript, outputs);
+    transcript
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::poly::MPolynomial;
+    use ff::Field;
+    use rand_core::OsRng;
+    use sumcheck::commitment::hyrax::Hyrax;
+
+    fn random_inputs(var_num: usize) -> Vec<Scalar> {
+        (0..1 << var_num).map(|_| Scalar::random(OsRng)).collect()
+    }
+
+    #[test]
+    fn test_succinct_gkr() {
+        for var_nums in [vec![1, 1], vec![1, 2, 3], vec![2, 3, 2, 4]] {
+            let config = CircuitConfig::random(&var_nums, &mut OsRng);
+            let inputs = random_inputs(config.input_var_num);
+            let pcs = Hyrax::setup(config.input_var_num);
+
+            let (proof, outputs) = prove(&pcs, &config, &inputs);
+            assert_eq!(outputs, config.evaluate(&inputs));
+            assert_eq!(verify(&pcs, &config, &outputs, &proof), Ok(()));
+
+            // wrong outputs
+            let mut wrong_outputs = outputs.clone();
+            wrong_outputs[0] += Scalar::one();
+            assert!(verify(&pcs, &config, &wrong_outputs, &proof).is_err());
+        }
+    }
+
+    #[test]
+    fn test_succinct_gkr_wrong_value() {
+        let config = CircuitConfig::random(&[1, 2, 2], &mut OsRng);
+        let inputs = random_inputs(2);
+        let pcs = Hyrax::setup(2);
+        let (mut proof, outputs) = prove(&pcs, &config, &inputs);
+
+        proof.value += Scalar::one();
+        assert_eq!(
+            verify(&pcs, &config, &outputs, &proof),
+            Err("GKR verifier: final check failed".to_string())
+        );
+    }
+
+    #[test]
+    fn test_succinct_gkr_other_inputs() {
+        let config = CircuitConfig::random(&[1, 2, 2], &mut OsRng);
+        let pcs = Hyrax::setup(2);
+        let (mut proof, outputs) = prove(&pcs, &config, &random_inputs(2));
+
+        // the challenges are bound to the commitment, so it can't be swapped for the one of other inputs.
+        proof.commitment = pcs.commit(&MPolynomial::from_evals(2, &random_inputs(2)));
+        assert!(verify(&pcs, &config, &outputs, &proof).is_err());
+    }
+
+    #[test]
+    fn test_succinct_gkr_wrong_opening() {
+        let config = CircuitConfig::random(&[1, 2, 2], &mut OsRng);
+        let pcs = Hyrax::setup(2);
+        let (mut proof, outputs) = prove(&pcs, &config, &random_inputs(2));
+
+        proof.opening[0] += Scalar::one();
+        assert!(verify(&pcs, &config, &outputs, &proof).is_err());
+    }
+
+    #[test]
+    fn test_succinct_gkr_mismatched_pcs() {
+        let config = CircuitConfig::random(&[1, 2, 2], &mut OsRng);
+        let (proof, outputs) = prove(&Hyrax::setup(2), &config, &random_inputs(2));
+
+        // the key is for the inputs of 3 variables, so V rejects instead of panicking.
+        assert_eq!(
+            verify(&Hyrax::setup(3), &config, &outputs, &proof),
+            Err("Hyrax: wrong point length".to_string())
+        );
+    }
+}
@@ -1,8 +1,12 @@
+use crate::arithmetic::layered_circuit::CircuitConfig;
+use crate::gkr::GkrProof;
+use crate::gkr_sumcheck::{GkrSumCheck, Points};
 use crate::poly::MPolynomial;
+use crate::utils::challenge_scalars;
 use bls12_381::Scalar;
-use rand_core::{OsRng, RngCore};
-use std::env::var;
-use std::os::unix::raw::mode_t;
+use ff::Field;
+use rand_core::OsRng;
+use Fiat_Shamir::Transcript;
 
 #[derive(Clone, Debug, Default)]
 pub struct Verifier {
@@ -16,14 +20,13 @@ impl Verifier {
         output: MPolynomial,
         inputs: &Vec<Scalar>,
         input_var_num: usize,
-    ) -> (Vec<usize>, Scalar) {
+    ) -> (Vec<Scalar>, Scalar) {
         //  V pick a challenge r_0( $r0∈Fk0$ ) and let $m_{0}=\widetilde{D}(r_0)$
         let r_0 = Self::gen_challenge(output.var_num);
-        // let r_0 = vec![3]; // used for debug
-        let m0 = output.evaluate(&r_0);
+        let m0 = output.evaluate_at(&r_0);
 
         // Encode the inputs as Mpoly.
-        let w_d = MPolynomial::lagrange(input_var_num, inputs);
+        let w_d = MPolynomial::from_evals(input_var_num, inputs);
 
         self.m0 = m0;
         self.w_d = w_d;
@@ -31,18 +34,81 @@ impl Verifier {
     }
 
     // generate r1, r2, ..., rv,  $r_i ∈ F^{k_i}$
-    pub fn gen_challenge(var_num: usize) -> Vec<usize> {
-        let randoms = (0..var_num)
-            .map(|_| OsRng.next_u32() % 1000)
-            .collect::<Vec<_>>();
-        randoms.iter().map(|r| *r as usize).collect::<Vec<_>>()
+    pub fn gen_challenge(var_num: usize) -> Vec<Scalar> {
+        (0..var_num).map(|_| Scalar::random(OsRng)).collect()
     }
 
-    // V checks  m_d = W_d (r_d )
-    pub fn check(&self, r_d: &Vec<usize>, target: Scalar) {
-        let actual = self.w_d.evaluate(r_d);
+    // V checks  m_d = W_d (r_d ), or ∑α·W_d(r) over the points (α, r) of r_d.
+    pub fn check(&self, r_d: &Points, target: Scalar) {
+        let actual = weighted_sum(&self.w_d, r_d);
         assert_eq!(target, actual, "GKR verifier: final check failed");
 
         println!("GKR: V accepted the output from P");
     }
+
+    // V checks the proof with r_0 and the challenges from the transcript:
+    //  m_0 = W_0(r_0) from the outputs, the sumcheck of each layer, and m_d = W_d(r_d) from the inputs.
+    pub fn verify(
+        circuit: &CircuitConfig,
+        inputs: &[Scalar],
+        outputs: &[Scalar],
+        proof: &GkrProof,
+        transcript: &mut impl Transcript,
+    ) -> Result<(), String> {
+        if inputs.len() != 1 << circuit.input_var_num {
+            return Err("Wrong number of inputs or outputs".to_string());
+        }
+        let (r_d, m_d) = Self::verify_layers(circuit, outputs, proof, transcript)?;
+
+        let w_d = MPolynomial::from_evals(circuit.input_var_num, inputs);
+        if weighted_sum(&w_d, &r_d) != m_d {
+            return Err("GKR verifier: final check failed".to_string());
+        }
+        Ok(())
+    }
+
+    // V checks m_0 = W_0(r_0) and the sumcheck of each layer.
+    // Return (r_d, m_d), where m_d = ∑α·W_d(r) over the points (α, r) of r_d is left to check.
+    pub(crate) fn verify_layers(
+        circuit: &CircuitConfig,
+        outputs: &[Scalar],
+        proof: &GkrProof,
+        transcript: &mut impl Transcript,
+    ) -> Result<(Points, Scalar), String> {
+        circuit.validate()?;
+        let k_0 = circuit.layers[0].var_num;
+        if outputs.len() != 1 << k_0 {
+            return Err("Wrong number of inputs or outputs".to_string());
+        }
+        if proof.layers.len() != circuit.depth - 1 {
+            return Err(format!(
+                "Expect {} layer proofs, got {}",
+                circuit.depth - 1,
+                proof.layers.len()
+            ));
+        }
+
+        let r_0 = challenge_scalars(transcript, k_0);
+        let mut m_i = MPolynomial::from_evals(k_0, outputs).evaluate_at(&r_0);
+        let mut r_i = vec![(Scalar::one(), r_0)];
+        for (i, (wiring, layer_proof)) in circuit
+            .ops_to_wiring()
+            .iter()
+            .zip(&proof.layers)
+            .enumerate()
+        {
+            (r_i, m_i) =
+                GkrSumCheck::verify(wiring, &r_i, m_i, proof.reduction, layer_proof, transcript)
+                    .map_err(|e| format!("layer {}: {}", i, e))?;
+        }
+        Ok((r_i, m_i))
+    }
+}
+
+// ∑α·W(r) over the weighted points (α, r)
+fn weighted_sum(w: &MPolynomial, points: &Points) -> Scalar {
+    points
+        .iter()
+        .map(|(alpha, r)| alpha * w.evaluate_at(r))
+        .sum()
 }
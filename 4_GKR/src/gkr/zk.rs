@@ -0,0 +1,523 @@
+// Zero-knowledge GKR, following Libra(XZZ+19).
+//
+// 1. The sumcheck of each layer is masked by a random g(x) = a_0 + g_1(x_1) + ... + g_n(x_n) committed up front,
+//    where deg(g_j) is the degree of the round poly j. With H = ∑g(x) and the challenge ρ, P runs the sumcheck on
+//    F + ρ·g for the claim m + ρ·H instead, and opens g(r) at the end. Then the round polys leak nothing but F(r).
+// 2. The witness of the layer 1 to d-2 is masked as
+//          Ẇ_i(x) = W_i(x) + Z(x)·∑R_i(x_1, w), w in {0,1}, Z(x) = ∏x_j(1-x_j)
+//    with a random R_i committed up front. Ẇ_i = W_i on the hypercube, so the sumchecks hold with Ẇ in place of W,
+//    and the evaluations Ẇ_i(b*), Ẇ_i(c*) sent to V are random.
+// 3. The line restriction q = W∘l would reveal W_i+1 on a line, so the two claims are reduced by the random
+//    linear combination instead: layer i proves α·Ẇ_i(u) + β·Ẇ_i(v) = ∑F(b,c), where
+//          F(b,c) = α·f_u(b,c) + β·f_v(b,c) + E(c_1)/2^(n-1),  E(w) = α·Z(u)·R_i(u_1,w) + β·Z(v)·R_i(v_1,w)
+//    and f_u is f_{r_i} at r_i = u, with Ẇ_i+1. At the end, P opens R_i(u_1, c*_1) and R_i(v_1, c*_1) for E(c*_1).
+//
+// The inputs and outputs are public, so Ẇ_0 = W_0 and Ẇ_d-1 = W_d-1.
+use crate::arithmetic::circuit_file::scalar_str;
+use crate::arithmetic::layered_circuit::CircuitConfig;
+use crate::gkr::prover::Prover;
+use crate::gkr::statement_transcript;
+use crate::gkr::zk::pedersen::{append_point, point_hex, DotProductProof, Pedersen};
+use crate::gkr_sumcheck::libra_prover::{vanishing, LibraProver};
+use crate::gkr_sumcheck::verifier::f_r_at;
+use crate::gkr_sumcheck::{combine_eq, ClaimReduction, LayerProver};
+use crate::poly::{MPolynomial, Polynomial};
+use crate::utils::{append_scalars, challenge_scalar, challenge_scalars};
+use bls12_381::{G1Projective, Scalar};
+use ff::Field;
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+
+pub mod pedersen;
+
+// The coeffs number of R_i(x_1, w), which has degree 2 in x_1 and 1 in w.
+// V learns two evaluations of ∑R_i(x_1, w) and two of R_i, so that 4 coeffs at least.
+const MASK_LEN: usize = 6;
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ZkLayerProof {
+    #[serde(with = "point_hex")]
+    pub g_commitment: G1Projective,
+    #[serde(with = "scalar_str")]
+    pub g_sum: Scalar, // H = ∑g(x)
+    #[serde(with = "scalar_str::vecs")]
+    pub round_polys: Vec<Vec<Scalar>>,
+    #[serde(with = "scalar_str")]
+    pub w_b: Scalar, // Ẇ_i+1(b*)
+    #[serde(with = "scalar_str")]
+    pub w_c: Scalar, // Ẇ_i+1(c*)
+    pub g_opening: DotProductProof,
+    pub mask_openings: Vec<DotProductProof>, // R_i(u_1, c*_1) and R_i(v_1, c*_1) if layer i is masked.
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ZkGkrProof {
+    #[serde(with = "point_hex::vec")]
+    pub mask_commitments: Vec<G1Projective>, // R_1, ..., R_d-2
+    pub layers: Vec<ZkLayerProof>,
+}
+
+// The commitment key for the masks of the circuit.
+pub fn setup(circuit: &CircuitConfig) -> Pedersen {
+    let max_len = (1..circuit.depth)
+        .map(|i| {
+            let k = circuit.var_num(i);
+            MaskPoly::len(&round_degrees(k, is_masked(circuit, i)))
+        })
+        .max()
+        .unwrap_or(0)
+        .max(MASK_LEN);
+    Pedersen::setup(max_len)
+}
+
+pub fn prove(pedersen: &Pedersen, circuit: &CircuitConfig, inputs: &[Scalar]) -> ZkGkrProof {
+    let mut prover = Prover::init(circuit.clone());
+    prover.synthesize(inputs);
+    let transcript = &mut statement_transcript(
+        circuit,
+        inputs,
+        &prover.outputs(),
+        ClaimReduction::RandomCombination,
+    );
+    let d = circuit.depth;
+
+    // R_1, ..., R_d-2, committed up front.
+    let masks = (1..d - 1)
+        .map(|_| (random_vec(MASK_LEN), Scalar::random(OsRng)))
+        .collect::<Vec<_>>();
+    let mask_commitments = masks
+        .iter()
+        .map(|(r, blind)| pedersen.commit(r, blind))
+        .collect::<Vec<_>>();
+    mask_commitments
+        .iter()
+        .for_each(|c| append_point(transcript, c));
+    let mask = |i: usize| is_masked(circuit, i).then(|| &masks[i - 1]);
+
+    // the claim α·Ẇ_i(u) + β·Ẇ_i(v), which starts from W_0(r_0).
+    let r_0 = challenge_scalars(transcript, circuit.layers[0].var_num);
+    let (mut u, mut v) = (r_0.clone(), r_0);
+    let (mut alpha, mut beta) = (Scalar::one(), Scalar::zero());
+
+    let mut layers = vec![];
+    for i in 0..d - 1 {
+        let k = circuit.var_num(i + 1);
+        let n = 2 * k;
+        let degrees = round_degrees(k, mask(i + 1).is_some());
+
+        let g = (prover.ops[i].clone(), prover.witness[i + 1].clone());
+        let mut libra =
+            LibraProver::with_eq_table(g, combine_eq(&[(alpha, u.clone()), (beta, v.clone())]));
+        if let Some((r, _)) = mask(i + 1) {
+            libra = libra.with_mask(mask_sum(r));
+        }
+        // E(0), E(1)
+        let e = [Scalar::zero(), Scalar::one()].map(|w| match mask(i) {
+            Some((r, _)) => {
+                alpha * vanishing(&u) * inner_product(r, &mask_monomials(u[0], w))
+                    + beta * vanishing(&v) * inner_product(r, &mask_monomials(v[0], w))
+            }
+            None => Scalar::zero(),
+        });
+        let e_at = |w: Scalar| e[0] + w * (e[1] - e[0]);
+
+        let g_mask = MaskPoly::random(degrees.clone());
+        let g_blind = Scalar::random(OsRng);
+        let g_commitment = pedersen.commit(&g_mask.coeffs, &g_blind);
+        let g_sum = g_mask.sum();
+        append_point(transcript, &g_commitment);
+        append_scalars(transcript, &[g_sum]);
+        let rho = challenge_scalar(transcript);
+
+        let mut round_polys = vec![];
+        let mut challenges = vec![];
+        for j in 1..=n {
+            let f_j = match j {
+                1 => libra.round_1(),
+                _ if j == n => libra.round_v(&challenges),
+                _ => libra.recursive_round_j(&challenges),
+            };
+            // T_j(X) = ∑E(c_1)/2^(n-1) over the unbound variables.
+            let t_j = |x: Scalar| {
+                let e_j = match j {
+                    _ if j <= k => (e[0] + e[1]) * half(),
+                    _ if j == k + 1 => e_at(x),
+                    _ => e_at(challenges[k]),
+                };
+                e_j * pow(half(), j - 1)
+            };
+
+            let xs = (0..=degrees[j - 1])
+                .map(|x| Scalar::from(x as u64))
+                .collect::<Vec<_>>();
+            let evals = xs
+                .iter()
+                .map(|x| f_j.evaluate(*x) + t_j(*x) + rho * g_mask.partial_sum(&challenges, *x))
+                .collect();
+            let p_j = Polynomial::lagrange_interpolate(xs, evals).coeffs();
+
+            append_scalars(transcript, &p_j);
+            challenges.push(challenge_scalar(transcript));
+            round_polys.push(p_j);
+        }
+
+        let (b, c) = challenges.split_at(k);
+        let w_b = libra.masked_w(b);
+        let w_c = libra.masked_w(c);
+        append_scalars(transcript, &[w_b, w_c]);
+
+        let y = MaskPoly::monomials(&degrees, &challenges);
+        let g_opening = pedersen.open(&g_mask.coeffs, &g_blind, &y, transcript);
+        let mask_openings = match mask(i) {
+            Some((r, blind)) => [&u, &v]
+                .iter()
+                .map(|p| pedersen.open(r, blind, &mask_monomials(p[0], c[0]), transcript))
+                .collect(),
+            None => vec![],
+        };
+
+        layers.push(ZkLayerProof {
+            g_commitment,
+            g_sum,
+            round_polys,
+            w_b,
+            w_c,
+            g_opening,
+            mask_openings,
+        });
+
+        (alpha, beta) = (challenge_scalar(transcript), challenge_scalar(transcript));
+        (u, v) = (b.to_vec(), c.to_vec());
+    }
+
+    ZkGkrProof {
+        mask_commitments,
+        layers,
+    }
+}
+
+pub fn verify(
+    pedersen: &Pedersen,
+    circuit: &CircuitConfig,
+    inputs: &[Scalar],
+    outputs: &[Scalar],
+    proof: &ZkGkrProof,
+) -> Result<(), String> {
+    circuit.validate()?;
+    let d = circuit.depth;
+    let k_0 = circuit.layers[0].var_num;
+    if inputs.len() != 1 << circuit.input_var_num || outputs.len() != 1 << k_0 {
+        return Err("Wrong number of inputs or outputs".to_string());
+    }
+    if proof.layers.len() != d - 1 || proof.mask_commitments.len() != d - 2 {
+        return Err("Wrong number of layer proofs or masks".to_string());
+    }
+
+    let transcript =
+        &mut statement_transcript(circuit, inputs, outputs, ClaimReduction::RandomCombination);
+    proof
+        .mask_commitments
+        .iter()
+        .for_each(|c| append_point(transcript, c));
+
+    let r_0 = challenge_scalars(transcript, k_0);
+    let mut m = MPolynomial::from_evals(k_0, outputs).evaluate_at(&r_0);
+    let (mut u, mut v) = (r_0.clone(), r_0);
+    let (mut alpha, mut beta) = (Scalar::one(), Scalar::zero());
+
+    for (i, (wiring, layer)) in circuit
+        .ops_to_wiring()
+        .iter()
+        .zip(&proof.layers)
+        .enumerate()
+    {
+        let err = |msg: String| format!("layer {}: {}", i, msg);
+        let k = wiring.var_nums.1;
+        let n = 2 * k;
+        let degrees = round_degrees(k, is_masked(circuit, i + 1));
+        if layer.round_polys.len() != n {
+            return Err(err(format!("Expect {} round polys", n)));
+        }
+
+        append_point(transcript, &layer.g_commitment);
+        append_scalars(transcript, &[layer.g_sum]);
+        let rho = challenge_scalar(transcript);
+
+        // p_j(0) + p_j(1) = p_j-1(r_j-1), with p_0(r_0) = m + ρ·H
+        let mut claim = m + rho * layer.g_sum;
+        let mut challenges = vec![];
+        for (j, coeffs) in layer.round_polys.iter().enumerate() {
+            if coeffs.is_empty() || coeffs.len() > degrees[j] + 1 {
+                return Err(err(format!("p_{} has a wrong degree", j + 1)));
+            }
+            let p_j = Polynomial::from_coeffs(coeffs.clone());
+            if p_j.evaluate(Scalar::zero()) + p_j.evaluate(Scalar::one()) != claim {
+                return Err(err(format!("Not-equal in round_{}", j + 1)));
+            }
+            append_scalars(transcript, coeffs);
+            let r_j = challenge_scalar(transcript);
+            claim = p_j.evaluate(r_j);
+            challenges.push(r_j);
+        }
+
+        let (b, c) = challenges.split_at(k);
+        let w_b = layer.w_b;
+        let w_c = layer.w_c;
+        append_scalars(transcript, &[w_b, w_c]);
+
+        let y = MaskPoly::monomials(&degrees, &challenges);
+        pedersen
+            .verify(&layer.g_commitment, &y, &layer.g_opening, transcript)
+            .map_err(err)?;
+        // E(c*_1) from the openings of R_i.
+        let e = match is_masked(circuit, i) {
+            true if layer.mask_openings.len() == 2 => {
+                let commitment = &proof.mask_commitments[i - 1];
+                for (p, opening) in [&u, &v].iter().zip(&layer.mask_openings) {
+                    let y = mask_monomials(p[0], c[0]);
+                    pedersen
+                        .verify(commitment, &y, opening, transcript)
+                        .map_err(err)?;
+                }
+                let [r_u, r_v] = [0, 1].map(|i| layer.mask_openings[i].value);
+                alpha * vanishing(&u) * r_u + beta * vanishing(&v) * r_v
+            }
+            false if layer.mask_openings.is_empty() => Scalar::zero(),
+            _ => return Err(err("Wrong number of mask openings".to_string())),
+        };
+
+        // p_n(r_n) = F(b*, c*) + ρ·g(r)
+        let f = |p: &[Scalar]| f_r_at(wiring.evaluate(&[p, b, c].concat()), w_b, w_c);
+        let target =
+            alpha * f(&u) + beta * f(&v) + e * pow(half(), n - 1) + rho * layer.g_opening.value;
+        if claim != target {
+            return Err(err("Verifier rejected the proof".to_string()));
+        }
+
+        (alpha, beta) = (challenge_scalar(transcript), challenge_scalar(transcript));
+        m = alpha * w_b + beta * w_c;
+        (u, v) = (b.to_vec(), c.to_vec());
+    }
+
+    // V checks the last claim with the inputs directly.
+    let w_d = MPolynomial::from_evals(circuit.input_var_num, inputs);
+    if alpha * w_d.evaluate_at(&u) + beta * w_d.evaluate_at(&v) != m {
+        return Err("GKR verifier: final check failed".to_string());
+    }
+    Ok(())
+}
+
+// g(x) = a_0 + ∑g_j(x_j), deg(g_j) = d_j, with the coeffs [a_0, g_1[1..], ..., g_n[1..]].
+struct MaskPoly {
+    coeffs: Vec<Scalar>,
+    degrees: Vec<usize>,
+}
+
+impl MaskPoly {
+    fn random(degrees: Vec<usize>) -> Self {
+        Self {
+            coeffs: random_vec(Self::len(&degrees)),
+            degrees,
+        }
+    }
+
+    fn len(degrees: &[usize]) -> usize {
+        1 + degrees.iter().sum::<usize>()
+    }
+
+    // g_j without the constant.
+    fn g_j(&self, j: usize) -> Polynomial {
+        let offset = 1 + self.degrees[..j - 1].iter().sum::<usize>();
+        let mut coeffs = vec![Scalar::zero()];
+        coeffs.extend_from_slice(&self.coeffs[offset..offset + self.degrees[j - 1]]);
+        Polynomial::from_coeffs(coeffs)
+    }
+
+    // g_j(0) + g_j(1)
+    fn sum_j(&self, j: usize) -> Scalar {
+        self.g_j(j).evaluate(Scalar::one())
+    }
+
+    // H = ∑g(x) = 2^n·a_0 + 2^(n-1)·∑(g_j(0) + g_j(1))
+    fn sum(&self) -> Scalar {
+        let n = self.degrees.len();
+        let sums = (1..=n).map(|j| self.sum_j(j)).sum::<Scalar>();
+        pow(Scalar::from(2), n) * (self.coeffs[0] + sums * half())
+    }
+
+    // G_j(X) = ∑g(r_1, ..., r_j-1, X, x), x in {0,1}^(n-j)
+    //        = 2^(n-j)·(a_0 + ∑g_l(r_l) + g_j(X)) + 2^(n-j-1)·∑(g_l(0) + g_l(1)), l < j for r_l and l > j for the sums.
+    fn partial_sum(&self, bound: &[Scalar], x: Scalar) -> Scalar {
+        let (n, j) = (self.degrees.len(), bound.len() + 1);
+        let prefix = bound
+            .iter()
+            .enumerate()
+            .map(|(l, r)| self.g_j(l + 1).evaluate(*r))
+            .sum::<Scalar>();
+        let rest = (j + 1..=n).map(|l| self.sum_j(l)).sum::<Scalar>();
+        pow(Scalar::from(2), n - j)
+            * (self.coeffs[0] + prefix + self.g_j(j).evaluate(x) + rest * half())
+    }
+
+    // y with g(r) = <coeffs, y>, ie. [1, r_1, ..., r_1^d_1, ..., r_n, ..., r_n^d_n]
+    fn monomials(degrees: &[usize], point: &[Scalar]) -> Vec<Scalar> {
+        let mut y = vec![Scalar::one()];
+        for (d, r) in degrees.iter().zip(point) {
+            y.extend((1..=*d).map(|e| pow(*r, e)));
+        }
+        y
+    }
+}
+
+// R_i(x_1, w) = <coeffs, [1, w, x_1, x_1·w, x_1^2, x_1^2·w]>
+fn mask_monomials(x: Scalar, w: Scalar) -> Vec<Scalar> {
+    let x_2 = x.square();
+    vec![Scalar::one(), w, x, x * w, x_2, x_2 * w]
+}
+
+// S_i(x_1) = R_i(x_1, 0) + R_i(x_1, 1)
+fn mask_sum(r: &[Scalar]) -> Polynomial {
+    Polynomial::from_coeffs(vec![
+        r[0].double() + r[1],
+        r[2].double() + r[3],
+        r[4].double() + r[5],
+    ])
+}
+
+// The degrees of the round polys of layer i, where W_i+1 has k vars.
+// With the masked Ẇ_i+1, the last round of each phase has degree 5 at most, see `LibraProver`.
+fn round_degrees(k: usize, masked: bool) -> Vec<usize> {
+    (1..=2 * k)
+        .map(|j| match masked && (j == k || j == 2 * k) {
+            true => 5,
+            false => 2,
+        })
+        .collect()
+}
+
+// The layer 1 to d-2 are masked, but not the outputs and inputs.
+fn is_masked(circuit: &CircuitConfig, i: usize) -> bool {
+    i > 0 && i < circuit.depth - 1
+}
+
+fn pow(x: Scalar, e: usize) -> Scalar {
+    x.pow_vartime(&[e as u64, 0, 0, 0])
+}
+
+fn half() -> Scalar {
+    Scalar::from(2).invert().unwrap()
+}
+
+fn inner_product(a: &[Scalar], b: &[Scalar]) -> Scalar {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+fn random_vec(n: usize) -> Vec<Scalar> {
+    (0..n).map(|_| Scalar::random(OsRng)).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::arithmetic::layered_circuit::Layer;
+    use crate::arithmetic::layered_circuit::Ops::{ADD, MUL};
+    use ff::PrimeField;
+
+    #[test]
+    fn test_mask_poly() {
+        let degrees = vec![2, 5, 2];
+        let g = MaskPoly::random(degrees.clone());
+        let evaluate = |x: &[Scalar]| {
+            g.coeffs[0] + (1..=3).map(|j| g.g_j(j).evaluate(x[j - 1])).sum::<Scalar>()
+        };
+
+        let hypercube = (0..8)
+            .map(|n| [4, 2, 1].map(|bit| Scalar::from((n & bit != 0) as u64)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            g.sum(),
+            hypercube.iter().map(|x| evaluate(x)).sum::<Scalar>()
+        );
+
+        // G_2(X) at X = 7, with r_1 = 3
+        let (r_1, x) = (Scalar::from(3), Scalar::from(7));
+        let expected = [0, 1]
+            .map(|x_3| evaluate(&[r_1, x, Scalar::from(x_3)]))
+            .iter()
+            .sum::<Scalar>();
+        assert_eq!(g.partial_sum(&[r_1], x), expected);
+
+        let r = random_vec(3);
+        let y = MaskPoly::monomials(&degrees, &r);
+        assert_eq!(inner_product(&g.coeffs, &y), evaluate(&r));
+    }
+
+    #[test]
+    fn test_zk_gkr() {
+        // 3 layers of gates, so that layer 1 and 2 are masked.
+        let layer = |gates: Vec<_>| Layer {
+            var_num: gates.len().trailing_zeros() as usize,
+            gates,
+        };
+        let config = CircuitConfig {
+            layers: vec![
+                layer(vec![MUL(0, 1), ADD(1, 1)]),
+                layer(vec![MUL(0, 0), ADD(0, 1)]),
+                layer(vec![MUL(0, 1), MUL(1, 2), ADD(2, 3), MUL(3, 0)]),
+            ],
+            input_var_num: 2,
+            depth: 4,
+        };
+        let inputs = [1, 2, 3, 4].map(Scalar::from_u128).to_vec();
+        let outputs = config.evaluate(&inputs);
+
+        let pedersen = setup(&config);
+        let proof = prove(&pedersen, &config, &inputs);
+        assert_eq!(proof.mask_commitments.len(), 2);
+        assert_eq!(
+            verify(&pedersen, &config, &inputs, &outputs, &proof),
+            Ok(())
+        );
+
+        // the proof is shared as json.
+        let json = serde_json::to_string(&proof).unwrap();
+        let decoded: ZkGkrProof = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, proof);
+        assert!(serde_json::from_str::<ZkGkrProof>(&json.replacen("\"0x", "\"0x1", 1)).is_err());
+
+        // P proves again with new masks, so that the messages are different.
+        let other = prove(&pedersen, &config, &inputs);
+        assert_ne!(other.layers[1].w_b, proof.layers[1].w_b);
+        assert_ne!(other.layers[1].round_polys, proof.layers[1].round_polys);
+
+        let mut wrong_outputs = outputs.clone();
+        wrong_outputs[0] += Scalar::one();
+        assert!(verify(&pedersen, &config, &inputs, &wrong_outputs, &proof).is_err());
+
+        // the claimed Ẇ_2(b*) is changed.
+        let mut wrong_proof = proof.clone();
+        wrong_proof.layers[1].w_b += Scalar::one();
+        assert!(verify(&pedersen, &config, &inputs, &outputs, &wrong_proof).is_err());
+
+        // the opening of R_1 is changed.
+        let mut wrong_proof = proof;
+        wrong_proof.layers[1].mask_openings[0].value += Scalar::one();
+        assert!(verify(&pedersen, &config, &inputs, &outputs, &wrong_proof).is_err());
+    }
+
+    #[test]
+    fn test_zk_gkr_random_circuits() {
+        for var_nums in [vec![1, 1], vec![1, 2, 1], vec![2, 1, 3, 2]] {
+            let config = CircuitConfig::random(&var_nums, &mut OsRng);
+            let inputs = random_vec(1 << var_nums[var_nums.len() - 1]);
+            let outputs = config.evaluate(&inputs);
+
+            let pedersen = setup(&config);
+            let proof = prove(&pedersen, &config, &inputs);
+            assert_eq!(
+                verify(&pedersen, &config, &inputs, &outputs, &proof),
+                Ok(())
+            );
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use crate::arithmetic::circuit_file::scalar_str;
+use crate::utils::{append_scalars, challenge_scalar};
+use bls12_381::{G1Projective, Scalar};
+use ff::Field;
+use group::Curve;
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+use sumcheck::commitment::{hash_to_generators, msm};
+use Fiat_Shamir::Transcript;
+
+// Pedersen commitment to a vector a, which is hiding by the blind s:
+//      C = ∑a_j·G_j + s·H
+// with a Σ-protocol (made non-interactive by the transcript) to open <a, y> = value for a public y,
+// while a stays hidden:
+//  1. P samples d, δ, and sends D = ∑d_j·G_j + δ·H and e = <d, y>.
+//  2. V sends the challenge c.
+//  3. P sends z = c·a + d and z_δ = c·s + δ.
+//  4. V checks ∑z_j·G_j + z_δ·H = c·C + D and <z, y> = c·value + e.
+#[derive(Clone, Debug)]
+pub struct Pedersen {
+    generators: Vec<G1Projective>, // G_0, ..., G_n-1
+    h: G1Projective,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DotProductProof {
+    #[serde(with = "scalar_str")]
+    pub value: Scalar, // <a, y>
+    #[serde(with = "point_hex")]
+    d: G1Projective,
+    #[serde(with = "scalar_str")]
+    e: Scalar,
+    #[serde(with = "scalar_str::vec")]
+    z: Vec<Scalar>,
+    #[serde(with = "scalar_str")]
+    z_blind: Scalar,
+}
+
+impl Pedersen {
+    // Commit to the vectors with n elements at most.
+    // G_j and H are hashed to the curve, as the binding breaks once P knows a discrete log between them.
+    pub fn setup(n: usize) -> Self {
+        let mut generators = hash_to_generators(b"pedersen", n + 1);
+        let h = generators.pop().unwrap();
+        Self { generators, h }
+    }
+
+    pub fn commit(&self, a: &[Scalar], blind: &Scalar) -> G1Projective {
+        assert!(a.len() <= self.generators.len(), "The vector is too long");
+        msm(a, &self.generators[..a.len()]) + self.h * blind
+    }
+
+    pub fn open(
+        &self,
+        a: &[Scalar],
+        blind: &Scalar,
+        y: &[Scalar],
+        transcript: &mut impl Transcript,
+    ) -> DotProductProof {
+        assert_eq!(a.len(), y.len());
+        let value = inner_product(a, y);
+        append_scalars(transcript, &[value]);
+
+        let d_vec = (0..a.len())
+            .map(|_| Scalar::random(OsRng))
+            .collect::<Vec<_>>();
+        let d_blind = Scalar::random(OsRng);
+        let d = self.commit(&d_vec, &d_blind);
+        let e = inner_product(&d_vec, y);
+        append_point(transcript, &d);
+        append_scalars(transcript, &[e]);
+
+        let c = challenge_scalar(transcript);
+        DotProductProof {
+            value,
+            d,
+            e,
+            z: a.iter().zip(d_vec).map(|(a, d)| c * a + d).collect(),
+            z_blind: c * blind + d_blind,
+        }
+    }
+
+    // Check <a, y> = proof.value for the a in the commitment.
+    pub fn verify(
+        &self,
+        commitment: &G1Projective,
+        y: &[Scalar],
+        proof: &DotProductProof,
+        transcript: &mut impl Transcript,
+    ) -> Result<(), String> {
+        if proof.z.len() != y.len() || y.len() > self.generators.len() {
+            return Err("Pedersen: wrong length of the opening".to_string());
+        }
+        append_scalars(transcript, &[proof.value]);
+        append_point(transcript, &proof.d);
+        append_scalars(transcript, &[proof.e]);
+
+        let c = challenge_scalar(transcript);
+        if self.commit(&proof.z, &proof.z_blind) != commitment * c + proof.d {
+            return Err("Pedersen: the opening doesn't match the commitment".to_string());
+        }
+        if inner_product(&proof.z, y) != c * proof.value + proof.e {
+            return Err("Pedersen: wrong inner product".to_string());
+        }
+        Ok(())
+    }
+}
+
+pub fn append_point(transcript: &mut impl Transcript, point: &G1Projective) {
+    transcript.append(&point.to_affine().to_compressed());
+}
+
+fn inner_product(a: &[Scalar], b: &[Scalar]) -> Scalar {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+// G1Projective <-> the 0x-prefixed hex of its compressed form, which is checked on the curve and in the subgroup.
+pub(crate) mod point_hex {
+    use bls12_381::{G1Affine, G1Projective};
+    use group::Curve;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn to_str(point: &G1Projective) -> String {
+        let bytes = point.to_affine().to_compressed();
+        let hex = bytes
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+        format!("0x{}", hex)
+    }
+
+    pub fn from_str(s: &str) -> Option<G1Projective> {
+        let hex = s.strip_prefix("0x")?;
+        if hex.len() != 96 || !hex.is_ascii() {
+            return None;
+        }
+        let mut bytes = [0u8; 48];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok()?;
+        }
+        Option::<G1Affine>::from(G1Affine::from_compressed(&bytes)).map(G1Projective::from)
+    }
+
+    pub fn serialize<S: Serializer>(
+        point: &G1Projective,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&to_str(point))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<G1Projective, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        from_str(&s).ok_or_else(|| D::Error::custom(format!("Invalid point: {}", s)))
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(transparent)]
+    struct Str(#[serde(with = "super::point_hex")] G1Projective);
+
+    // Vec<G1Projective>, eg: the commitments to the masks.
+    pub mod vec {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(
+            v: &[G1Projective],
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(v.iter().map(to_str))
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Vec<G1Projective>, D::Error> {
+            let v = Vec::<Str>::deserialize(deserializer)?;
+            Ok(v.into_iter().map(|s| s.0).collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use Fiat_Shamir::default::Keccak256Transcript;
+
+    fn random_vec(n: usize) -> Vec<Scalar> {
+        (0..n).map(|_| Scalar::random(OsRng)).collect()
+    }
+
+    #[test]
+    fn test_dot_product_proof() {
+        let pedersen = Pedersen::setup(6);
+        let (a, blind) = (random_vec(5), Scalar::random(OsRng));
+        let commitment = pedersen.commit(&a, &blind);
+
+        let y = random_vec(5);
+        let proof = pedersen.open(&a, &blind, &y, &mut Keccak256Transcript::default());
+        assert_eq!(proof.value, inner_product(&a, &y));
+        let verify = |proof: &DotProductProof, commitment: &G1Projective| {
+            pedersen.verify(commitment, &y, proof, &mut Keccak256Transcript::default())
+        };
+        assert_eq!(verify(&proof, &commitment), Ok(()));
+
+        // a wrong value
+        let mut wrong_proof = proof.clone();
+        wrong_proof.value += Scalar::one();
+        assert!(verify(&wrong_proof, &commitment).is_err());
+
+        // the commitment to another vector.
+        let other = pedersen.commit(&random_vec(5), &blind);
+        assert_eq!(
+            verify(&proof, &other),
+            Err("Pedersen: the opening doesn't match the commitment".to_string())
+        );
+    }
+}
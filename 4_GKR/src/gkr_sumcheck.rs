@@ -1,11 +1,16 @@
-use crate::gkr_sumcheck::prover::Prover;
-use crate::gkr_sumcheck::verifier::Verifier;
+use crate::arithmetic::circuit_file::scalar_str;
+use crate::arithmetic::layered_circuit::Wiring;
+use crate::gkr_sumcheck::data_parallel_prover::DataParallelProver;
+use crate::gkr_sumcheck::libra_prover::LibraProver;
+use crate::gkr_sumcheck::verifier::{f_r_at, Verifier};
 use crate::poly::{MPolynomial, Polynomial};
+use crate::utils::{append_scalars, challenge_scalar, eq_at, eq_table};
 use bls12_381::Scalar;
-use ff::PrimeField;
-use std::env::var;
-use std::iter::Sum;
+use serde::{Deserialize, Serialize};
+use Fiat_Shamir::Transcript;
 
+pub mod data_parallel_prover;
+pub mod libra_prover;
 pub mod prover;
 pub mod verifier;
 
@@ -17,36 +22,134 @@ pub mod verifier;
 //      so it only sums up  the evaluations of a multi-polynomial over partial(the variable part) Boolean inputs
 // 2. The evaluated poly:
 //      The standard one evaluated only on one poly.
-//      The gkr one evaluted on three polys-add, mult, W_i+1
+//      The gkr one evaluted on the wiring predicates and W_i+1
 pub struct GkrSumCheck {
     // v_l: usize, // the constants_part var_num.  v_l + v_r = ki + 2*k_i_plus_1
     v_r: usize, // the variable_part var_num. equals to `v` in standard sumcheck.
-    // r_i: Vec<usize>,
+    p: usize,   // the var_num of the copy index in a data-parallel circuit, 0 otherwise.
+    r_i: Points,
     // layer_i: usize, // the gkr layer index. [0,d)
-    prover: Prover,
+    ops: Wiring, // V's own wiring predicates, as the wiring only depends on the circuit.
+    prover: Box<dyn LayerProver>,
     verifier: Verifier,
+    reduction: ClaimReduction,
 }
 
-//  (add, mult, w_i_plus_1)
-type F_r_Poly = (MPolynomial, MPolynomial, MPolynomial);
+//  (wiring, w_i_plus_1)
+type F_r_Poly = (Wiring, MPolynomial);
+
+// The weighted points (α, r) of the claim ∑α·W_i(r) = m_i, eg: [(1, r_i)] for m_i = W_i(r_i).
+pub type Points = Vec<(Scalar, Vec<Scalar>)>;
+
+// How the claims W_i+1(u) and W_i+1(v) at the end of the sumcheck of layer i are reduced to the one of layer i+1.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClaimReduction {
+    // P sends q = W_i+1 ∘ l of degree k_i+1, with the line l(0) = u and l(1) = v,
+    // and the claim of layer i+1 is W_i+1(l(t)) = q(t) for a random t.
+    #[default]
+    Line,
+    // P sends W_i+1(u) and W_i+1(v) only, and the claim of layer i+1 is α·W_i+1(u) + β·W_i+1(v) for random α, β,
+    // whose sumcheck is on the eq-weighted predicates, eg: α·add_i+1(u,b,c) + β·add_i+1(v,b,c).
+    // V evaluates the predicates at two points, and P computes the eq table of two points instead of q.
+    RandomCombination,
+}
+
+impl ClaimReduction {
+    // The tag absorbed by the transcript, so that the challenges depend on the reduction.
+    pub fn label(&self) -> &'static [u8] {
+        match self {
+            ClaimReduction::Line => b"line",
+            ClaimReduction::RandomCombination => b"random_combination",
+        }
+    }
+}
+
+// The messages of P in the sumcheck of a layer.
+pub trait LayerProver: Send {
+    // Return g1(X) = sum f_{r_i}(X, x_2, ..., x_v)
+    fn round_1(&self) -> Polynomial;
+
+    // 1 < j < v_r, total v_r-2 rounds
+    // Return g_j = f_{r_i}(r1, ..., r_j-1, X, x_j+1, ..., x_v), after folding with r_j-1.
+    fn recursive_round_j(&mut self, challenges: &[Scalar]) -> Polynomial;
+
+    // Return g_v = f_{r_i}(r1, r2, ..., r_v-1, X_v)
+    fn round_v(&mut self, challenges: &[Scalar]) -> Polynomial;
+
+    // Return q = W_i+1 ∘ l, where l is the line through the two points of W_i+1 in f_{r_i}(r1, ..., r_v).
+    fn evaluate(&self, challenges: &[Scalar]) -> Polynomial;
+
+    // Return q of degree 1, with q(0) and q(1) the values of W_i+1 at the two points only.
+    fn evaluate_ends(&self, challenges: &[Scalar]) -> Polynomial;
+}
+
+// The messages of P in the non-interactive sumcheck of layer i:
+//  the coeffs of g_1, ..., g_v, and q, where q(0) = W_i+1(u) and q(1) = W_i+1(v) are the claimed W_i+1 values,
+//  and q = W_i+1 ∘ l for `ClaimReduction::Line`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LayerProof {
+    #[serde(with = "scalar_str::vecs")]
+    pub round_polys: Vec<Vec<Scalar>>,
+    #[serde(with = "scalar_str::vec")]
+    pub q: Vec<Scalar>,
+}
 
 impl GkrSumCheck {
-    pub fn init(g: F_r_Poly, r_i: Vec<usize>, m_i: Scalar) -> Self {
-        assert_eq!(g.0.var_num, g.1.var_num);
-        let (v_l, v_r) = (r_i.len(), 2 * g.2.var_num);
-        assert_eq!(g.0.var_num, v_l + v_r);
+    pub fn init(g: F_r_Poly, r_i: Vec<Scalar>, m_i: Scalar) -> Self {
+        Self::data_parallel(g, r_i, m_i, 0)
+    }
+
+    // The sumcheck of a layer with 2^p copies of the sub-circuit one, see `DataParallelCircuit`:
+    //      m_i = ∑eq(r_j, j)·f'_{r'}(j,b,c), (j,b,c) in {0,1}^(p+2k)
+    // where r_i = (r_j, r'), and the wiring in g is the sub-circuit one, with W_i+1 of all copies.
+    pub fn data_parallel(g: F_r_Poly, r_i: Vec<Scalar>, m_i: Scalar, p: usize) -> Self {
+        Self::new(g, vec![(Scalar::one(), r_i)], m_i, p)
+    }
 
-        let prover = Prover::new(g, r_i);
+    // The sumcheck on the claim ∑α·W_i(r) = m_i over the weighted points (α, r), which is
+    //      m_i = ∑∑α·f_r(b,c) = ∑f_{eq}(b,c), (b,c) in {0,1}^2k
+    // where f_{eq} is with the eq-weighted predicates, eg: ∑α·add_i(r,b,c) = ∑(∑α·eq(r,a))·add_i(a,b,c).
+    // Only one point with α = 1 for a data-parallel circuit.
+    pub fn new(g: F_r_Poly, r_i: Points, m_i: Scalar, p: usize) -> Self {
+        let k = g.0.var_nums.1;
+        let v_r = p + 2 * k;
+        assert_eq!(g.1.var_num, p + k);
+        assert!(r_i
+            .iter()
+            .all(|(_, r)| g.0.var_num() + p == r.len() + 2 * k));
+        assert!(v_r >= 2, "Layer i+1 should have at least 2 gates");
+
+        let ops = g.0.clone();
+        let prover: Box<dyn LayerProver> = match p {
+            0 => Box::new(LibraProver::with_eq_table(g, combine_eq(&r_i))),
+            _ => {
+                assert!(
+                    r_i.len() == 1 && r_i[0].0 == Scalar::one(),
+                    "Only one point for a data-parallel circuit"
+                );
+                Box::new(DataParallelProver::new(g, r_i[0].1.clone(), p))
+            }
+        };
         let verifier = Verifier::new(v_r, m_i);
 
         Self {
             v_r,
+            p,
+            r_i,
+            ops,
             prover,
             verifier,
+            reduction: ClaimReduction::default(),
         }
     }
 
-    pub fn run_protocol(&mut self) -> (Vec<usize>, Scalar) {
+    pub fn with_reduction(mut self, reduction: ClaimReduction) -> Self {
+        self.reduction = reduction;
+        self
+    }
+
+    pub fn run_protocol(&mut self) -> (Points, Scalar) {
         // round 1
         let g1 = self.prover.round_1();
         self.verifier.round_1(g1);
@@ -56,31 +159,196 @@ impl GkrSumCheck {
             let challenges = self.verifier.challenges();
             let g_j = self.prover.recursive_round_j(&challenges);
             self.verifier.recursive_round_j(j, g_j);
-            drop(challenges);
         }
 
         // round v
         let challenges = self.verifier.challenges();
         let g_v = self.prover.round_v(&challenges);
         self.verifier.round_v(g_v);
-        // drop(challenges);
 
         // finally check
+        //  P sends q, with q(0) = W_i+1(j,u) and q(1) = W_i+1(j,v).
+        //  V evaluates the wiring predicates at (r_i,u,v) by itself,
+        //  which are eq(r_j, j) times the sub-circuit ones at (r',u,v) for a data-parallel circuit.
         let challenges = self.verifier.challenges();
-        let (add_value, mult_value, l_polys, p_poly) = self.prover.evaluate(&challenges);
-        self.verifier.check((add_value, mult_value, &p_poly));
+        let (j, uv) = challenges.split_at(self.p);
+        let q_poly = self.reduction.q(self.prover.as_ref(), &challenges);
+        assert!(
+            q_poly.degree() <= self.reduction.q_degree(uv.len() / 2),
+            "GKR verifier: q has a too high degree"
+        );
+        self.verifier
+            .check(ops_at(&self.ops, &self.r_i, j, uv), &q_poly);
 
-        // Prepare for next sumcheck:
-        //  V chooses random t and sets r_{i+1} = l(t) and mi+1 = q(r_{i+1})=q(l(t)).
-        let t = Verifier::gen_challenge();
-        let r_1_plus_1 = l_polys
-            .iter()
-            .map(|l_i| l_i.iter().rev().fold(0, |acc, coeff| acc * t + *coeff))
-            .collect::<Vec<_>>();
-        let m_i_plus_1 = p_poly.evaluate(Scalar::from_u128(t as u128));
+        // Prepare for next sumcheck, with the random challenges of V.
+        self.reduction
+            .reduce(j, uv, &q_poly, Verifier::gen_challenge)
+    }
+
+    // The same as `run_protocol`, but r_1, ..., r_v and the challenges of the reduction are derived
+    // from the transcript, which absorbs each message of P.
+    // Return the proof, and the points of the claim for the next layer.
+    pub fn prove(
+        g: F_r_Poly,
+        r_i: Points,
+        reduction: ClaimReduction,
+        transcript: &mut impl Transcript,
+    ) -> (LayerProof, Points) {
+        let v_r = 2 * g.1.var_num;
+        let mut prover = LibraProver::with_eq_table(g, combine_eq(&r_i));
+
+        let mut round_polys = vec![];
+        let mut challenges = vec![];
+        for j in 1..=v_r {
+            let g_j = match j {
+                1 => prover.round_1(),
+                _ if j == v_r => prover.round_v(&challenges),
+                _ => prover.recursive_round_j(&challenges),
+            };
+            append_scalars(transcript, &g_j.coeffs());
+            challenges.push(challenge_scalar(transcript));
+            round_polys.push(g_j.coeffs());
+        }
+
+        let q_poly = reduction.q(&prover, &challenges);
+        let q = q_poly.coeffs();
+        append_scalars(transcript, &q);
+        let (r_i_plus_1, _) =
+            reduction.reduce(&[], &challenges, &q_poly, || challenge_scalar(transcript));
 
-        (r_1_plus_1, m_i_plus_1)
+        (LayerProof { round_polys, q }, r_i_plus_1)
     }
+
+    // V checks the proof of layer i on the claim ∑α·W_i(r) = m_i, with its own wiring predicates.
+    // Return the claim for the next layer.
+    pub fn verify(
+        wiring: &Wiring,
+        r_i: &Points,
+        m_i: Scalar,
+        reduction: ClaimReduction,
+        proof: &LayerProof,
+        transcript: &mut impl Transcript,
+    ) -> Result<(Points, Scalar), String> {
+        assert!(r_i.iter().all(|(_, r)| wiring.var_nums.0 == r.len()));
+        let v_r = 2 * wiring.var_nums.1;
+        if proof.round_polys.len() != v_r {
+            return Err(format!(
+                "Expect {} round polys, got {}",
+                v_r,
+                proof.round_polys.len()
+            ));
+        }
+
+        // g_j(0) + g_j(1) = g_j-1(r_j-1), with g_0(r_0) = m_i
+        let mut claim = m_i;
+        let mut challenges = vec![];
+        for (j, coeffs) in proof.round_polys.iter().enumerate() {
+            // deg(g_j) <= 2
+            if coeffs.is_empty() || coeffs.len() > 3 {
+                return Err(format!("g_{} has a wrong degree", j + 1));
+            }
+            let g_j = Polynomial::from_coeffs(coeffs.clone());
+            if g_j.evaluate(Scalar::zero()) + g_j.evaluate(Scalar::one()) != claim {
+                return Err(format!("Not-equal in round_{}", j + 1));
+            }
+
+            append_scalars(transcript, coeffs);
+            let r_j = challenge_scalar(transcript);
+            claim = g_j.evaluate(r_j);
+            challenges.push(r_j);
+        }
+
+        // g_v(r_v) = f_{r_i}(u,v), with W_i+1(u) = q(0) and W_i+1(v) = q(1).
+        if proof.q.is_empty() || proof.q.len() > reduction.q_degree(v_r / 2) + 1 {
+            return Err("q has a wrong degree".to_string());
+        }
+        let q_poly = Polynomial::from_coeffs(proof.q.clone());
+        let target = f_r_at(
+            ops_at(wiring, r_i, &[], &challenges),
+            q_poly.evaluate(Scalar::zero()),
+            q_poly.evaluate(Scalar::one()),
+        );
+        if claim != target {
+            return Err("Verifier rejected the proof".to_string());
+        }
+
+        append_scalars(transcript, &proof.q);
+        Ok(reduction.reduce(&[], &challenges, &q_poly, || challenge_scalar(transcript)))
+    }
+}
+
+impl ClaimReduction {
+    // q of P, from which V reads W_i+1(j,u) = q(0) and W_i+1(j,v) = q(1).
+    fn q(&self, prover: &dyn LayerProver, challenges: &[Scalar]) -> Polynomial {
+        match self {
+            ClaimReduction::Line => prover.evaluate(challenges),
+            ClaimReduction::RandomCombination => prover.evaluate_ends(challenges),
+        }
+    }
+
+    // The degree of q, where k is the var_num of u and v.
+    fn q_degree(&self, k: usize) -> usize {
+        match self {
+            ClaimReduction::Line => k,
+            ClaimReduction::RandomCombination => 1,
+        }
+    }
+
+    // Reduce the claims q(0) = W_i+1(j,u) and q(1) = W_i+1(j,v) to the claim of the next layer.
+    fn reduce(
+        &self,
+        j: &[Scalar],
+        uv: &[Scalar],
+        q_poly: &Polynomial,
+        mut challenge: impl FnMut() -> Scalar,
+    ) -> (Points, Scalar) {
+        let (u, v) = uv.split_at(uv.len() / 2);
+        match self {
+            // V chooses random t and sets r_{i+1} = l(t) and mi+1 = q(r_{i+1})=q(l(t)).
+            ClaimReduction::Line => {
+                let t = challenge();
+                let r_i_plus_1 = [j, &line(u, v, t)].concat();
+                (vec![(Scalar::one(), r_i_plus_1)], q_poly.evaluate(t))
+            }
+            // V chooses random α, β and sets mi+1 = α·q(0) + β·q(1) on the points (j,u), (j,v).
+            ClaimReduction::RandomCombination => {
+                let (alpha, beta) = (challenge(), challenge());
+                let m_i_plus_1 =
+                    alpha * q_poly.evaluate(Scalar::zero()) + beta * q_poly.evaluate(Scalar::one());
+                let points = vec![(alpha, [j, u].concat()), (beta, [j, v].concat())];
+                (points, m_i_plus_1)
+            }
+        }
+    }
+}
+
+// ∑α·eq(r_j, j)·(add_i, mult_i, ...)(r', u, v) over the weighted points (α, (r_j, r')) of the claim.
+fn ops_at(wiring: &Wiring, r_i: &Points, j: &[Scalar], uv: &[Scalar]) -> [Scalar; 6] {
+    r_i.iter().fold([Scalar::zero(); 6], |acc, (alpha, point)| {
+        let (r_j, r) = point.split_at(j.len());
+        let e = alpha * eq_at(r_j, j);
+        let ops = wiring.evaluate(&[r, uv].concat());
+        std::array::from_fn(|t| acc[t] + e * ops[t])
+    })
+}
+
+// ∑α·eq(r, a) over the weighted points (α, r), indexed by a.
+pub(crate) fn combine_eq(points: &[(Scalar, Vec<Scalar>)]) -> Vec<Scalar> {
+    let mut table = vec![Scalar::zero(); 1 << points[0].1.len()];
+    for (alpha, r) in points {
+        for (t, e) in table.iter_mut().zip(eq_table(r)) {
+            *t += alpha * e;
+        }
+    }
+    table
+}
+
+// l(t) = u + t·(v - u), the line with l(0) = u and l(1) = v.
+pub(crate) fn line(u: &[Scalar], v: &[Scalar], t: Scalar) -> Vec<Scalar> {
+    u.iter()
+        .zip(v)
+        .map(|(u_i, v_i)| u_i + t * (v_i - u_i))
+        .collect()
 }
 
 // #[cfg(test)]
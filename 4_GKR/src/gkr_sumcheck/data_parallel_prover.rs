@@ -0,0 +1,229 @@
+use crate::arithmetic::layered_circuit::Wiring;
+use crate::gkr_sumcheck::libra_prover::LibraProver;
+use crate::gkr_sumcheck::{F_r_Poly, LayerProver};
+use crate::poly::{MPolynomial, Polynomial};
+use crate::utils::eq_table;
+use bls12_381::Scalar;
+use rayon::prelude::*;
+
+// The prover of a layer of the data-parallel circuit with 2^p copies, see `DataParallelCircuit`, on
+//      ∑eq(r_j, j)·f'_{r'}(j,b',c') = W_i(r_j, r'), (j,b',c') in {0,1}^(p+2k)
+// where r_i = (r_j, r') and f' is with the sub-circuit wiring and W_i+1(j, ·).
+//
+// Phase 0: bind j in the first p rounds. With j = (r_1, ..., r_j-1, X, x), each
+//      eq(r_j, j)·∑f'_{r'}(b',c') over (b',c') in {0,1}^2k
+// is a sum over the sparse sub-circuit wiring with W_i+1(r_1, ..., r_j-1, X, x, ·), so that the round j costs
+// O(2^(p-j)·S'), and the phase costs O(2^p·S'), ie: linear in the number of copies.
+// Phase 1,2: with j bound to r*, the rest is the sumcheck of the sub-circuit on W_i+1(r*, ·),
+//      eq(r_j, r*)·∑f'_{r'}(b',c') over (b',c') in {0,1}^2k
+// which is run by `LibraProver`, with eq(r_j, r*) put into its eq table.
+pub struct DataParallelProver {
+    p: usize,                   // the var_num of the copy index.
+    k: usize,                   // k'_i+1, the var_num of b' and c'.
+    wiring: Wiring,             // the sub-circuit predicates.
+    eq_r: Vec<Scalar>,          // eq(r', a')
+    eq_j: Vec<Scalar>,          // eq(r_j, j), with j so far bound to r_1, ..., r_j-1.
+    w: Vec<Scalar>,             // W_i+1(j, b'), with j so far bound to r_1, ..., r_j-1.
+    libra: Option<LibraProver>, // the sumcheck on (b', c'), after j is bound.
+}
+
+impl DataParallelProver {
+    pub fn new((wiring, w_i_plus_1): F_r_Poly, r_i: Vec<Scalar>, p: usize) -> Self {
+        let k = wiring.var_nums.1;
+        assert!(p > 0, "No copy variables, use `LibraProver` instead");
+        assert_eq!(w_i_plus_1.var_num, p + k);
+        assert_eq!(r_i.len(), p + wiring.var_nums.0);
+
+        let (r_j, r) = r_i.split_at(p);
+        Self {
+            p,
+            k,
+            eq_r: eq_table(r),
+            eq_j: eq_table(r_j),
+            w: w_i_plus_1.evals(),
+            wiring,
+            libra: None,
+        }
+    }
+
+    // m_i = ∑eq(r_j, j)·W_i(j, r') over j in {0,1}^p
+    pub fn proof(&self) -> Scalar {
+        let n = 1 << self.k;
+        (0..self.eq_j.len())
+            .map(|j| self.eq_j[j] * self.sub_sum(|b| self.w[j * n + b]))
+            .sum()
+    }
+
+    // ∑f'_{r'}(b',c') over (b',c') in {0,1}^2k with W_i+1(j, ·) = w, from the sparse sub-circuit wiring.
+    fn sub_sum(&self, w: impl Fn(usize) -> Scalar) -> Scalar {
+        let weight = |a: &usize, value: &Scalar| self.eq_r[*a] * value;
+        let wiring = &self.wiring;
+        let add: Scalar = (wiring.add.entries.iter())
+            .map(|(a, b, c, value)| weight(a, value) * (w(*b) + w(*c)))
+            .sum();
+        let mult: Scalar = (wiring.mult.entries.iter())
+            .map(|(a, b, c, value)| weight(a, value) * w(*b) * w(*c))
+            .sum();
+        let sub: Scalar = (wiring.sub.entries.iter())
+            .map(|(a, b, c, value)| weight(a, value) * (w(*b) - w(*c)))
+            .sum();
+        let cnst: Scalar = (wiring.cnst.entries.iter())
+            .map(|(a, _, _, value)| weight(a, value))
+            .sum();
+        let unary: Scalar = (wiring.cmul.entries.iter().chain(&wiring.id.entries))
+            .map(|(a, b, _, value)| weight(a, value) * w(*b))
+            .sum();
+        add + mult + sub + cnst + unary
+    }
+
+    // g_j(X) = ∑eq(r_j, (bound, X, x))·∑f'_{r'}(b',c') with W_i+1(bound, X, x, ·), x in {0,1}^(p-j),
+    // which has degree 3, so it's interpolated from g_j(0), ..., g_j(3).
+    // The copies x are independent, so they're summed in parallel.
+    fn round_poly(&self) -> Polynomial {
+        let (half, n) = (self.eq_j.len() / 2, 1 << self.k);
+        let xs = (0..4).map(|x| Scalar::from(x as u64)).collect::<Vec<_>>();
+        let evals = xs
+            .iter()
+            .map(|x| {
+                (0..half)
+                    .into_par_iter()
+                    .map(|i| {
+                        // T(X, x) = T(0, x) + X·(T(1, x) - T(0, x))
+                        let eq = self.eq_j[i] + x * (self.eq_j[half + i] - self.eq_j[i]);
+                        let (lo, hi) = (&self.w[i * n..], &self.w[(half + i) * n..]);
+                        eq * self.sub_sum(|b| lo[b] + x * (hi[b] - lo[b]))
+                    })
+                    .sum()
+            })
+            .collect::<Vec<Scalar>>();
+        Polynomial::lagrange_interpolate(xs, evals)
+    }
+
+    // Bind the highest variable of j to r.
+    fn fold(&mut self, r: Scalar) {
+        for table in [&mut self.eq_j, &mut self.w] {
+            let (lo, hi) = table.split_at(table.len() / 2);
+            *table = lo.iter().zip(hi).map(|(l, h)| l + r * (h - l)).collect();
+        }
+    }
+}
+
+impl LayerProver for DataParallelProver {
+    fn round_1(&self) -> Polynomial {
+        assert_eq!(self.eq_j.len(), 1 << self.p);
+        self.round_poly()
+    }
+
+    fn recursive_round_j(&mut self, challenges: &[Scalar]) -> Polynomial {
+        let j = challenges.len() + 1;
+        assert!(self.p + 2 * self.k >= j && j > 1);
+
+        if j > self.p + 1 {
+            let libra = self.libra.as_mut().expect("j should be bound");
+            return libra.recursive_round_j(&challenges[self.p..]);
+        }
+        self.fold(*challenges.last().unwrap());
+        if j <= self.p {
+            return self.round_poly();
+        }
+
+        // j is bound to r*, turn to the sub-circuit sumcheck on W_i+1(r*, ·), with eq(r_j, r*)·eq(r', a').
+        let eq_r = self.eq_r.iter().map(|e| e * self.eq_j[0]).collect();
+        let g = (
+            self.wiring.clone(),
+            MPolynomial::from_evals(self.k, &self.w),
+        );
+        let libra = LibraProver::with_eq_table(g, eq_r);
+        let g_j = libra.round_1();
+        self.libra = Some(libra);
+        g_j
+    }
+
+    fn round_v(&mut self, challenges: &[Scalar]) -> Polynomial {
+        assert_eq!(self.p + 2 * self.k - 1, challenges.len());
+        self.recursive_round_j(challenges)
+    }
+
+    // Return q = W_i+1(r*, ·) ∘ l, where l is the line with l(0)=u and l(1)=v, as W_i+1 is at (r*,u) and (r*,v).
+    fn evaluate(&self, challenges: &[Scalar]) -> Polynomial {
+        let libra = self.libra.as_ref().expect("j should be bound");
+        libra.evaluate(&challenges[self.p..])
+    }
+
+    fn evaluate_ends(&self, challenges: &[Scalar]) -> Polynomial {
+        let libra = self.libra.as_ref().expect("j should be bound");
+        libra.evaluate_ends(&challenges[self.p..])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::arithmetic::data_parallel::DataParallelCircuit;
+    use crate::arithmetic::layered_circuit::CircuitConfig;
+    use crate::gkr_sumcheck::verifier::f_r_at;
+    use crate::utils::eq_at;
+    use ff::Field;
+    use rand_core::OsRng;
+
+    #[test]
+    fn test_data_parallel_sumcheck() {
+        for (var_nums, copies) in [(vec![1, 1], 2), (vec![2, 1], 4), (vec![1, 2], 8)] {
+            let circuit =
+                DataParallelCircuit::new(CircuitConfig::random(&var_nums, &mut OsRng), copies);
+            let p = circuit.copy_var_num();
+            let inputs = (0..copies << var_nums[1])
+                .map(|_| Scalar::random(OsRng))
+                .collect::<Vec<_>>();
+            let (witness, _) = circuit.witness_to_poly(&inputs);
+            let wiring = circuit.sub.ops_to_wiring().remove(0);
+            let r_0 = (0..p + var_nums[0])
+                .map(|_| Scalar::random(OsRng))
+                .collect::<Vec<_>>();
+
+            let g = (wiring.clone(), witness[1].clone());
+            let mut prover = DataParallelProver::new(g, r_0.clone(), p);
+            assert_eq!(prover.proof(), witness[0].evaluate_at(&r_0));
+
+            // g_1(0) + g_1(1) = m_i, and g_j(0) + g_j(1) = g_j-1(r_j-1)
+            let v_r = p + 2 * var_nums[1];
+            let mut claim = prover.proof();
+            let mut challenges = vec![];
+            for j in 1..=v_r {
+                let g_j = match j {
+                    1 => prover.round_1(),
+                    _ if j == v_r => prover.round_v(&challenges),
+                    _ => prover.recursive_round_j(&challenges),
+                };
+                assert_eq!(
+                    g_j.evaluate(Scalar::zero()) + g_j.evaluate(Scalar::one()),
+                    claim
+                );
+                let r = Scalar::random(OsRng);
+                claim = g_j.evaluate(r);
+                challenges.push(r);
+            }
+
+            // the last claim is eq(r_j, r*)·f'_{r'}(u, v), with W_i+1(r*, u) = q(0) and W_i+1(r*, v) = q(1)
+            let q = prover.evaluate(&challenges);
+            let (j, bc) = challenges.split_at(p);
+            let (u, v) = bc.split_at(var_nums[1]);
+            assert_eq!(
+                q.evaluate(Scalar::zero()),
+                witness[1].evaluate_at(&[j, u].concat())
+            );
+            assert_eq!(
+                q.evaluate(Scalar::one()),
+                witness[1].evaluate_at(&[j, v].concat())
+            );
+            let (r_j, r) = r_0.split_at(p);
+            let ops_values = wiring.evaluate(&[r, bc].concat());
+            let f = f_r_at(
+                ops_values,
+                q.evaluate(Scalar::zero()),
+                q.evaluate(Scalar::one()),
+            );
+            assert_eq!(claim, eq_at(r_j, j) * f);
+        }
+    }
+}
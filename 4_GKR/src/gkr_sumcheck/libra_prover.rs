@@ -0,0 +1,281 @@
+use crate::arithmetic::layered_circuit::Wiring;
+use crate::gkr_sumcheck::{line, F_r_Poly, LayerProver};
+use crate::poly::Polynomial;
+use crate::utils::eq_table;
+use bls12_381::Scalar;
+
+// The linear-time prover from Libra(XZZ+19), which binds b in the first k rounds and c in the last k rounds.
+//
+// Phase 1: ∑f_{r_i}(b,c) = ∑W_i+1(b)·h_1(b) + h_0(b), b in {0,1}^k, where
+//      h_1(b) = ∑(add_i + sub_i + cmul_i + id_i)(r_i,b,c) + mult_i(r_i,b,c)·W_i+1(c)
+//      h_0(b) = ∑(add_i - sub_i)(r_i,b,c)·W_i+1(c) + cnst_i(r_i,b,c),  c in {0,1}^k
+// Phase 2: with b bound to u, f_{r_i}(u,c) = W_i+1(c)·g_1(c) + g_0(c), where
+//      g_1(c) = (add_i - sub_i)(r_i,u,c) + mult_i(r_i,u,c)·W_i+1(u)
+//      g_0(c) = (add_i + sub_i + cmul_i + id_i)(r_i,u,c)·W_i+1(u) + cnst_i(r_i,u,c)
+// Each of the tables takes O(S_i + S_i+1) from the sparse wiring, and is halved round by round,
+// so that a layer costs O(S_i + S_i+1) instead of O(S_i+1^2) of the dense `Prover`.
+// The round polys are the same, as both are the sums of the same polys.
+//
+// For the zero-knowledge GKR, W_i+1 can be masked as Ẇ(x) = W_i+1(x) + Z(x)·S(x_1), Z(x) = ∏x_j(1-x_j),
+// which is W_i+1 on the hypercube. So Ẇ only differs in the last round of each phase, where all the other
+// variables are bound, and there the round poly has degree 5 at most.
+pub struct LibraProver {
+    k: usize, // k_i+1, the var_num of b and c.
+    wiring: Wiring,
+    eq_r_i: Vec<Scalar>, // eq(r_i, a), or any combination of them, eg: α·eq(u, a) + β·eq(v, a)
+    w_evals: Vec<Scalar>, // W_i+1 on the hypercube.
+    // (W, h_1, h_0) in phase 1, (W, g_1, g_0) in phase 2, with the variables so far bound to r_1, ..., r_j-1.
+    tables: [Vec<Scalar>; 3],
+    w_mask: Option<Polynomial>, // S(x_1) of Ẇ.
+}
+
+impl LibraProver {
+    pub fn new(g: F_r_Poly, r_i: Vec<Scalar>) -> Self {
+        Self::with_eq_table(g, eq_table(&r_i))
+    }
+
+    // The sumcheck on ∑eq_r_i[a]·f(a,b,c), with the given eq_r_i indexed by a.
+    pub fn with_eq_table((wiring, w_i_plus_1): F_r_Poly, eq_r_i: Vec<Scalar>) -> Self {
+        let k = w_i_plus_1.var_num;
+        let w_evals = w_i_plus_1.evals();
+        assert_eq!(eq_r_i.len(), 1 << wiring.var_nums.0);
+
+        let n = 1 << k;
+        let (mut h_1, mut h_0) = (vec![Scalar::zero(); n], vec![Scalar::zero(); n]);
+        for (a, b, c, value) in wiring.add.entries.iter() {
+            let e = eq_r_i[*a] * value;
+            h_1[*b] += e;
+            h_0[*b] += e * w_evals[*c];
+        }
+        for (a, b, c, value) in wiring.mult.entries.iter() {
+            h_1[*b] += eq_r_i[*a] * value * w_evals[*c];
+        }
+        for (a, b, c, value) in wiring.sub.entries.iter() {
+            let e = eq_r_i[*a] * value;
+            h_1[*b] += e;
+            h_0[*b] -= e * w_evals[*c];
+        }
+        for (a, b, _, value) in wiring.cnst.entries.iter() {
+            h_0[*b] += eq_r_i[*a] * value;
+        }
+        for (a, b, _, value) in wiring.cmul.entries.iter().chain(&wiring.id.entries) {
+            h_1[*b] += eq_r_i[*a] * value;
+        }
+
+        Self {
+            k,
+            wiring,
+            eq_r_i,
+            tables: [w_evals.clone(), h_1, h_0],
+            w_evals,
+            w_mask: None,
+        }
+    }
+
+    // Prove with Ẇ = W_i+1 + Z·S in place of W_i+1.
+    pub fn with_mask(mut self, s: Polynomial) -> Self {
+        self.w_mask = Some(s);
+        self
+    }
+
+    // Ẇ(point) = W_i+1(point) + Z(point)·S(point_1)
+    pub fn masked_w(&self, point: &[Scalar]) -> Scalar {
+        self.w_at(point) + self.mask_at(point)
+    }
+
+    fn w_at(&self, point: &[Scalar]) -> Scalar {
+        self.w_evals
+            .iter()
+            .zip(eq_table(point))
+            .map(|(w, chi)| w * chi)
+            .sum()
+    }
+
+    fn mask_at(&self, point: &[Scalar]) -> Scalar {
+        match &self.w_mask {
+            Some(s) => vanishing(point) * s.evaluate(point[0]),
+            None => Scalar::zero(),
+        }
+    }
+
+    // obtain m_i by $\sum_{b,c \in (0,1)^{k_{i+1}}}f_{r_i} = m_i $ , m1 means C1.
+    pub fn proof(&self) -> Scalar {
+        let [w, t_1, t_0] = &self.tables;
+        (0..w.len()).map(|x| w[x] * t_1[x] + t_0[x]).sum()
+    }
+
+    fn phase_2_tables(&self, u: &[Scalar]) -> [Vec<Scalar>; 3] {
+        assert_eq!(self.tables[0].len(), 1, "b should be bound");
+        let w_u = self.tables[0][0] + self.mask_at(u);
+        let eq_u = eq_table(u);
+
+        let n = 1 << self.k;
+        let (mut g_1, mut g_0) = (vec![Scalar::zero(); n], vec![Scalar::zero(); n]);
+        let weight =
+            |(a, b, _, value): &(usize, usize, usize, Scalar)| self.eq_r_i[*a] * eq_u[*b] * value;
+        for entry in self.wiring.add.entries.iter() {
+            let e = weight(entry);
+            g_1[entry.2] += e;
+            g_0[entry.2] += e * w_u;
+        }
+        for entry in self.wiring.mult.entries.iter() {
+            g_1[entry.2] += weight(entry) * w_u;
+        }
+        for entry in self.wiring.sub.entries.iter() {
+            let e = weight(entry);
+            g_1[entry.2] -= e;
+            g_0[entry.2] += e * w_u;
+        }
+        for entry in self.wiring.cnst.entries.iter() {
+            g_0[entry.2] += weight(entry);
+        }
+        for entry in self
+            .wiring
+            .cmul
+            .entries
+            .iter()
+            .chain(&self.wiring.id.entries)
+        {
+            g_0[entry.2] += weight(entry) * w_u;
+        }
+
+        [self.w_evals.clone(), g_1, g_0]
+    }
+
+    // g_j(X) = ∑W(X,x)·T_1(X,x) + T_0(X,x), x in {0,1}^(v-j), which has degree 2,
+    // so it's interpolated from g_j(0), g_j(1), g_j(2).
+    // In the last round of a phase with the mask, W(X) = W_i+1(bound, X) + Z(bound, X)·S(x_1), and g_j has degree 5 at most.
+    fn round_poly(&self, bound: &[Scalar]) -> Polynomial {
+        let half = self.tables[0].len() / 2;
+        let masked = half == 1 && self.w_mask.is_some();
+        let xs = (0..if masked { 6 } else { 3 })
+            .map(|x| Scalar::from(x as u64))
+            .collect::<Vec<_>>();
+        let evals = xs
+            .iter()
+            .map(|x| {
+                let mask = match masked {
+                    true => self.mask_at(&[bound, &[*x]].concat()),
+                    false => Scalar::zero(),
+                };
+                (0..half)
+                    .map(|i| {
+                        // T(X, x) = T(0, x) + X·(T(1, x) - T(0, x))
+                        let [w, t_1, t_0]: [Scalar; 3] = std::array::from_fn(|k| {
+                            let t = &self.tables[k];
+                            t[i] + x * (t[half + i] - t[i])
+                        });
+                        (w + mask) * t_1 + t_0
+                    })
+                    .sum()
+            })
+            .collect::<Vec<Scalar>>();
+        Polynomial::lagrange_interpolate(xs, evals)
+    }
+}
+
+impl LayerProver for LibraProver {
+    // Return g1(X) = sum f_{r_i}(X, x_2, ..., x_v)
+    fn round_1(&self) -> Polynomial {
+        assert_eq!(self.tables[0].len(), 1 << self.k);
+        self.round_poly(&[])
+    }
+
+    // 1 < j < v_r, total v_r-2 rounds
+    // Return g_j = f_{r_i}(r1, ..., r_j-1, X, x_j+1, ..., x_v), after folding with r_j-1.
+    fn recursive_round_j(&mut self, challenges: &[Scalar]) -> Polynomial {
+        let j = challenges.len() + 1;
+        assert!(2 * self.k >= j && j > 1);
+
+        let r = *challenges.last().unwrap();
+        for table in self.tables.iter_mut() {
+            let (lo, hi) = table.split_at(table.len() / 2);
+            *table = lo.iter().zip(hi).map(|(l, h)| l + r * (h - l)).collect();
+        }
+        // b is bound to u = (r_1, ..., r_k), turn to phase 2.
+        if j == self.k + 1 {
+            self.tables = self.phase_2_tables(challenges);
+        }
+        // the variables bound in the current phase.
+        let bound = &challenges[(j > self.k) as usize * self.k..];
+        self.round_poly(bound)
+    }
+
+    // Return g_v = f_{r_i}(r1, r2, ..., r_v-1, X_v)
+    fn round_v(&mut self, challenges: &[Scalar]) -> Polynomial {
+        assert_eq!(2 * self.k - 1, challenges.len());
+        self.recursive_round_j(challenges)
+    }
+
+    // challenges include (u, v), here we need (r,u,v)
+    // Return q = W_i+1 ∘ l, where l is the line with l(0)=u and l(1)=v.
+    // As deg(q) <= k_i+1, it's interpolated from q(0), ..., q(k_i+1).
+    fn evaluate(&self, challenges: &[Scalar]) -> Polynomial {
+        assert_eq!(2 * self.k, challenges.len());
+
+        let (u, v) = challenges.split_at(self.k);
+        let ts = (0..=self.k)
+            .map(|t| Scalar::from(t as u64))
+            .collect::<Vec<_>>();
+        let evals = ts.iter().map(|t| self.w_at(&line(u, v, *t))).collect();
+        Polynomial::lagrange_interpolate(ts, evals)
+    }
+
+    // q(X) = W_i+1(u) + X·(W_i+1(v) - W_i+1(u))
+    fn evaluate_ends(&self, challenges: &[Scalar]) -> Polynomial {
+        assert_eq!(2 * self.k, challenges.len());
+        let (u, v) = challenges.split_at(self.k);
+        let (w_u, w_v) = (self.w_at(u), self.w_at(v));
+        Polynomial::from_coeffs(vec![w_u, w_v - w_u])
+    }
+}
+
+// Z(x) = ∏x_j(1-x_j), which vanishes on the hypercube.
+pub fn vanishing(point: &[Scalar]) -> Scalar {
+    point.iter().map(|x| x * (Scalar::one() - x)).product()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::arithmetic::layered_circuit::CircuitConfig;
+    use crate::gkr_sumcheck::prover::Prover;
+    use ff::Field;
+    use rand_core::OsRng;
+
+    #[test]
+    fn test_same_proof_as_dense_prover() {
+        for var_nums in [vec![1, 1], vec![2, 1], vec![1, 3], vec![3, 2]] {
+            let config = CircuitConfig::random(&var_nums, &mut OsRng);
+            let inputs = (0..1 << var_nums[1])
+                .map(|_| Scalar::random(OsRng))
+                .collect::<Vec<_>>();
+            let (witness, _) = config.witness_to_poly(&inputs);
+            let wiring = config.ops_to_wiring().remove(0);
+            let r_0 = (0..var_nums[0])
+                .map(|_| Scalar::random(OsRng))
+                .collect::<Vec<_>>();
+
+            let g = (wiring, witness[1].clone());
+            let mut dense = Prover::new(g.clone(), r_0.clone());
+            let mut libra = LibraProver::new(g, r_0.clone());
+
+            // the claim is W_0(r_0)
+            assert_eq!(libra.proof(), dense.proof());
+            assert_eq!(libra.proof(), witness[0].evaluate_at(&r_0));
+
+            let v_r = 2 * var_nums[1];
+            let mut challenges = vec![];
+            assert_eq!(libra.round_1(), dense.round_1());
+            for _ in 1..v_r {
+                challenges.push(Scalar::random(OsRng));
+                assert_eq!(
+                    libra.recursive_round_j(&challenges),
+                    dense.recursive_round_j(&challenges)
+                );
+            }
+            challenges.push(Scalar::random(OsRng));
+            assert_eq!(libra.evaluate(&challenges), dense.evaluate(&challenges));
+        }
+    }
+}
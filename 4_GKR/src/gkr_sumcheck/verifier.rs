@@ -1,13 +1,13 @@
 use crate::poly::Polynomial;
 use bls12_381::Scalar;
-use ff::{Field, PrimeField};
-use rand_core::{OsRng, RngCore};
+use ff::Field;
+use rand_core::OsRng;
 
 pub struct Verifier {
     proof: Scalar, // C1 in sumcheck, <==> mi in GKR.
     v: usize,
     cached_g_j: Vec<Polynomial>,
-    challenges: Vec<usize>, // challenges: r1, r2, ..., rv. (In implement, r1 is a random usize, which is easy to construct a Field)
+    challenges: Vec<Scalar>, // challenges: r1, r2, ..., rv in F.
 }
 
 impl Verifier {
@@ -20,14 +20,13 @@ impl Verifier {
         }
     }
 
-    pub fn challenges(&self) -> Vec<usize> {
+    pub fn challenges(&self) -> Vec<Scalar> {
         self.challenges.clone()
     }
 
     // generate r1, r2, ..., rv
-    pub(super) fn gen_challenge() -> usize {
-        let k = OsRng.next_u32() % 1000;
-        k as usize
+    pub(crate) fn gen_challenge() -> Scalar {
+        Scalar::random(OsRng)
     }
     fn prepare_next_round(&mut self, g_i: Polynomial) {
         assert_eq!(self.challenges.len(), self.cached_g_j.len());
@@ -76,21 +75,18 @@ impl Verifier {
             "length of challenges != (j-1)"
         );
 
-        let r = self.challenges.last().unwrap().clone() as u128;
+        let r = *self.challenges.last().unwrap();
         let actual = g_j.evaluate(Scalar::zero()) + g_j.evaluate(Scalar::one());
-        let target = self
-            .cached_g_j
-            .last()
-            .unwrap()
-            .evaluate(Scalar::from_u128(r));
+        let target = self.cached_g_j.last().unwrap().evaluate(r);
 
         assert_eq!(actual, target, "Not-equal in round_{}", j);
     }
 
     // V checks below:
     //  gv (rv ) = add(r_i,u,v)(W_i_1(u) + W_i_1(v)) + mult(r_i,u,v)(W_i_1(u) * W_i_1(v)))
-    //  We use p encode the (0,W_i_1(u)),(1,W_i_1(v)).
-    pub fn check(&self, (add_value, mult_value, p_poly): (Scalar, Scalar, &Polynomial)) {
+    //          + sub(r_i,u,v)(W_i_1(u) - W_i_1(v)) + cnst(r_i,u,v) + (cmul(r_i,u,v) + id(r_i,u,v))W_i_1(u)
+    //  where q = W_i_1 ∘ l gives W_i_1(u) = q(0) and W_i_1(v) = q(1).
+    pub fn check(&self, [add, mult, sub, cnst, cmul, id]: [Scalar; 6], q_poly: &Polynomial) {
         assert_eq!(
             self.v,
             self.cached_g_j.len(),
@@ -101,14 +97,26 @@ impl Verifier {
             self.challenges.len(),
             "length of challenges != (j-1)"
         );
-        let target = add_value * (p_poly.evaluate(Scalar::zero()) + p_poly.evaluate(Scalar::one()))
-            + mult_value * (p_poly.evaluate(Scalar::zero()) * p_poly.evaluate(Scalar::one()));
+        let (w_u, w_v) = (
+            q_poly.evaluate(Scalar::zero()),
+            q_poly.evaluate(Scalar::one()),
+        );
+        let target = f_r_at([add, mult, sub, cnst, cmul, id], w_u, w_v);
 
-        let r_v = self.challenges.last().unwrap().clone() as u128;
+        let r_v = *self.challenges.last().unwrap();
         let g_v = self.cached_g_j.last().unwrap().clone();
-        let actual = g_v.evaluate(Scalar::from_u128(r_v));
+        let actual = g_v.evaluate(r_v);
 
         assert_eq!(actual, target, "Verifier rejected the proof");
         println!("Verifier accepted the proof");
     }
 }
+
+// f_{r_i}(u,v), from the wiring predicates at (r_i,u,v), and W_i_1(u), W_i_1(v).
+pub(crate) fn f_r_at(
+    [add, mult, sub, cnst, cmul, id]: [Scalar; 6],
+    w_u: Scalar,
+    w_v: Scalar,
+) -> Scalar {
+    add * (w_u + w_v) + mult * (w_u * w_v) + sub * (w_u - w_v) + cnst + (cmul + id) * w_u
+}
@@ -1,2 +1,34 @@
 pub use sumcheck::utils::convert_from_binary;
 pub use sumcheck::utils::convert_to_binary;
+pub use sumcheck::utils::eq_table;
+pub use sumcheck::utils::{install, thread_pool};
+
+use bls12_381::Scalar;
+use Fiat_Shamir::Transcript;
+
+// eq(x, y) = ∏(x_j·y_j + (1-x_j)·(1-y_j)), the MLE of x == y.
+pub fn eq_at(x: &[Scalar], y: &[Scalar]) -> Scalar {
+    assert_eq!(x.len(), y.len());
+    x.iter()
+        .zip(y)
+        .map(|(x, y)| x * y + (Scalar::one() - x) * (Scalar::one() - y))
+        .product()
+}
+
+pub fn append_scalars(transcript: &mut impl Transcript, scalars: &[Scalar]) {
+    for s in scalars {
+        transcript.append(&s.to_bytes());
+    }
+}
+
+// A challenge in F from the 32 bytes of the transcript, reduced mod p.
+pub fn challenge_scalar(transcript: &mut impl Transcript) -> Scalar {
+    let mut bytes = [0u8; 64];
+    bytes[..32].copy_from_slice(&transcript.challenge());
+    Scalar::from_bytes_wide(&bytes)
+}
+
+// r_1, ..., r_n in F
+pub fn challenge_scalars(transcript: &mut impl Transcript, n: usize) -> Vec<Scalar> {
+    (0..n).map(|_| challenge_scalar(transcript)).collect()
+}
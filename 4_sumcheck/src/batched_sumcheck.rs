@@ -0,0 +1,114 @@
+use crate::batched_sumcheck::prover::Prover;
+use crate::batched_sumcheck::verifier::Verifier;
+use crate::poly::multivar_poly::MPolynomial;
+use crate::sumcheck::EvaluationClaim;
+
+pub mod prover;
+pub mod verifier;
+
+// Batch m sumcheck claims over the same number of variables into one sumcheck:
+//      H_k = ∑_{b∈{0,1}^v} g_k(b),  k = 1, ..., m
+//
+// 1. V picks random ρ_1, ..., ρ_m and both parties run a single sumcheck on
+//      g = ∑ρ_k·g_k  with the claim C1 = ∑ρ_k·H_k.
+//    The round polynomials are the ρ-combination of the round polynomials of each g_k,
+//    so the proof size is the same as one sumcheck.
+// 2. At the end, P reports g_k(r_1, ..., r_v) for each k, V checks g_v(r_v) = ∑ρ_k·g_k(r_1, ..., r_v)
+//    and outputs the m evaluation claims, which can be discharged separately.
+pub struct BatchedSumCheck {
+    v: usize,
+    prover: Prover,
+    verifier: Verifier,
+}
+
+impl BatchedSumCheck {
+    pub fn new(polys: Vec<MPolynomial>) -> Self {
+        assert!(!polys.is_empty(), "Nothing to batch");
+        let var_num = polys[0].var_num;
+
+        let mut prover = Prover::new(polys);
+        let claims = prover.proof();
+        let verifier = Verifier::new(var_num, claims);
+
+        // V sends the batching coefficients at the start of the protocol.
+        prover.batch(verifier.batching_coeffs());
+
+        Self {
+            v: var_num,
+            prover,
+            verifier,
+        }
+    }
+
+    pub fn run_protocol(&mut self) -> Vec<EvaluationClaim> {
+        // round 1
+        let g1 = self.prover.round_1();
+        self.verifier.round_1(g1);
+
+        // round 2 - (v-1)
+        for j in 2..self.v {
+            let challenges = self.verifier.challenges();
+            let g_j = self.prover.recursive_round_j(&challenges);
+            self.verifier.recursive_round_j(j, g_j);
+        }
+
+        // round v
+        let challenges = self.verifier.challenges();
+        let g_v = self.prover.round_v(&challenges);
+        self.verifier.round_v(g_v);
+
+        // finally check, and hand out g_k(r_1, ..., r_v) for each k.
+        let challenges = self.verifier.challenges();
+        let evals = self.prover.evaluate(&challenges);
+        self.verifier.check(evals)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bls12_381::Scalar;
+    use ff::Field;
+    use rand_core::OsRng;
+
+    fn random_mpoly(var_num: usize) -> MPolynomial {
+        MPolynomial {
+            var_num,
+            coeffs: (0..1 << var_num)
+                .map(|_| Scalar::random(OsRng))
+                .collect::<Vec<_>>(),
+        }
+    }
+
+    #[test]
+    fn test_batched_sumcheck() {
+        let var_num = 4;
+        let polys = (0..3).map(|_| random_mpoly(var_num)).collect::<Vec<_>>();
+
+        let mut sumcheck = BatchedSumCheck::new(polys.clone());
+        let claims = sumcheck.run_protocol();
+
+        // discharge each claim by evaluating g_k directly.
+        assert_eq!(claims.len(), polys.len());
+        for (g_k, claim) in polys.iter().zip(claims) {
+            assert_eq!(g_k.evaluate(&claim.point), claim.value);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "No-equal in round_1")]
+    fn test_batched_sumcheck_wrong_claim() {
+        let var_num = 3;
+        let polys = (0..2).map(|_| random_mpoly(var_num)).collect::<Vec<_>>();
+
+        let mut sumcheck = BatchedSumCheck::new(polys);
+
+        // P lies about the second sum.
+        let mut claims = sumcheck.prover.proof();
+        claims[1] += Scalar::one();
+        sumcheck.verifier = Verifier::new(var_num, claims);
+        sumcheck.prover.batch(sumcheck.verifier.batching_coeffs());
+
+        sumcheck.run_protocol();
+    }
+}
@@ -0,0 +1,70 @@
+use crate::poly::multivar_poly::MPolynomial;
+use crate::poly::univar_poly::Polynomial;
+use bls12_381::Scalar;
+
+pub struct Prover {
+    polys: Vec<MPolynomial>,
+    rhos: Vec<Scalar>, // batching coefficients: ρ_1, ..., ρ_m
+}
+
+impl Prover {
+    pub fn new(polys: Vec<MPolynomial>) -> Self {
+        let var_num = polys[0].var_num;
+        assert!(
+            polys.iter().all(|g_k| g_k.var_num == var_num),
+            "All the batched polys should have the same var_num"
+        );
+
+        Self {
+            polys,
+            rhos: vec![],
+        }
+    }
+
+    // The claims H_k = ∑_{b∈{0,1}^v} g_k(b)
+    pub fn proof(&self) -> Vec<Scalar> {
+        self.polys.iter().map(|g_k| g_k.sum_all_evals()).collect()
+    }
+
+    // Receive ρ_1, ..., ρ_m from V.
+    pub fn batch(&mut self, rhos: Vec<Scalar>) {
+        assert_eq!(rhos.len(), self.polys.len());
+        self.rhos = rhos;
+    }
+
+    // Return g_j(X) = ∑ρ_k·g_{k,j}(X), where g_{k,j} is the round poly of g_k.
+    fn combined_round(&self, challenges: &Vec<usize>) -> Polynomial {
+        assert_eq!(self.rhos.len(), self.polys.len(), "Batch before proving");
+
+        self.polys
+            .iter()
+            .zip(self.rhos.iter())
+            .map(|(g_k, rho_k)| &g_k.partial_evaluate(challenges) * rho_k)
+            .reduce(|acc, g_k_j| &acc + &g_k_j)
+            .unwrap()
+    }
+
+    // Return g1(X) = ∑ρ_k·∑g_k(X, x_2, ..., x_v)
+    pub fn round_1(&self) -> Polynomial {
+        self.combined_round(&vec![])
+    }
+
+    // 1 < j < v, total v-2 rounds
+    // Return g_j = ∑ρ_k·∑g_k(r1, ..., r_j-1, X, x_j+1, ..., x_v)
+    pub fn recursive_round_j(&self, challenges: &Vec<usize>) -> Polynomial {
+        self.combined_round(challenges)
+    }
+
+    // Return g_v = ∑ρ_k·g_k(r1, r2, ..., r_v-1, X_v)
+    pub fn round_v(&self, challenges: &Vec<usize>) -> Polynomial {
+        self.combined_round(challenges)
+    }
+
+    // Return g_k(r1, ..., r_v) for each k.
+    pub fn evaluate(&self, challenges: &Vec<usize>) -> Vec<Scalar> {
+        self.polys
+            .iter()
+            .map(|g_k| g_k.evaluate(challenges))
+            .collect()
+    }
+}
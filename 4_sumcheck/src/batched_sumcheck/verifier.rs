@@ -0,0 +1,74 @@
+use crate::poly::univar_poly::Polynomial;
+use crate::sumcheck::verifier::Verifier as SumCheckVerifier;
+use crate::sumcheck::EvaluationClaim;
+use bls12_381::Scalar;
+use ff::Field;
+use rand_core::OsRng;
+
+pub struct Verifier {
+    rhos: Vec<Scalar>, // batching coefficients: ρ_1, ..., ρ_m
+    // The rounds are exactly the ones of the standard sumcheck on C1 = ∑ρ_k·H_k.
+    inner: SumCheckVerifier,
+}
+
+impl Verifier {
+    pub fn new(v: usize, claims: Vec<Scalar>) -> Self {
+        let rhos = claims
+            .iter()
+            .map(|_| Scalar::random(OsRng))
+            .collect::<Vec<_>>();
+        let proof = Self::combine(&rhos, &claims);
+
+        Self {
+            rhos,
+            inner: SumCheckVerifier::new(v, proof),
+        }
+    }
+
+    fn combine(rhos: &[Scalar], values: &[Scalar]) -> Scalar {
+        assert_eq!(rhos.len(), values.len());
+        rhos.iter()
+            .zip(values)
+            .map(|(rho, value)| rho * value)
+            .sum()
+    }
+
+    pub fn batching_coeffs(&self) -> Vec<Scalar> {
+        self.rhos.clone()
+    }
+
+    pub fn challenges(&self) -> Vec<usize> {
+        self.inner.challenges()
+    }
+
+    // Check: C1 = g_1(0) + g_1(1)
+    pub fn round_1(&mut self, g1: Polynomial) {
+        self.inner.round_1(g1);
+    }
+
+    // check: g_j-1(r_j-1) = g_j(0) + g_j(1)
+    pub fn recursive_round_j(&mut self, round_num: usize, g_j: Polynomial) {
+        self.inner.recursive_round_j(round_num, g_j);
+    }
+
+    // Check: gv−1 (rv−1 ) = gv (0) + gv (1).
+    pub fn round_v(&mut self, g_v: Polynomial) {
+        self.inner.round_v(g_v);
+    }
+
+    // Check: gv(rv) = ∑ρ_k·g_k(r1, ..., rv), where g_k(r1, ..., rv) are claimed by P.
+    // Return the claims g_k(r1, ..., rv), which are left to the caller.
+    pub fn check(&self, evals: Vec<Scalar>) -> Vec<EvaluationClaim> {
+        assert_eq!(evals.len(), self.rhos.len());
+        self.inner.check_value(Self::combine(&self.rhos, &evals));
+
+        let point = self.challenges();
+        evals
+            .into_iter()
+            .map(|value| EvaluationClaim {
+                point: point.clone(),
+                value,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,84 @@
+use crate::poly::multivar_poly::MPolynomial;
+use bls12_381::{G1Affine, G1Projective, Scalar};
+use sha3::{Digest, Keccak256};
+
+pub mod hyrax;
+
+// A polynomial commitment scheme for the multilinear polys.
+// P commits to g up front, later opens g at a point chosen by V, and V checks the opening against the commitment.
+pub trait PolynomialCommitment {
+    type Commitment: Clone;
+    type Proof;
+
+    fn commit(&self, poly: &MPolynomial) -> Self::Commitment;
+
+    // Return g(point) and the proof of the evaluation.
+    fn open(&self, poly: &MPolynomial, point: &[Scalar]) -> (Scalar, Self::Proof);
+
+    // Check: g(point) = value, where g is the committed poly.
+    fn verify(
+        &self,
+        commitment: &Self::Commitment,
+        point: &[Scalar],
+        value: &Scalar,
+        proof: &Self::Proof,
+    ) -> Result<(), String>;
+}
+
+// ∑s_i·G_i
+pub fn msm(scalars: &[Scalar], bases: &[G1Projective]) -> G1Projective {
+    assert_eq!(scalars.len(), bases.len());
+    scalars
+        .iter()
+        .zip(bases)
+        .fold(G1Projective::identity(), |acc, (s, g)| acc + g * s)
+}
+
+// n generators of G1 by hashing (label, i) to the curve, with try-and-increment on the x-coordinate,
+// so that nobody knows their discrete logs, and the commitments are binding without a trusted setup.
+pub fn hash_to_generators(label: &[u8], n: usize) -> Vec<G1Projective> {
+    (0..n as u64)
+        .map(|i| {
+            (0u64..)
+                .find_map(|counter| {
+                    let hash = |part: u8| {
+                        Keccak256::new()
+                            .chain_update(label)
+                            .chain_update(i.to_le_bytes())
+                            .chain_update(counter.to_le_bytes())
+                            .chain_update([part])
+                            .finalize()
+                    };
+                    let mut x = [0u8; 48];
+                    x[..32].copy_from_slice(&hash(0));
+                    x[32..].copy_from_slice(&hash(1)[..16]);
+                    // set the compression flag, and clear the infinity and sign flags.
+                    x[0] = (x[0] & 0x1f) | 0x80;
+
+                    // x may be out of the field or not on the curve, then try the next counter.
+                    let point = Option::<G1Affine>::from(G1Affine::from_compressed_unchecked(&x))?;
+                    let point = G1Projective::from(point).clear_cofactor();
+                    (!bool::from(point.is_identity())).then_some(point)
+                })
+                .unwrap()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hash_to_generators() {
+        let generators = hash_to_generators(b"test", 8);
+        assert_eq!(generators, hash_to_generators(b"test", 8));
+        assert_ne!(generators, hash_to_generators(b"other", 8));
+
+        // in the prime order subgroup, and distinct.
+        for (i, g) in generators.iter().enumerate() {
+            assert!(bool::from(G1Affine::from(g).is_torsion_free()));
+            assert!(generators[..i].iter().all(|h| h != g));
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use crate::commitment::{hash_to_generators, msm, PolynomialCommitment};
+use crate::poly::multivar_poly::MPolynomial;
+use crate::utils::convert_to_binary;
+use bls12_381::{G1Projective, Scalar};
+
+// Hyrax-style commitment (without hiding) for a multilinear poly with v variables.
+//
+// Arrange the 2^v coeffs as a matrix M with 2^v1 rows and 2^v2 columns (v = v1 + v2),
+// where the row index is the exponents of (x_1, ..., x_v1) and the column index is the ones of (x_v1+1, ..., x_v).
+// So that:
+//      g(r) = L^T · M · R,  L[row] = ∏r_i^{row_i}, R[col] = ∏r_{v1+i}^{col_i}
+//
+// 1. Commit: Pedersen commitment of each row, C_row = ∑M[row][col]·G_col.
+// 2. Open: P sends t = L^T · M.
+// 3. Verify: ∑L[row]·C_row = ∑t[col]·G_col, and g(r) = <t, R>.
+//
+// Both the commitment and the proof are O(√2^v).
+#[derive(Clone, Debug)]
+pub struct Hyrax {
+    var_num: usize,
+    row_var_num: usize,
+    generators: Vec<G1Projective>, // G_0, ..., G_{2^v2 - 1}
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HyraxCommitment {
+    row_commitments: Vec<G1Projective>,
+}
+
+impl HyraxCommitment {
+    // C_row of each row.
+    pub fn row_commitments(&self) -> &[G1Projective] {
+        &self.row_commitments
+    }
+}
+
+impl Hyrax {
+    pub fn setup(var_num: usize) -> Self {
+        let row_var_num = var_num / 2;
+        let col_num = 1 << (var_num - row_var_num);
+
+        let generators = hash_to_generators(b"hyrax", col_num);
+
+        Self {
+            var_num,
+            row_var_num,
+            generators,
+        }
+    }
+
+    fn col_var_num(&self) -> usize {
+        self.var_num - self.row_var_num
+    }
+
+    // The monomials on vars: (∏r_i^{w_i}) for w in {0,1}^vars.len()
+    fn monomials(vars: &[Scalar]) -> Vec<Scalar> {
+        let var_num = vars.len();
+        (0..1 << var_num)
+            .map(|n| {
+                convert_to_binary(&var_num, n)
+                    .iter()
+                    .zip(vars)
+                    .filter(|(exp, _)| **exp == 1)
+                    .fold(Scalar::one(), |acc, (_, r)| acc * r)
+            })
+            .collect()
+    }
+
+    fn rows<'a>(&self, poly: &'a MPolynomial) -> std::slice::Chunks<'a, Scalar> {
+        assert_eq!(poly.var_num, self.var_num, "var_num mismatch with setup");
+        poly.coeffs.chunks(1 << self.col_var_num())
+    }
+}
+
+impl PolynomialCommitment for Hyrax {
+    type Commitment = HyraxCommitment;
+    type Proof = Vec<Scalar>;
+
+    fn commit(&self, poly: &MPolynomial) -> HyraxCommitment {
+        let row_commitments = self
+            .rows(poly)
+            .map(|row| msm(row, &self.generators))
+            .collect();
+
+        HyraxCommitment { row_commitments }
+    }
+
+    fn open(&self, poly: &MPolynomial, point: &[Scalar]) -> (Scalar, Vec<Scalar>) {
+        assert_eq!(point.len(), self.var_num);
+        let (l_vars, r_vars) = point.split_at(self.row_var_num);
+        let l = Self::monomials(l_vars);
+        let r = Self::monomials(r_vars);
+
+        // t = L^T · M
+        let mut t = vec![Scalar::zero(); 1 << self.col_var_num()];
+        for (l_row, row) in l.iter().zip(self.rows(poly)) {
+            for (t_col, m) in t.iter_mut().zip(row) {
+                *t_col += l_row * m;
+            }
+        }
+
+        let value = t
+            .iter()
+            .zip(r.iter())
+            .map(|(t_col, r_col)| t_col * r_col)
+            .sum();
+        (value, t)
+    }
+
+    fn verify(
+        &self,
+        commitment: &HyraxCommitment,
+        point: &[Scalar],
+        value: &Scalar,
+        proof: &Vec<Scalar>,
+    ) -> Result<(), String> {
+        if point.len() != self.var_num {
+            return Err("Hyrax: wrong point length".to_string());
+        }
+        if proof.len() != self.generators.len()
+            || commitment.row_commitments.len() != 1 << self.row_var_num
+        {
+            return Err("Hyrax: wrong opening length".to_string());
+        }
+        let (l_vars, r_vars) = point.split_at(self.row_var_num);
+        let l = Self::monomials(l_vars);
+        let r = Self::monomials(r_vars);
+
+        // ∑L[row]·C_row = ∑t[col]·G_col
+        let lhs = msm(&l, &commitment.row_commitments);
+        let rhs = msm(proof, &self.generators);
+        if lhs != rhs {
+            return Err("Hyrax: the opening doesn't match the commitment".to_string());
+        }
+
+        // g(r) = <t, R>
+        let actual: Scalar = proof.iter().zip(r.iter()).map(|(t, r)| t * r).sum();
+        if &actual != value {
+            return Err("Hyrax: wrong evaluation".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ff::Field;
+    use rand_core::OsRng;
+
+    fn random_mpoly(var_num: usize) -> MPolynomial {
+        MPolynomial {
+            var_num,
+            coeffs: (0..1 << var_num)
+                .map(|_| Scalar::random(OsRng))
+                .collect::<Vec<_>>(),
+        }
+    }
+
+    #[test]
+    fn test_hyrax() {
+        for var_num in 1..6 {
+            let poly = random_mpoly(var_num);
+            let pcs = Hyrax::setup(var_num);
+            let commitment = pcs.commit(&poly);
+
+            let point = (0..var_num).map(|i| 3 * i + 7).collect::<Vec<usize>>();
+            let point_scalar = point
+                .iter()
+                .map(|r| Scalar::from(*r as u64))
+                .collect::<Vec<_>>();
+
+            let (value, proof) = pcs.open(&poly, &point_scalar);
+            assert_eq!(value, poly.evaluate(&point));
+            assert_eq!(
+                pcs.verify(&commitment, &point_scalar, &value, &proof),
+                Ok(())
+            );
+
+            // a wrong value
+            let wrong = value + Scalar::one();
+            assert_eq!(
+                pcs.verify(&commitment, &point_scalar, &wrong, &proof),
+                Err("Hyrax: wrong evaluation".to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn test_hyrax_wrong_poly() {
+        let var_num = 4;
+        let pcs = Hyrax::setup(var_num);
+        let commitment = pcs.commit(&random_mpoly(var_num));
+
+        // P opens another poly.
+        let point = (0..var_num)
+            .map(|_| Scalar::random(OsRng))
+            .collect::<Vec<_>>();
+        let (value, proof) = pcs.open(&random_mpoly(var_num), &point);
+        assert_eq!(
+            pcs.verify(&commitment, &point, &value, &proof),
+            Err("Hyrax: the opening doesn't match the commitment".to_string())
+        );
+
+        // V rejects a point of the other var_num, instead of panicking.
+        assert_eq!(
+            pcs.verify(&commitment, &point[1..], &value, &proof),
+            Err("Hyrax: wrong point length".to_string())
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use crate::ext_sumcheck::prover::Prover;
+use crate::ext_sumcheck::verifier::Verifier;
+use crate::field::{ExtensionField, Field};
+use crate::poly::ext_poly::MultilinearPoly;
+
+pub mod prover;
+pub mod verifier;
+
+// The sumcheck over a small base field F, with the challenges and round polys in an extension K ⊇ F.
+//
+// g = f_1·f_2·...·f_d is a product of MLEs over F, and P claims C1 = ∑g(b), b in {0,1}^v, which is in F.
+// 1. In round 1, P works in F only, and sends g_1 lifted to K.
+// 2. V picks r_1 in K, so P folds each f_i to f_i(r_1, x_2, ..., x_v) with the mixed K × F arithmetic,
+//    and from then on, both of them work in K.
+// 3. V checks g_v(r_v) = g(r_1, ..., r_v), by evaluating the MLEs at the point in K^v.
+// The soundness error is v·d/|K| instead of v·d/|F|.
+pub struct ExtSumCheck<F: Field, K: ExtensionField<F>> {
+    v: usize,
+    prover: Prover<F, K>,
+    verifier: Verifier<F, K>,
+    mles: Vec<MultilinearPoly<F>>, // V's access to g for the final check.
+}
+
+impl<F: Field, K: ExtensionField<F>> ExtSumCheck<F, K> {
+    pub fn new(mles: Vec<MultilinearPoly<F>>) -> Self {
+        let claim = Prover::<F, K>::new(mles.clone()).proof();
+        Self::with_claim(mles, claim)
+    }
+
+    // P claims C1 instead of the actual sum.
+    pub fn with_claim(mles: Vec<MultilinearPoly<F>>, claim: F) -> Self {
+        let v = mles[0].var_num;
+        let prover = Prover::new(mles.clone());
+        let verifier = Verifier::new(v, mles.len(), claim);
+
+        Self {
+            v,
+            prover,
+            verifier,
+            mles,
+        }
+    }
+
+    // The claimed sum C1.
+    pub fn claim(&self) -> F {
+        self.prover.proof()
+    }
+
+    pub fn run_protocol(&mut self) {
+        // round 1, in F.
+        let g1 = self.prover.round_1();
+        self.verifier.round_1(g1);
+
+        // round 2 - v, in K.
+        for j in 2..=self.v {
+            let r_j_minus_1 = *self.verifier.challenges().last().unwrap();
+            let g_j = self.prover.recursive_round_j(r_j_minus_1);
+            self.verifier.recursive_round_j(j, g_j);
+        }
+
+        // finally check, V evaluates g(r1, ..., rv) = ∏f_i(r1, ..., rv) by itself.
+        let point = self.verifier.challenges();
+        let target = self
+            .mles
+            .iter()
+            .map(|f| f.evaluate(&point))
+            .fold(K::one(), |acc, f_r| acc * f_r);
+        self.verifier.check(target);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::field::extension::{GoldilocksExt2, GoldilocksExt4};
+    use crate::field::goldilocks::Goldilocks;
+    use rand_core::OsRng;
+
+    fn gen_mles(var_num: usize, d: usize) -> Vec<MultilinearPoly<Goldilocks>> {
+        (0..d)
+            .map(|_| {
+                let evals = (0..1 << var_num)
+                    .map(|_| Goldilocks::random(OsRng))
+                    .collect();
+                MultilinearPoly::new(var_num, evals)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ext_sumcheck() {
+        let mles = gen_mles(5, 3);
+        let expected = (0..1 << 5)
+            .map(|b| {
+                mles.iter()
+                    .fold(Goldilocks::one(), |acc, f| acc * f.evals[b])
+            })
+            .sum::<Goldilocks>();
+
+        // without extension, and with the quadratic and quartic extensions.
+        let mut sumcheck = ExtSumCheck::<Goldilocks, Goldilocks>::new(mles.clone());
+        assert_eq!(sumcheck.claim(), expected);
+        sumcheck.run_protocol();
+
+        ExtSumCheck::<Goldilocks, GoldilocksExt2>::new(mles.clone()).run_protocol();
+        ExtSumCheck::<Goldilocks, GoldilocksExt4>::new(mles).run_protocol();
+    }
+
+    #[test]
+    fn test_ext_sumcheck_one_var() {
+        ExtSumCheck::<Goldilocks, GoldilocksExt2>::new(gen_mles(1, 2)).run_protocol();
+    }
+
+    #[test]
+    #[should_panic(expected = "No-equal in round_1")]
+    fn test_ext_sumcheck_wrong_claim() {
+        let mles = gen_mles(4, 2);
+        let claim =
+            Prover::<Goldilocks, GoldilocksExt2>::new(mles.clone()).proof() + Goldilocks::one();
+
+        ExtSumCheck::<Goldilocks, GoldilocksExt2>::with_claim(mles, claim).run_protocol();
+    }
+}
@@ -0,0 +1,63 @@
+use crate::field::{ExtensionField, Field};
+use crate::poly::ext_poly::{MultilinearPoly, RoundPoly};
+
+pub struct Prover<F: Field, K: ExtensionField<F>> {
+    mles: Vec<MultilinearPoly<F>>,   // f_1, ..., f_d over F
+    folded: Vec<MultilinearPoly<K>>, // f_i(r_1, ..., r_j-1, x_j, ..., x_v), empty in round 1.
+}
+
+impl<F: Field, K: ExtensionField<F>> Prover<F, K> {
+    pub fn new(mles: Vec<MultilinearPoly<F>>) -> Self {
+        assert!(!mles.is_empty());
+        assert!(
+            mles.iter().all(|f| f.var_num == mles[0].var_num),
+            "All the MLEs should have the same var_num"
+        );
+        Self {
+            mles,
+            folded: vec![],
+        }
+    }
+
+    // C1 = ∑∏f_i(b), b in {0,1}^v
+    pub fn proof(&self) -> F {
+        (0..self.mles[0].evals.len())
+            .map(|b| self.mles.iter().fold(F::one(), |acc, f| acc * f.evals[b]))
+            .sum()
+    }
+
+    // Return g1(X) = ∑∏f_i(X, x_2, ..., x_v), which is computed in F.
+    pub fn round_1(&self) -> RoundPoly<K> {
+        let evals = Self::round_evals(&self.mles);
+        RoundPoly::new(evals.into_iter().map(K::from).collect())
+    }
+
+    // 1 < j <= v
+    // Return g_j(X) = ∑∏f_i(r1, ..., r_j-1, X, x_j+1, ..., x_v), after folding with r_j-1.
+    pub fn recursive_round_j(&mut self, r_j_minus_1: K) -> RoundPoly<K> {
+        self.folded = match self.folded.is_empty() {
+            // K × F, in round 2 only.
+            true => self.mles.iter().map(|f| f.fold(r_j_minus_1)).collect(),
+            // K × K
+            false => self.folded.iter().map(|f| f.fold(r_j_minus_1)).collect(),
+        };
+        RoundPoly::new(Self::round_evals(&self.folded))
+    }
+
+    // ∑∏(f_i(0, b) + X·(f_i(1, b) - f_i(0, b))), b in {0,1}^(v-j), on X = 0, 1, ..., d.
+    fn round_evals<E: Field>(mles: &[MultilinearPoly<E>]) -> Vec<E> {
+        let halves = mles.iter().map(|f| f.halves()).collect::<Vec<_>>();
+        (0..=mles.len() as u64)
+            .map(|x| {
+                let x = E::from_u64(x);
+                (0..halves[0].0.len())
+                    .map(|b| {
+                        halves.iter().fold(E::one(), |acc, (lo, hi)| {
+                            acc * (lo[b] + x * (hi[b] - lo[b]))
+                        })
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+}
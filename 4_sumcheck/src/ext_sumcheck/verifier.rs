@@ -0,0 +1,82 @@
+use crate::field::{ExtensionField, Field};
+use crate::poly::ext_poly::RoundPoly;
+use rand_core::OsRng;
+use std::marker::PhantomData;
+
+pub struct Verifier<F: Field, K: ExtensionField<F>> {
+    proof: K, // C1, lifted to K.
+    v: usize,
+    degree: usize, // the max degree of the round polys.
+    cached_g_j: Vec<RoundPoly<K>>,
+    challenges: Vec<K>, // challenges: r1, r2, ..., rv in K.
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field, K: ExtensionField<F>> Verifier<F, K> {
+    pub fn new(v: usize, degree: usize, proof: F) -> Self {
+        Self {
+            proof: K::from(proof),
+            v,
+            degree,
+            cached_g_j: vec![],
+            challenges: vec![],
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn challenges(&self) -> Vec<K> {
+        self.challenges.clone()
+    }
+
+    fn prepare_next_round(&mut self, g_i: RoundPoly<K>) {
+        assert_eq!(self.challenges.len(), self.cached_g_j.len());
+
+        self.cached_g_j.push(g_i);
+        self.challenges.push(K::random(OsRng));
+    }
+
+    // Check: C1 = g_1(0) + g_1(1)
+    pub fn round_1(&mut self, g1: RoundPoly<K>) {
+        assert!(g1.degree() <= self.degree, "Degree too high in round_1");
+        assert_eq!(g1.sum_over_hypercube(), self.proof, "No-equal in round_1");
+
+        // prepare for next round
+        self.prepare_next_round(g1);
+    }
+
+    // 1 < j <= v
+    // check: g_j-1(r_j-1) = g_j(0) + g_j(1)
+    pub fn recursive_round_j(&mut self, j: usize, g_j: RoundPoly<K>) {
+        assert_eq!(
+            j - 1,
+            self.challenges.len(),
+            "length of challenges != (j-1)"
+        );
+        assert!(
+            g_j.degree() <= self.degree,
+            "Degree too high in round_{}",
+            j
+        );
+
+        let r = *self.challenges.last().unwrap();
+        let target = self.cached_g_j.last().unwrap().evaluate::<F>(r);
+        assert_eq!(g_j.sum_over_hypercube(), target, "Not-equal in round_{}", j);
+
+        // prepare for next round
+        self.prepare_next_round(g_j);
+    }
+
+    // gv(rv) = target, where target is claimed to be g(r1, ..., rv).
+    pub fn check(&self, target: K) {
+        assert_eq!(
+            self.v,
+            self.challenges.len(),
+            "length of challenges != (j-1)"
+        );
+        let r_v = *self.challenges.last().unwrap();
+        let actual = self.cached_g_j.last().unwrap().evaluate::<F>(r_v);
+
+        assert_eq!(actual, target, "Verifier rejected the proof");
+        println!("Verifier accepted the proof");
+    }
+}
@@ -0,0 +1,60 @@
+use rand_core::RngCore;
+use std::fmt::Debug;
+use std::iter::Sum;
+use std::ops::{Add, Mul, Neg, Sub};
+
+pub mod extension;
+pub mod goldilocks;
+
+// A minimal field interface for the protocols over small fields, eg: the 64-bit Goldilocks field.
+//
+// Over such a field F, a challenge from F gives a soundness error of about v·d/|F| per sumcheck,
+// which is too large. So the challenges are drawn from an extension K ⊇ F instead,
+// while the polynomial of P stays in F.
+pub trait Field:
+    Copy
+    + Clone
+    + Debug
+    + Default
+    + Eq
+    + PartialEq
+    + Send
+    + Sync
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+    + Sum
+{
+    fn zero() -> Self;
+
+    fn one() -> Self;
+
+    fn from_u64(n: u64) -> Self;
+
+    fn random(rng: impl RngCore) -> Self;
+
+    // None for zero.
+    fn invert(&self) -> Option<Self>;
+
+    fn to_bytes(&self) -> Vec<u8>;
+}
+
+// K as a vector space of dimension DEGREE over F.
+// The mixed arithmetic K × F is cheaper than K × K, as it's done coordinate-wise.
+pub trait ExtensionField<F: Field>: Field + From<F> + Mul<F, Output = Self> {
+    const DEGREE: usize;
+
+    // a_0 + a_1·X + ... + a_D-1·X^(D-1)
+    fn from_base_slice(coords: &[F]) -> Self;
+}
+
+// F is the trivial extension of itself, eg: to run the protocols without an extension.
+impl<F: Field> ExtensionField<F> for F {
+    const DEGREE: usize = 1;
+
+    fn from_base_slice(coords: &[F]) -> Self {
+        assert_eq!(coords.len(), 1);
+        coords[0]
+    }
+}
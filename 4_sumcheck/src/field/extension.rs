@@ -0,0 +1,202 @@
+use crate::field::goldilocks::{Goldilocks, MODULUS};
+use crate::field::{ExtensionField, Field};
+use rand_core::RngCore;
+use std::iter::Sum;
+use std::ops::{Add, Mul, Neg, Sub};
+
+// F admits the binomial extension F[X]/(X^D - W), ie: X^D - W is irreducible over F and D | p - 1.
+pub trait Extendable<const D: usize>: Field {
+    // The non-residue W.
+    fn w() -> Self;
+
+    // W^((p-1)/D), a D-th root of unity, which gives the Frobenius map X -> X^p = W^((p-1)/D)·X.
+    fn dth_root() -> Self;
+}
+
+// a_0 + a_1·X + ... + a_D-1·X^(D-1) in K = F[X]/(X^D - W).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct BinomialExtension<F: Extendable<D>, const D: usize>(pub [F; D]);
+
+// the quadratic and quartic extensions of Goldilocks, with X^2 - 7 and X^4 - 7.
+pub type GoldilocksExt2 = BinomialExtension<Goldilocks, 2>;
+pub type GoldilocksExt4 = BinomialExtension<Goldilocks, 4>;
+
+impl Extendable<2> for Goldilocks {
+    fn w() -> Self {
+        Goldilocks::new(7)
+    }
+
+    fn dth_root() -> Self {
+        <Self as Extendable<2>>::w().pow((MODULUS - 1) / 2)
+    }
+}
+
+impl Extendable<4> for Goldilocks {
+    fn w() -> Self {
+        Goldilocks::new(7)
+    }
+
+    fn dth_root() -> Self {
+        <Self as Extendable<4>>::w().pow((MODULUS - 1) / 4)
+    }
+}
+
+impl<F: Extendable<D>, const D: usize> BinomialExtension<F, D> {
+    // φ^k(a) = a^(p^k) = ∑a_j·(W^((p-1)/D))^(jk)·X^j
+    fn frobenius(&self, k: usize) -> Self {
+        let z = (0..k).fold(F::one(), |acc, _| acc * F::dth_root());
+        let mut z_j = F::one();
+        let mut coords = self.0;
+        for c in coords.iter_mut() {
+            *c = *c * z_j;
+            z_j = z_j * z;
+        }
+        Self(coords)
+    }
+}
+
+impl<F: Extendable<D>, const D: usize> Default for BinomialExtension<F, D> {
+    fn default() -> Self {
+        Self([F::zero(); D])
+    }
+}
+
+impl<F: Extendable<D>, const D: usize> Field for BinomialExtension<F, D> {
+    fn zero() -> Self {
+        Self::default()
+    }
+
+    fn one() -> Self {
+        Self::from(F::one())
+    }
+
+    fn from_u64(n: u64) -> Self {
+        Self::from(F::from_u64(n))
+    }
+
+    fn random(mut rng: impl RngCore) -> Self {
+        let mut coords = [F::zero(); D];
+        coords.iter_mut().for_each(|c| *c = F::random(&mut rng));
+        Self(coords)
+    }
+
+    // a^-1 = a'/N(a), where a' = φ(a)·...·φ^(D-1)(a) and the norm N(a) = a·a' is in F.
+    fn invert(&self) -> Option<Self> {
+        let a_prime = (1..D).fold(Self::one(), |acc, k| acc * self.frobenius(k));
+        let norm = (*self * a_prime).0[0];
+        norm.invert().map(|n| a_prime * n)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.iter().flat_map(|c| c.to_bytes()).collect()
+    }
+}
+
+impl<F: Extendable<D>, const D: usize> ExtensionField<F> for BinomialExtension<F, D> {
+    const DEGREE: usize = D;
+
+    fn from_base_slice(coords: &[F]) -> Self {
+        Self(coords.try_into().expect("One coordinate per degree"))
+    }
+}
+
+impl<F: Extendable<D>, const D: usize> From<F> for BinomialExtension<F, D> {
+    fn from(f: F) -> Self {
+        let mut coords = [F::zero(); D];
+        coords[0] = f;
+        Self(coords)
+    }
+}
+
+impl<F: Extendable<D>, const D: usize> Add for BinomialExtension<F, D> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let mut coords = self.0;
+        coords.iter_mut().zip(rhs.0).for_each(|(l, r)| *l = *l + r);
+        Self(coords)
+    }
+}
+
+impl<F: Extendable<D>, const D: usize> Sub for BinomialExtension<F, D> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self + (-rhs)
+    }
+}
+
+impl<F: Extendable<D>, const D: usize> Neg for BinomialExtension<F, D> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self(self.0.map(|c| -c))
+    }
+}
+
+// schoolbook, with X^D = W.
+impl<F: Extendable<D>, const D: usize> Mul for BinomialExtension<F, D> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let mut coords = [F::zero(); D];
+        for (i, a) in self.0.iter().enumerate() {
+            for (j, b) in rhs.0.iter().enumerate() {
+                match i + j < D {
+                    true => coords[i + j] = coords[i + j] + *a * *b,
+                    false => coords[i + j - D] = coords[i + j - D] + F::w() * *a * *b,
+                }
+            }
+        }
+        Self(coords)
+    }
+}
+
+// K × F, coordinate-wise.
+impl<F: Extendable<D>, const D: usize> Mul<F> for BinomialExtension<F, D> {
+    type Output = Self;
+    fn mul(self, rhs: F) -> Self {
+        Self(self.0.map(|c| c * rhs))
+    }
+}
+
+impl<F: Extendable<D>, const D: usize> Sum for BinomialExtension<F, D> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, x| acc + x)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand_core::OsRng;
+
+    fn test_field_axioms<K: ExtensionField<Goldilocks>>() {
+        let (a, b, c) = (K::random(OsRng), K::random(OsRng), K::random(OsRng));
+        assert_eq!((a * b) * c, a * (b * c));
+        assert_eq!(a * (b + c), a * b + a * c);
+        assert_eq!(a - b, -(b - a));
+        assert_eq!(a * a.invert().unwrap(), K::one());
+        assert_eq!(K::zero().invert(), None);
+
+        // K × F is the same as K × K on the embedding of F.
+        let f = Goldilocks::random(OsRng);
+        assert_eq!(a * f, a * K::from(f));
+        assert_eq!(K::from(f) * K::from(f), K::from(f * f));
+    }
+
+    #[test]
+    fn test_extensions() {
+        test_field_axioms::<GoldilocksExt2>();
+        test_field_axioms::<GoldilocksExt4>();
+    }
+
+    #[test]
+    fn test_non_residue() {
+        // X^D = W
+        let x = GoldilocksExt4::from_base_slice(&[0, 1, 0, 0].map(Goldilocks::new));
+        assert_eq!(x * x * x * x, GoldilocksExt4::from(Goldilocks::new(7)));
+
+        // 7 is not a square, so X^2 - 7 and X^4 - 7 are irreducible (as p = 1 mod 4).
+        assert_eq!(
+            Goldilocks::new(7).pow((MODULUS - 1) / 2),
+            -Goldilocks::one()
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use crate::field::Field;
+use rand_core::RngCore;
+use std::iter::Sum;
+use std::ops::{Add, Mul, Neg, Sub};
+
+// p = 2^64 - 2^32 + 1
+pub const MODULUS: u64 = 0xffff_ffff_0000_0001;
+
+// The Goldilocks field, whose elements are kept in [0, p).
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct Goldilocks(u64);
+
+impl Goldilocks {
+    pub const fn new(n: u64) -> Self {
+        Self(n % MODULUS)
+    }
+
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+
+    // self^exp, by square and multiply.
+    pub fn pow(&self, mut exp: u64) -> Self {
+        let (mut base, mut result) = (*self, Self::one());
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result * base;
+            }
+            base = base * base;
+            exp >>= 1;
+        }
+        result
+    }
+}
+
+impl Field for Goldilocks {
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    fn one() -> Self {
+        Self(1)
+    }
+
+    fn from_u64(n: u64) -> Self {
+        Self::new(n)
+    }
+
+    fn random(mut rng: impl RngCore) -> Self {
+        Self::new(rng.next_u64())
+    }
+
+    // a^(p-2) = a^-1, by Fermat's little theorem.
+    fn invert(&self) -> Option<Self> {
+        match self.0 {
+            0 => None,
+            _ => Some(self.pow(MODULUS - 2)),
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_le_bytes().to_vec()
+    }
+}
+
+impl Add for Goldilocks {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let sum = self.0 as u128 + rhs.0 as u128;
+        Self((sum % MODULUS as u128) as u64)
+    }
+}
+
+impl Sub for Goldilocks {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        self + (-rhs)
+    }
+}
+
+impl Neg for Goldilocks {
+    type Output = Self;
+    fn neg(self) -> Self {
+        match self.0 {
+            0 => self,
+            n => Self(MODULUS - n),
+        }
+    }
+}
+
+impl Mul for Goldilocks {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let product = self.0 as u128 * rhs.0 as u128;
+        Self((product % MODULUS as u128) as u64)
+    }
+}
+
+impl Sum for Goldilocks {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, x| acc + x)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand_core::OsRng;
+
+    #[test]
+    fn test_goldilocks() {
+        let minus_one = Goldilocks::new(MODULUS - 1);
+        assert_eq!(minus_one + Goldilocks::one(), Goldilocks::zero());
+        assert_eq!(minus_one * minus_one, Goldilocks::one());
+        assert_eq!(-Goldilocks::one(), minus_one);
+        assert_eq!(Goldilocks::new(3) - Goldilocks::new(5), -Goldilocks::new(2));
+
+        let a = Goldilocks::random(OsRng);
+        assert_eq!(a * a.invert().unwrap(), Goldilocks::one());
+        assert_eq!(Goldilocks::zero().invert(), None);
+    }
+}
@@ -0,0 +1,199 @@
+use crate::oracle::Oracle;
+use crate::poly::multivar_poly::MPolynomial;
+use crate::poly::MultivariatePolynomial;
+use crate::sumcheck::SumCheck;
+use crate::utils::convert_to_binary;
+use bls12_381::Scalar;
+use rand_core::{OsRng, RngCore};
+
+// The second frequency moment of a data stream with sumcheck(Section 4.2 of the book).
+//
+// A stream a = (a_1, ..., a_m) of items in the universe [n] (n = 2^v) defines the frequencies
+//      f_a(i) = #{k : a_k = i}
+// and F2 = ∑_{i∈{0,1}^v} f_a(i)^2, which is the sum of g(x) = f̃_a(x)^2 with degree 2 in each variable.
+//
+// 1. V picks r in F^v before reading the stream, and keeps only f̃_a(r) = ∑_k χ_{a_k}(r),
+//    which costs O(log n) per item and O(log n) space.
+// 2. P stores the whole frequency vector, claims F2 and runs the sumcheck on g,
+//    with r as the challenges which are revealed round by round.
+// 3. At the end, V checks g_v(r_v) = f̃_a(r)^2 by itself.
+pub struct FrequencyMoment {
+    sumcheck: SumCheck<SquaredMLE, StreamingVerifier>,
+}
+
+impl FrequencyMoment {
+    // Both P and V read the stream, but only P stores it.
+    pub fn new(var_num: usize, stream: &[usize]) -> Self {
+        let mut verifier = StreamingVerifier::new(var_num);
+        let mut frequencies = vec![0u64; 1 << var_num];
+        for item in stream {
+            verifier.update(*item);
+            frequencies[*item] += 1;
+        }
+
+        let g = SquaredMLE::new(var_num, &frequencies);
+        Self::from_parts(g, verifier)
+    }
+
+    fn from_parts(g: SquaredMLE, verifier: StreamingVerifier) -> Self {
+        let challenges = verifier.point.clone();
+        Self {
+            sumcheck: SumCheck::with_challenges(g, verifier, challenges),
+        }
+    }
+
+    // Return F2, once V accepts.
+    pub fn run_protocol(&mut self) -> Scalar {
+        self.sumcheck.run_protocol();
+        self.sumcheck.claim()
+    }
+}
+
+// g(x) = f̃_a(x)^2, where f̃_a is the MLE of the frequencies.
+#[derive(Debug, Clone)]
+pub struct SquaredMLE {
+    frequency_mle: MPolynomial,
+}
+
+impl SquaredMLE {
+    pub fn new(var_num: usize, frequencies: &[u64]) -> Self {
+        let evals = frequencies
+            .iter()
+            .map(|f| Scalar::from(*f))
+            .collect::<Vec<_>>();
+        Self {
+            frequency_mle: MPolynomial::lagrange(var_num, &evals),
+        }
+    }
+}
+
+impl MultivariatePolynomial for SquaredMLE {
+    fn var_num(&self) -> usize {
+        self.frequency_mle.var_num
+    }
+
+    fn degree(&self) -> usize {
+        2
+    }
+
+    fn evaluate(&self, domain: &[usize]) -> Scalar {
+        self.frequency_mle.evaluate(&domain.to_vec()).square()
+    }
+}
+
+// V's state while reading the stream: the random point r and f̃_a(r).
+// As V is also the oracle to g(r) = f̃_a(r)^2, it can only answer at r.
+pub struct StreamingVerifier {
+    point: Vec<usize>,
+    point_scalar: Vec<Scalar>,
+    value: Scalar, // f̃_a(r)
+}
+
+impl StreamingVerifier {
+    pub fn new(var_num: usize) -> Self {
+        let point = (0..var_num)
+            .map(|_| (OsRng.next_u32() % 1000) as usize)
+            .collect::<Vec<_>>();
+        Self::with_point(point)
+    }
+
+    fn with_point(point: Vec<usize>) -> Self {
+        let point_scalar = point.iter().map(|r| Scalar::from(*r as u64)).collect();
+        Self {
+            point,
+            point_scalar,
+            value: Scalar::zero(),
+        }
+    }
+
+    // f̃_a(r) += χ_item(r), χ_w(r) = ∏(r_i·w_i + (1−r_i)(1−w_i)), in O(log n).
+    pub fn update(&mut self, item: usize) {
+        let var_num = self.point.len();
+        assert!(item < 1 << var_num, "Item out of the universe");
+
+        let chi = convert_to_binary(&var_num, item)
+            .iter()
+            .zip(self.point_scalar.iter())
+            .fold(Scalar::one(), |acc, (w_i, r_i)| match w_i {
+                1 => acc * r_i,
+                _ => acc * (Scalar::one() - r_i),
+            });
+        self.value += chi;
+    }
+
+    // f̃_a(r)
+    pub fn value(&self) -> Scalar {
+        self.value
+    }
+}
+
+impl Oracle for StreamingVerifier {
+    fn query(&mut self, point: &[usize]) -> Scalar {
+        assert_eq!(point, self.point, "V only knows f̃_a at r");
+        self.value.square()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn random_stream(var_num: usize, len: usize) -> Vec<usize> {
+        (0..len)
+            .map(|_| OsRng.next_u32() as usize % (1 << var_num))
+            .collect()
+    }
+
+    fn count_f2(var_num: usize, stream: &[usize]) -> u64 {
+        let mut frequencies = vec![0u64; 1 << var_num];
+        stream.iter().for_each(|item| frequencies[*item] += 1);
+        frequencies.iter().map(|f| f * f).sum()
+    }
+
+    #[test]
+    fn test_streaming_verifier() {
+        let var_num = 4;
+        let stream = random_stream(var_num, 50);
+
+        let mut verifier = StreamingVerifier::new(var_num);
+        stream.iter().for_each(|item| verifier.update(*item));
+
+        let mut frequencies = vec![0u64; 1 << var_num];
+        stream.iter().for_each(|item| frequencies[*item] += 1);
+        let g = SquaredMLE::new(var_num, &frequencies);
+        assert_eq!(
+            verifier.value(),
+            g.frequency_mle.evaluate(&verifier.point.clone())
+        );
+    }
+
+    #[test]
+    fn test_frequency_moment() {
+        for var_num in [2, 4, 6] {
+            let stream = random_stream(var_num, 100);
+
+            let mut protocol = FrequencyMoment::new(var_num, &stream);
+            let f2 = protocol.run_protocol();
+            assert_eq!(f2, Scalar::from(count_f2(var_num, &stream)));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Verifier rejected the proof")]
+    fn test_frequency_moment_wrong_stream() {
+        let var_num = 3;
+        let stream = [0, 1, 1, 3, 5, 5, 5, 7];
+
+        let mut verifier = StreamingVerifier::with_point(vec![17, 23, 29]);
+        stream.iter().for_each(|item| verifier.update(*item));
+
+        // P works on the stream with one more item.
+        let mut frequencies = vec![0u64; 1 << var_num];
+        stream.iter().for_each(|item| frequencies[*item] += 1);
+        frequencies[2] += 1;
+
+        let mut protocol =
+            FrequencyMoment::from_parts(SquaredMLE::new(var_num, &frequencies), verifier);
+        protocol.run_protocol();
+    }
+}
@@ -1,3 +1,11 @@
+pub mod batched_sumcheck;
+pub mod commitment;
+pub mod ext_sumcheck;
+pub mod field;
+pub mod frequency_moment;
+pub mod oracle;
 pub mod poly;
+pub mod sat;
 pub mod sumcheck;
+pub mod triangle;
 pub mod utils;
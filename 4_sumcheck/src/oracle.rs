@@ -0,0 +1,149 @@
+use crate::commitment::PolynomialCommitment;
+use crate::poly::multivar_poly::MPolynomial;
+use crate::poly::MultivariatePolynomial;
+use crate::sumcheck::EvaluationClaim;
+use bls12_381::Scalar;
+
+// The oracle access to g that the sumcheck verifier needs for its final check: gv(rv) = g(r1, ..., rv).
+pub trait Oracle {
+    // Return g(point)
+    fn query(&mut self, point: &[usize]) -> Scalar;
+}
+
+// V knows g and evaluates it itself, so that the answer is trusted.
+pub struct DirectOracle<P: MultivariatePolynomial = MPolynomial> {
+    g: P,
+}
+
+impl<P: MultivariatePolynomial> DirectOracle<P> {
+    pub fn new(g: P) -> Self {
+        Self { g }
+    }
+}
+
+impl<P: MultivariatePolynomial> Oracle for DirectOracle<P> {
+    fn query(&mut self, point: &[usize]) -> Scalar {
+        self.g.evaluate(point)
+    }
+}
+
+// V takes the value claimed by P without checking it,
+// and records the claim g(point) = value, which must be discharged by the caller.
+// This is how sumcheck composes into GKR, where the claim is reduced to the next layer.
+pub struct DelegatedOracle<P: MultivariatePolynomial = MPolynomial> {
+    g: P, // held by P
+    claims: Vec<EvaluationClaim>,
+}
+
+impl<P: MultivariatePolynomial> DelegatedOracle<P> {
+    pub fn new(g: P) -> Self {
+        Self { g, claims: vec![] }
+    }
+
+    pub fn claims(&self) -> Vec<EvaluationClaim> {
+        self.claims.clone()
+    }
+}
+
+impl<P: MultivariatePolynomial> Oracle for DelegatedOracle<P> {
+    fn query(&mut self, point: &[usize]) -> Scalar {
+        let point = point.to_vec();
+        let value = self.g.evaluate(&point);
+        self.claims.push(EvaluationClaim { point, value });
+        value
+    }
+}
+
+// P commits to g before the sumcheck starts, and opens it at the point on query.
+// V accepts the value only if the opening matches the commitment.
+pub struct CommittedOracle<PCS: PolynomialCommitment> {
+    pcs: PCS,
+    commitment: PCS::Commitment, // received by V up front
+    g: MPolynomial,              // held by P
+}
+
+impl<PCS: PolynomialCommitment> CommittedOracle<PCS> {
+    pub fn new(pcs: PCS, g: MPolynomial) -> Self {
+        let commitment = pcs.commit(&g);
+        Self { pcs, commitment, g }
+    }
+
+    pub fn commitment(&self) -> PCS::Commitment {
+        self.commitment.clone()
+    }
+}
+
+impl<PCS: PolynomialCommitment> Oracle for CommittedOracle<PCS> {
+    fn query(&mut self, point: &[usize]) -> Scalar {
+        let point = point
+            .iter()
+            .map(|r| Scalar::from(*r as u64))
+            .collect::<Vec<_>>();
+
+        // P opens, V verifies, and rejects the proof on an invalid opening.
+        let (value, proof) = self.pcs.open(&self.g, &point);
+        if let Err(err) = self.pcs.verify(&self.commitment, &point, &value, &proof) {
+            panic!("{}", err);
+        }
+        value
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::commitment::hyrax::Hyrax;
+    use crate::sumcheck::SumCheck;
+    use ff::PrimeField;
+
+    fn gen_mpoly() -> MPolynomial {
+        // let g(x1, x2, x3) = 9 + 2*x3 + 3*x2 + 2 * x1 * x2 + 4* x1 * x2 * x3
+        MPolynomial {
+            var_num: 3,
+            coeffs: vec![
+                Scalar::from_u128(9),
+                Scalar::from_u128(2),
+                Scalar::from_u128(3),
+                Scalar::zero(),
+                Scalar::zero(),
+                Scalar::zero(),
+                Scalar::from_u128(2),
+                Scalar::from_u128(4),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_delegated_oracle() {
+        let g = gen_mpoly();
+
+        let mut sumcheck = SumCheck::with_oracle(g.clone(), DelegatedOracle::new(g.clone()));
+        sumcheck.run_protocol();
+
+        // the claim on g(r1, r2, r3) is left to the caller.
+        let claims = sumcheck.oracle().claims();
+        assert_eq!(claims.len(), 1);
+        assert_eq!(claims[0].point.len(), g.var_num);
+        assert_eq!(g.evaluate(&claims[0].point), claims[0].value);
+    }
+
+    #[test]
+    fn test_committed_oracle() {
+        let g = gen_mpoly();
+        let oracle = CommittedOracle::new(Hyrax::setup(g.var_num), g.clone());
+
+        let mut sumcheck = SumCheck::with_oracle(g, oracle);
+        sumcheck.run_protocol();
+    }
+
+    #[test]
+    #[should_panic(expected = "Hyrax: the opening doesn't match the commitment")]
+    fn test_committed_oracle_with_other_poly() {
+        let g = gen_mpoly();
+        let mut oracle = CommittedOracle::new(Hyrax::setup(g.var_num), g.clone());
+        // P swaps g after committing.
+        oracle.g.coeffs[0] += Scalar::one();
+
+        oracle.query(&[3, 5, 7]);
+    }
+}
@@ -1,2 +1,75 @@
+use crate::poly::univar_poly::Polynomial;
+use crate::utils::{boolean_domains, convert_to_domain, domain_size};
+use bls12_381::Scalar;
+use rayon::prelude::*;
+
+pub mod ext_poly;
+pub mod lde;
 pub mod multivar_poly;
+pub mod streaming;
 pub mod univar_poly;
+
+// A v-variate poly g that the sumcheck prover can work on.
+// Only the evaluations are required, so g can be in any form, eg: an arithmetised formula or a product of MLEs.
+// g is shared by the threads which sum over the hypercube, so it must be Sync.
+pub trait MultivariatePolynomial: Sync {
+    fn var_num(&self) -> usize;
+
+    // The max degree of g in each variable, which bounds the degree of the round polys.
+    fn degree(&self) -> usize;
+
+    fn evaluate(&self, domain: &[usize]) -> Scalar;
+
+    // Return g_j(X) = ∑g(r1, ..., r_j-1, X, x_j+1, ..., x_v), (x_j+1, ..., x_v) in hypercube.
+    fn partial_evaluate(&self, challenge_domain: &[usize]) -> Polynomial {
+        let extra_var_num = self.var_num() - challenge_domain.len() - 1;
+        self.partial_evaluate_over(challenge_domain, &boolean_domains(extra_var_num))
+    }
+
+    // Return g_j(X) = ∑g(r1, ..., r_j-1, X, x_j+1, ..., x_v), (x_j+1, ..., x_v) in H_j+1 × ... × H_v,
+    // where `domains` are the H_j+1, ..., H_v.
+    //
+    // As deg(g_j) <= degree, g_j is interpolated from its evaluations on X = 0, 1, ..., degree.
+    fn partial_evaluate_over(
+        &self,
+        challenge_domain: &[usize],
+        domains: &[Vec<usize>],
+    ) -> Polynomial {
+        let j = challenge_domain.len();
+        assert!(j < self.var_num());
+        assert_eq!(j + 1 + domains.len(), self.var_num());
+
+        let xs = (0..=self.degree()).collect::<Vec<_>>();
+        let evals = xs
+            .iter()
+            .map(|x| {
+                (0..domain_size(domains))
+                    .into_par_iter()
+                    .map(|n| {
+                        let mut domain = challenge_domain.to_vec();
+                        domain.push(*x);
+                        domain.extend(convert_to_domain(domains, n));
+                        self.evaluate(&domain)
+                    })
+                    .sum()
+            })
+            .collect::<Vec<Scalar>>();
+
+        let domains = xs.iter().map(|x| Scalar::from(*x as u64)).collect();
+        Polynomial::lagrange_interpolate(domains, evals)
+    }
+
+    // sum all the evaluations on hypercube.
+    fn sum_all_evals(&self) -> Scalar {
+        self.sum_over(&boolean_domains(self.var_num()))
+    }
+
+    // sum all the evaluations on H_1 × ... × H_v.
+    fn sum_over(&self, domains: &[Vec<usize>]) -> Scalar {
+        assert_eq!(domains.len(), self.var_num());
+        (0..domain_size(domains))
+            .into_par_iter()
+            .map(|n| self.evaluate(&convert_to_domain(domains, n)))
+            .sum()
+    }
+}
@@ -0,0 +1,151 @@
+use crate::field::{ExtensionField, Field};
+
+// The MLE of f: {0,1}^v -> E by its evaluations, with x_1 as the highest bit (the same as `convert_to_binary`).
+// E is the base field F for the polynomial of P, and the extension K once it's folded with a challenge.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MultilinearPoly<E: Field> {
+    pub var_num: usize,
+    pub evals: Vec<E>,
+}
+
+impl<E: Field> MultilinearPoly<E> {
+    pub fn new(var_num: usize, evals: Vec<E>) -> Self {
+        assert_eq!(evals.len(), 1 << var_num, "Domain is less than var_num");
+        Self { var_num, evals }
+    }
+
+    // f(0, x_2, ..., x_v) and f(1, x_2, ..., x_v)
+    pub fn halves(&self) -> (&[E], &[E]) {
+        self.evals.split_at(self.evals.len() / 2)
+    }
+
+    // f(r, x_2, ..., x_v) = f(0, x_2, ..., x_v) + r·(f(1, x_2, ..., x_v) - f(0, x_2, ..., x_v)), with r in K.
+    // The evals are in E and r is in K, so it costs one K × E product per point.
+    pub fn fold<K: ExtensionField<E>>(&self, r: K) -> MultilinearPoly<K> {
+        assert!(self.var_num > 0);
+        let (lo, hi) = self.halves();
+        let evals = lo
+            .iter()
+            .zip(hi)
+            .map(|(l, h)| r * (*h - *l) + K::from(*l))
+            .collect();
+        MultilinearPoly::new(self.var_num - 1, evals)
+    }
+
+    // f(r_1, ..., r_v), by folding x_1, ..., x_v in turn.
+    pub fn evaluate<K: ExtensionField<E>>(&self, point: &[K]) -> K {
+        assert_eq!(point.len(), self.var_num, "Domain is less than var_num");
+        match point.split_first() {
+            None => K::from(self.evals[0]),
+            Some((r_1, rest)) => self.fold(*r_1).evaluate(rest),
+        }
+    }
+
+    pub fn sum_all_evals(&self) -> E {
+        self.evals.iter().cloned().sum()
+    }
+}
+
+// A round poly g_j of degree d, by its evaluations g_j(0), g_j(1), ..., g_j(d) in K.
+// The points are small integers, so V interpolates g_j(r) with the base field inverses only.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RoundPoly<K: Field> {
+    pub evals: Vec<K>,
+}
+
+impl<K: Field> RoundPoly<K> {
+    pub fn new(evals: Vec<K>) -> Self {
+        assert!(!evals.is_empty());
+        Self { evals }
+    }
+
+    pub fn degree(&self) -> usize {
+        self.evals.len() - 1
+    }
+
+    // g_j(0) + g_j(1)
+    pub fn sum_over_hypercube(&self) -> K {
+        match self.evals.len() {
+            1 => self.evals[0] + self.evals[0],
+            _ => self.evals[0] + self.evals[1],
+        }
+    }
+
+    // g_j(r) = ∑g_j(i)·L_i(r), L_i(X) = ∏(X−k)/(i−k), k != i.
+    pub fn evaluate<F: Field>(&self, r: K) -> K
+    where
+        K: ExtensionField<F>,
+    {
+        let points = (0..self.evals.len() as u64)
+            .map(F::from_u64)
+            .collect::<Vec<_>>();
+        points
+            .iter()
+            .zip(self.evals.iter())
+            .enumerate()
+            .map(|(i, (x_i, y_i))| {
+                let (numerator, denominator) = points
+                    .iter()
+                    .enumerate()
+                    .filter(|(k, _)| *k != i)
+                    .fold((K::one(), F::one()), |(num, den), (_, x_k)| {
+                        (num * (r - K::from(*x_k)), den * (*x_i - *x_k))
+                    });
+                *y_i * numerator * denominator.invert().unwrap()
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::field::extension::GoldilocksExt2;
+    use crate::field::goldilocks::Goldilocks;
+    use rand_core::OsRng;
+
+    #[test]
+    fn test_fold_and_evaluate() {
+        let var_num = 3;
+        let evals = (0..1 << var_num)
+            .map(|_| Goldilocks::random(OsRng))
+            .collect::<Vec<_>>();
+        let f = MultilinearPoly::new(var_num, evals.clone());
+
+        // on the hypercube, the MLE agrees with f, whether the point is in F or K.
+        for (n, f_n) in evals.iter().enumerate() {
+            let point = (0..var_num)
+                .rev()
+                .map(|i| Goldilocks::from_u64((n >> i) as u64 & 1))
+                .collect::<Vec<_>>();
+            assert_eq!(f.evaluate(&point), *f_n);
+            let point = point
+                .into_iter()
+                .map(GoldilocksExt2::from)
+                .collect::<Vec<_>>();
+            assert_eq!(f.evaluate(&point), GoldilocksExt2::from(*f_n));
+        }
+    }
+
+    #[test]
+    fn test_round_poly() {
+        // g(X) = 3X^2 + 2X + 1
+        let g = |x: u64| 3 * x * x + 2 * x + 1;
+        let round_poly = RoundPoly::new(
+            (0..3)
+                .map(|x| GoldilocksExt2::from_u64(g(x)))
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(round_poly.sum_over_hypercube(), GoldilocksExt2::from_u64(7));
+        assert_eq!(
+            round_poly.evaluate::<Goldilocks>(GoldilocksExt2::from_u64(10)),
+            GoldilocksExt2::from_u64(g(10))
+        );
+
+        let r = GoldilocksExt2::random(OsRng);
+        let expected = GoldilocksExt2::from_u64(3) * r * r
+            + GoldilocksExt2::from_u64(2) * r
+            + GoldilocksExt2::one();
+        assert_eq!(round_poly.evaluate::<Goldilocks>(r), expected);
+    }
+}
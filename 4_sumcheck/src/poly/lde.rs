@@ -0,0 +1,109 @@
+use crate::poly::MultivariatePolynomial;
+use crate::utils::{convert_to_domain, domain_size};
+use bls12_381::Scalar;
+
+// The low-degree extension of f: {0, ..., k-1}^v -> F, which has degree k-1 in each variable:
+//      f̃(x_1, ..., x_v) = ∑f(w)·∏L_{w_i}(x_i),  w in {0, ..., k-1}^v
+// where L_h is the univariate lagrange basis on {0, ..., k-1}:
+//      L_h(x) = ∏_{t≠h} (x - t)/(h - t)
+//
+// With k = 2, it's the MLE. The sumcheck over {0, ..., k-1}^v proves ∑f(w) with v = log_k(n) rounds.
+#[derive(Debug, Clone)]
+pub struct LowDegreeExtension {
+    k: usize,
+    var_num: usize,
+    evals: Vec<Scalar>, // f(w), indexed by w in mixed radix, the same as `convert_to_domain`.
+}
+
+impl LowDegreeExtension {
+    pub fn new(k: usize, var_num: usize, evals: Vec<Scalar>) -> Self {
+        assert!(k >= 2, "The domain should contain at least 2 points");
+        assert_eq!(evals.len(), k.pow(var_num as u32), "Evals size mismatch");
+        Self { k, var_num, evals }
+    }
+
+    // H_1 = ... = H_v = {0, ..., k-1}
+    pub fn domains(&self) -> Vec<Vec<usize>> {
+        vec![(0..self.k).collect(); self.var_num]
+    }
+
+    // L_0(x), ..., L_k-1(x)
+    fn lagrange_basis(&self, x: usize) -> Vec<Scalar> {
+        let x = Scalar::from(x as u64);
+        (0..self.k)
+            .map(|h| {
+                let h_scalar = Scalar::from(h as u64);
+                (0..self.k)
+                    .filter(|t| *t != h)
+                    .fold(Scalar::one(), |acc, t| {
+                        let t = Scalar::from(t as u64);
+                        acc * (x - t) * (h_scalar - t).invert().unwrap()
+                    })
+            })
+            .collect()
+    }
+}
+
+impl MultivariatePolynomial for LowDegreeExtension {
+    fn var_num(&self) -> usize {
+        self.var_num
+    }
+
+    fn degree(&self) -> usize {
+        self.k - 1
+    }
+
+    fn evaluate(&self, domain: &[usize]) -> Scalar {
+        assert_eq!(domain.len(), self.var_num, "Domain is less than var_num");
+        let basis = domain
+            .iter()
+            .map(|x| self.lagrange_basis(*x))
+            .collect::<Vec<_>>();
+
+        let domains = self.domains();
+        (0..domain_size(&domains))
+            .map(|n| {
+                let w = convert_to_domain(&domains, n);
+                w.iter()
+                    .zip(basis.iter())
+                    .fold(self.evals[n], |acc, (w_i, l)| acc * l[*w_i])
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::poly::multivar_poly::MPolynomial;
+    use ff::Field;
+    use rand_core::OsRng;
+
+    #[test]
+    fn test_lde() {
+        let (k, var_num) = (3, 2);
+        let evals = (0..9).map(|_| Scalar::random(OsRng)).collect::<Vec<_>>();
+        let lde = LowDegreeExtension::new(k, var_num, evals.clone());
+
+        // f̃ agrees with f on the grid.
+        for (n, eval) in evals.iter().enumerate() {
+            let w = convert_to_domain(&lde.domains(), n);
+            assert_eq!(&lde.evaluate(&w), eval);
+        }
+
+        // deg 2 in x_1: f̃(·, x_2) is determined by 3 points.
+        let g_1 = lde.partial_evaluate_over(&[], &[vec![7]]);
+        assert_eq!(g_1.evaluate(Scalar::from(11)), lde.evaluate(&[11, 7]));
+    }
+
+    #[test]
+    fn test_lde_with_k_2_is_mle() {
+        let var_num = 3;
+        let evals = (0..8).map(|_| Scalar::random(OsRng)).collect::<Vec<_>>();
+        let lde = LowDegreeExtension::new(2, var_num, evals.clone());
+        let mle = MPolynomial::lagrange(var_num, &evals);
+
+        let point = vec![5, 9, 13];
+        assert_eq!(lde.evaluate(&point), mle.evaluate(&point));
+    }
+}
@@ -1,9 +1,10 @@
 use crate::poly::univar_poly::Polynomial;
-use crate::utils::{convert_to_binary, expand_factor_for_mpoly};
+use crate::poly::MultivariatePolynomial;
+use crate::utils::{convert_to_binary, eq_table, expand_factor_for_mpoly};
 use bls12_381::Scalar;
 use ff::Field;
 use log::{debug, log};
-use std::collections::HashMap;
+use rayon::prelude::*;
 use std::hash::Hash;
 use std::ops::{Add, AddAssign};
 
@@ -25,21 +26,30 @@ impl MPolynomial {
         let n: usize = 1 << var_num;
         assert_eq!(evals.len(), n, "Domain is less than var_num");
 
-        let mut F = vec![Scalar::zero(); n];
-
-        // compute f_i = f_w * X_w
-        for (i, f_w) in evals.iter().enumerate() {
-            let w_i = convert_to_binary(&var_num, i);
-            // X_w(x1,...,xv) := ∏(xiwi +(1−xi)(1−wi)).
-            let X_w = Self::mpoly_langrange_basis(var_num, w_i);
-            // f_i = f(w)·X_w
-            let f_i = X_w.iter().map(|X_w_i| X_w_i * f_w).collect::<Vec<_>>();
-
-            // F = ∑f_j
-            for i in 0..n {
-                F[i].add_assign(f_i[i]);
-            }
-        }
+        // compute f_i = f_w * X_w in parallel, and F = ∑f_i
+        let F = evals
+            .par_iter()
+            .enumerate()
+            .fold(
+                || vec![Scalar::zero(); n],
+                |mut F, (i, f_w)| {
+                    let w_i = convert_to_binary(&var_num, i);
+                    // X_w(x1,...,xv) := ∏(xiwi +(1−xi)(1−wi)).
+                    let X_w = Self::mpoly_langrange_basis(var_num, w_i);
+                    // F += f(w)·X_w
+                    for (F_i, X_w_i) in F.iter_mut().zip(X_w) {
+                        F_i.add_assign(X_w_i * f_w);
+                    }
+                    F
+                },
+            )
+            .reduce(
+                || vec![Scalar::zero(); n],
+                |mut lhs, rhs| {
+                    lhs.iter_mut().zip(rhs).for_each(|(l, r)| l.add_assign(r));
+                    lhs
+                },
+            );
         Self { var_num, coeffs: F }
     }
 
@@ -58,6 +68,10 @@ impl MPolynomial {
     pub fn mpoly_langrange_basis(var_num: usize, w: Vec<usize>) -> Vec<Scalar> {
         assert_eq!(var_num, w.len());
         let poly_len = 1 << var_num;
+        // a constant, eg: the single gate of a layer.
+        if var_num == 0 {
+            return vec![Scalar::one()];
+        }
 
         // eg: if var_num = 4, w=(0, 0, 1, 1), so that X_w(0,0,1,1)=(1-x_1)(1-x_2) * x_3 * x_4
         // factors as below:
@@ -121,33 +135,73 @@ impl MPolynomial {
                 let exps = convert_to_binary(&self.var_num, index);
 
                 // compute product of x , eg: product_x = (x_1^exp1) * (x_2^exp2)
-                let mut product = 1;
-                for (x_i, exp_i) in domain.into_iter().zip(exps) {
-                    let x = x_i.clone();
-
+                // NOTE: it's in F, as the challenges multiply out of usize with a few variables.
+                let mut product = Scalar::one();
+                for (x_i, exp_i) in domain.iter().zip(exps) {
                     // Note, as the definition, the exp is in [0, 1]
-                    // if exp != 0 && x != 0 {
-                    product *= x.pow(exp_i as u32);
+                    if exp_i == 0 {
+                        continue;
+                    }
 
                     // once product, the computation of product is over. As zero multiple anything is zero.
-                    if 0 == product {
+                    if *x_i == 0 {
+                        product = Scalar::zero();
                         break;
                     }
+                    product *= Scalar::from(*x_i as u64);
                 }
 
-                match product {
-                    0 => continue,
-                    1 => sum_of_term += coeff,
-                    _ => {
-                        let term_i = coeff.mul(&Scalar::from(product as u64));
-                        sum_of_term.add_assign(term_i);
-                    }
+                if product != Scalar::zero() {
+                    sum_of_term.add_assign(coeff.mul(&product));
                 }
             }
         }
         sum_of_term
     }
 
+    // The evaluations on the hypercube, the inverse of `lagrange`.
+    // f(w) = ∑c_e, e ⊆ w (as bits), which takes O(v·2^v) by summing in one variable at a time.
+    pub fn evals(&self) -> Vec<Scalar> {
+        let mut evals = self.coeffs.clone();
+        for i in 0..self.var_num {
+            let bit = 1 << i;
+            for w in 0..evals.len() {
+                if w & bit != 0 {
+                    let lower = evals[w ^ bit];
+                    evals[w] += lower;
+                }
+            }
+        }
+        evals
+    }
+
+    // The same as `lagrange`, but in O(v·2^v) by the inverse of `evals`:
+    // c_w = ∑(-1)^|w - e|·f(e), e ⊆ w, subtracting in one variable at a time.
+    pub fn from_evals(var_num: usize, evals: &[Scalar]) -> Self {
+        assert_eq!(evals.len(), 1 << var_num, "Domain is less than var_num");
+        let mut coeffs = evals.to_vec();
+        for i in 0..var_num {
+            let bit = 1 << i;
+            for w in 0..coeffs.len() {
+                if w & bit != 0 {
+                    let lower = coeffs[w ^ bit];
+                    coeffs[w] -= lower;
+                }
+            }
+        }
+        Self { var_num, coeffs }
+    }
+
+    // Evaluate on a point in F^v instead of the usize domain, by f(r) = ∑f(w)·χ_w(r).
+    pub fn evaluate_at(&self, point: &[Scalar]) -> Scalar {
+        assert_eq!(point.len(), self.var_num, "Domain is less than var_num");
+        self.evals()
+            .iter()
+            .zip(eq_table(point))
+            .map(|(f, chi)| f * chi)
+            .sum()
+    }
+
     // Convert a multi-poly into a uni-poly:
     //      f(x1, x2, x3, x4) , x1,x2,x3,x4 in hypercube
     //      With inputs(r1,r2,X,x4), the multi-poly become a uni-poly p(X)
@@ -162,9 +216,6 @@ impl MPolynomial {
         let j = challenge_domain.len();
         assert!(j < self.var_num);
 
-        // <k,v>: k is the exp of X, v is the coeff, aka. <exp, coeff>
-        let mut map: HashMap<usize, Scalar> = HashMap::new();
-
         // var_num = challenger_len + 1 + extra_len
         let extra_var_num = self.var_num - j - 1;
         let extra_n = 1 << extra_var_num;
@@ -177,81 +228,74 @@ impl MPolynomial {
             extra_domain, j, self.var_num, extra_var_num, extra_n
         );
 
-        // compute each term_i: coeff * product_x * X(x_j)
-        for (index, coeff) in self.coeffs.iter().enumerate() {
+        // compute each term_i: coeff * product_x * X(x_j), with the terms split among the threads.
+        // coeffs[k] is the coeff of X^k, as the definition, k is in [0, 1].
+        let mut coeffs = self
+            .coeffs
+            .par_iter()
+            .enumerate()
             // if the coeff is 0, then skip it.
-            if coeff.eq(&Scalar::zero()) {
-                continue;
-            }
-
-            // x_0^exps[0] * x_1^exps[1] * x_2^exps[2]+ ...
-            let exps = convert_to_binary(&self.var_num, index);
-
-            // compute product_x on challenge_domain + hypercube_domain[i]
-            for extra in extra_domain.clone() {
-                // if index is 0, then term = coeff.
-                if index == 0 {
-                    map.entry(0)
-                        .and_modify(|v| v.add_assign(&coeff.clone()))
-                        .or_insert(coeff.clone());
-
-                    continue;
-                }
-
-                // compute product of x , eg: product_x = (x_1^exp1) * (x_2^exp2), except x_j
-                let mut key = 0;
-                let mut product = 1;
-
-                // evaluate on domain + hypercube_i
-                let mut domain = challenge_domain.clone();
-                domain.push(0);
-                domain.extend(extra.clone());
-                debug!(
-                    "coeff:{:?}, domain:{:?}, j: {:?}, exps: {:?}",
-                    coeff, domain, j, exps
-                );
-                for (index, (xi, exp)) in domain.iter().zip(exps.clone()).enumerate() {
-                    if index == j {
-                        key = exp.clone();
-                    } else {
-                        let pro = xi.pow(exp.clone() as u32);
-                        debug!("x_{:?}^exp: {:?}^{:?}={:?}", index + 1, xi, exp, pro);
-                        product *= pro;
-                        // product *= xi.pow(exp.clone() as u32);
-                    }
-                    // once product, the computation of product is over. As zero multiple anything is zero.
-                    if 0 == product {
-                        break;
+            .filter(|(_, coeff)| **coeff != Scalar::zero())
+            .fold(
+                || vec![Scalar::zero(); 2],
+                |mut coeffs, (index, coeff)| {
+                    // x_0^exps[0] * x_1^exps[1] * x_2^exps[2]+ ...
+                    let exps = convert_to_binary(&self.var_num, index);
+
+                    // compute product_x on challenge_domain + hypercube_domain[i]
+                    for extra in extra_domain.iter() {
+                        // if index is 0, then term = coeff.
+                        if index == 0 {
+                            coeffs[0] += coeff;
+                            continue;
+                        }
+
+                        // compute product of x , eg: product_x = (x_1^exp1) * (x_2^exp2), except x_j
+                        // NOTE: it's in F, as the challenges multiply out of usize with a few variables.
+                        let mut key = 0;
+                        let mut product = Scalar::one();
+
+                        // evaluate on domain + hypercube_i
+                        let mut domain = challenge_domain.clone();
+                        domain.push(0);
+                        domain.extend(extra.clone());
+                        debug!(
+                            "coeff:{:?}, domain:{:?}, j: {:?}, exps: {:?}",
+                            coeff, domain, j, exps
+                        );
+                        for (index, (xi, exp)) in domain.iter().zip(exps.iter()).enumerate() {
+                            if index == j {
+                                key = *exp;
+                            } else if *exp != 0 {
+                                // once product, the computation of product is over. As zero multiple anything is zero.
+                                if *xi == 0 {
+                                    product = Scalar::zero();
+                                    break;
+                                }
+                                product *= Scalar::from(*xi as u64);
+                            }
+                        }
+                        if product != Scalar::zero() {
+                            let term_i = coeff * product;
+                            debug!("k:{:?}, v:{:?}", key, term_i);
+                            coeffs[key] += term_i;
+                        }
                     }
-                }
-                if 0 == product {
-                    continue;
-                } else {
-                    let term_i = coeff.mul(&Scalar::from(product as u64));
-                    debug!("k:{:?}, v:{:?}", key, term_i);
-                    map.entry(key)
-                        .and_modify(|v| v.add_assign(&term_i))
-                        .or_insert(term_i);
-                }
-            }
+                    coeffs
+                },
+            )
+            .reduce(
+                || vec![Scalar::zero(); 2],
+                |mut lhs, rhs| {
+                    lhs.iter_mut().zip(rhs).for_each(|(l, r)| *l += r);
+                    lhs
+                },
+            );
+
+        // drop the zero leading coeffs, but keep the constant one.
+        while coeffs.len() > 1 && coeffs.last() == Some(&Scalar::zero()) {
+            coeffs.pop();
         }
-
-        // map -> poly
-        let coeffs = if map.is_empty() {
-            vec![Scalar::zero()]
-        } else {
-            let max_key = map.keys().max().unwrap().clone();
-            let coeffs = (0..=max_key)
-                .map(|i: usize| {
-                    if map.contains_key(&i) {
-                        map.get(&i).unwrap().clone()
-                    } else {
-                        Scalar::zero()
-                    }
-                })
-                .collect::<Vec<_>>();
-            coeffs
-        };
         Polynomial { coeffs }
     }
 
@@ -260,6 +304,7 @@ impl MPolynomial {
     pub fn sum_all_evals(&self) -> Scalar {
         let n = 1 << self.var_num;
         (0..n)
+            .into_par_iter()
             .map(|i| {
                 let domain = convert_to_binary(&self.var_num, i);
                 self.evaluate(&domain)
@@ -268,13 +313,37 @@ impl MPolynomial {
     }
 }
 
+impl MultivariatePolynomial for MPolynomial {
+    fn var_num(&self) -> usize {
+        self.var_num
+    }
+
+    // multilinear
+    fn degree(&self) -> usize {
+        1
+    }
+
+    fn evaluate(&self, domain: &[usize]) -> Scalar {
+        MPolynomial::evaluate(self, &domain.to_vec())
+    }
+
+    fn partial_evaluate(&self, challenge_domain: &[usize]) -> Polynomial {
+        MPolynomial::partial_evaluate(self, &challenge_domain.to_vec())
+    }
+
+    fn sum_all_evals(&self) -> Scalar {
+        MPolynomial::sum_all_evals(self)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::poly::multivar_poly::MPolynomial;
     use crate::poly::univar_poly::Polynomial;
     use crate::utils::convert_to_binary;
     use bls12_381::Scalar;
-    use ff::PrimeField;
+    use ff::{Field, PrimeField};
+    use rand_core::OsRng;
 
     fn gen_mpoly() -> MPolynomial {
         // let g(x1, x2, x3) = 5 + 2*x3 + 3*x2 +  x1 * x2 * x3
@@ -340,6 +409,27 @@ mod test {
         println!("poly: {:?}", poly);
     }
 
+    #[test]
+    fn test_evals_and_evaluate_at() {
+        let mpoly = gen_mpoly();
+        let evals = mpoly.evals();
+        assert_eq!(MPolynomial::lagrange(3, &evals), mpoly);
+        assert_eq!(MPolynomial::from_evals(3, &evals), mpoly);
+
+        let evals = (0..16).map(|_| Scalar::random(OsRng)).collect::<Vec<_>>();
+        assert_eq!(
+            MPolynomial::from_evals(4, &evals),
+            MPolynomial::lagrange(4, &evals)
+        );
+
+        let point = vec![3, 5, 7];
+        let point_scalar = point
+            .iter()
+            .map(|x| Scalar::from(*x as u64))
+            .collect::<Vec<_>>();
+        assert_eq!(mpoly.evaluate_at(&point_scalar), mpoly.evaluate(&point));
+    }
+
     #[test]
     fn test_partial_evaluate() {
         let mpoly = gen_mpoly();
@@ -361,6 +451,30 @@ mod test {
         assert_eq!(target_evaluation, actual_evaluation)
     }
 
+    #[test]
+    fn test_partial_evaluate_large_challenges() {
+        // the product of 8 challenges around 2^32 is out of usize, so it must be computed in F.
+        let var_num = 10;
+        let evals = (0..1 << var_num)
+            .map(|_| Scalar::random(OsRng))
+            .collect::<Vec<_>>();
+        let mpoly = MPolynomial::from_evals(var_num, &evals);
+
+        let challenge_domain = (0..8).map(|i| (1 << 32) + i).collect::<Vec<usize>>();
+        let actual = mpoly.partial_evaluate(&challenge_domain);
+
+        // t(X) = ∑ g(r_1, ..., r_8, X, x_10), x_10 in {0, 1}
+        let x = 7;
+        let target = (0..2)
+            .map(|x_10| {
+                let mut domain = challenge_domain.clone();
+                domain.extend([x, x_10]);
+                mpoly.evaluate(&domain)
+            })
+            .sum::<Scalar>();
+        assert_eq!(actual.evaluate(Scalar::from(x as u64)), target);
+    }
+
     #[test]
     fn test_evaluate() {
         // let g(x1, x2, x3) = 5 + 2*x3 + 3*x2 +  x1 * x2 * x3
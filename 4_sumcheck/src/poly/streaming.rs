@@ -0,0 +1,231 @@
+use crate::poly::univar_poly::Polynomial;
+use crate::poly::MultivariatePolynomial;
+use bls12_381::Scalar;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// The evaluations f(w) of g on the hypercube, in the order of `convert_to_binary` (x_1 as the highest bit).
+// The stream can be read once per pass, but is never held in memory by the prover.
+pub trait EvaluationStream: Sync {
+    fn var_num(&self) -> usize;
+
+    // Start a new pass over f(0...0), f(0...1), ..., f(1...1).
+    fn stream(&self) -> Box<dyn Iterator<Item = Scalar> + '_>;
+}
+
+// The evaluations are produced on the fly by a callback, eg: reading a file or recomputing a witness.
+#[derive(Clone)]
+pub struct FnStream<F: Fn(usize) -> Scalar> {
+    var_num: usize,
+    f: F,
+}
+
+impl<F: Fn(usize) -> Scalar> FnStream<F> {
+    pub fn new(var_num: usize, f: F) -> Self {
+        Self { var_num, f }
+    }
+}
+
+impl<F: Fn(usize) -> Scalar + Sync> EvaluationStream for FnStream<F> {
+    fn var_num(&self) -> usize {
+        self.var_num
+    }
+
+    fn stream(&self) -> Box<dyn Iterator<Item = Scalar> + '_> {
+        Box::new((0..1 << self.var_num).map(&self.f))
+    }
+}
+
+// The evaluations come from an iterator, which is restarted by `new_iter` for each pass.
+#[derive(Clone)]
+pub struct IterStream<I: Iterator<Item = Scalar>, F: Fn() -> I> {
+    var_num: usize,
+    new_iter: F,
+}
+
+impl<I: Iterator<Item = Scalar>, F: Fn() -> I> IterStream<I, F> {
+    pub fn new(var_num: usize, new_iter: F) -> Self {
+        Self { var_num, new_iter }
+    }
+}
+
+impl<I: Iterator<Item = Scalar>, F: Fn() -> I + Sync> EvaluationStream for IterStream<I, F> {
+    fn var_num(&self) -> usize {
+        self.var_num
+    }
+
+    fn stream(&self) -> Box<dyn Iterator<Item = Scalar> + '_> {
+        Box::new((self.new_iter)().take(1 << self.var_num))
+    }
+}
+
+// The MLE of a stream of evaluations, for the streaming sumcheck prover (Cormode, Thaler and Yi).
+//
+// Each query is answered with a single pass over the stream in O(v) space:
+//      g(r) = ∑f(w)·χ_w(r)
+//      g_j(X) = ∑f(w)·χ_{w_1..w_j-1}(r_1, ..., r_j-1),  for w_j = X in {0,1}
+// where χ of the prefix only changes every 2^(v-j+1) items, so a pass costs O(2^v).
+//
+// So the prover of the sumcheck makes v+1 passes, in O(v·2^v) time and O(v) space,
+// instead of holding all the 2^v evaluations. The round polys are the same as the ones from the dense MLE.
+pub struct StreamingMLE<S: EvaluationStream> {
+    source: S,
+    passes: AtomicUsize,
+}
+
+impl<S: EvaluationStream> StreamingMLE<S> {
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            passes: AtomicUsize::new(0),
+        }
+    }
+
+    // The number of passes over the stream so far.
+    pub fn passes(&self) -> usize {
+        self.passes.load(Ordering::Relaxed)
+    }
+
+    // ∑f(w)·χ_{w_1..w_j}(r_1, ..., r_j) for each suffix bucket (w_j+1, ..., w_j+k), with j = point.len().
+    // eg: k = 0 gives g(r) on a full point, and k = 1 gives g_j+1(0), g_j+1(1).
+    fn fold(&self, point: &[usize], k: usize) -> Vec<Scalar> {
+        let var_num = self.source.var_num();
+        assert!(point.len() + k <= var_num);
+        self.passes.fetch_add(1, Ordering::Relaxed);
+
+        let point = point
+            .iter()
+            .map(|r| Scalar::from(*r as u64))
+            .collect::<Vec<_>>();
+        let tail = var_num - point.len();
+        let mut buckets = vec![Scalar::zero(); 1 << k];
+
+        // χ of the current prefix, recomputed when the prefix changes.
+        let mut prefix = usize::MAX;
+        let mut chi = Scalar::zero();
+        for (n, f) in self.source.stream().enumerate() {
+            if n >> tail != prefix {
+                prefix = n >> tail;
+                chi = point.iter().enumerate().fold(Scalar::one(), |acc, (i, r)| {
+                    match (prefix >> (point.len() - 1 - i)) & 1 {
+                        1 => acc * r,
+                        _ => acc * (Scalar::one() - r),
+                    }
+                });
+            }
+            let bucket = (n >> (tail - k)) & ((1 << k) - 1);
+            buckets[bucket] += f * chi;
+        }
+        buckets
+    }
+}
+
+impl<S: EvaluationStream + Clone> Clone for StreamingMLE<S> {
+    fn clone(&self) -> Self {
+        Self {
+            source: self.source.clone(),
+            passes: AtomicUsize::new(self.passes()),
+        }
+    }
+}
+
+impl<S: EvaluationStream> MultivariatePolynomial for StreamingMLE<S> {
+    fn var_num(&self) -> usize {
+        self.source.var_num()
+    }
+
+    // multilinear
+    fn degree(&self) -> usize {
+        1
+    }
+
+    fn evaluate(&self, domain: &[usize]) -> Scalar {
+        assert_eq!(domain.len(), self.var_num(), "Domain is less than var_num");
+        self.fold(domain, 0)[0]
+    }
+
+    // g_j(X) = g_j(0) + (g_j(1) - g_j(0))·X
+    fn partial_evaluate(&self, challenge_domain: &[usize]) -> Polynomial {
+        assert!(challenge_domain.len() < self.var_num());
+        let evals = self.fold(challenge_domain, 1);
+        Polynomial::from_coeffs(vec![evals[0], evals[1] - evals[0]])
+    }
+
+    fn sum_all_evals(&self) -> Scalar {
+        self.fold(&[], 0)[0]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::oracle::DirectOracle;
+    use crate::poly::multivar_poly::MPolynomial;
+    use crate::sumcheck::prover::Prover;
+    use crate::sumcheck::SumCheck;
+    use ff::PrimeField;
+
+    // f(w) = w^2 + 1
+    fn gen_stream(var_num: usize) -> FnStream<impl Fn(usize) -> Scalar + Clone> {
+        FnStream::new(var_num, |w| Scalar::from_u128((w * w + 1) as u128))
+    }
+
+    #[test]
+    fn test_streaming_mle() {
+        let var_num = 4;
+        let g = StreamingMLE::new(gen_stream(var_num));
+        let evals = gen_stream(var_num).stream().collect::<Vec<_>>();
+        let mle = MPolynomial::lagrange(var_num, &evals);
+
+        let point = vec![3, 5, 7, 11];
+        assert_eq!(g.evaluate(&point), mle.evaluate(&point));
+        assert_eq!(g.sum_all_evals(), mle.sum_all_evals());
+
+        // the same MLE from an iterator.
+        let h = StreamingMLE::new(IterStream::new(var_num, || evals.iter().cloned()));
+        assert_eq!(h.evaluate(&point), mle.evaluate(&point));
+    }
+
+    #[test]
+    fn test_streaming_prover_same_round_polys() {
+        let var_num = 5;
+        let evals = gen_stream(var_num).stream().collect::<Vec<_>>();
+        let dense = Prover::new(MPolynomial::lagrange(var_num, &evals));
+        let streaming = Prover::new(StreamingMLE::new(gen_stream(var_num)));
+
+        assert_eq!(dense.proof(), streaming.proof());
+        let challenges = [17, 23, 29, 31, 37];
+        for j in 0..var_num {
+            let (g_j, h_j) = (
+                dense.recursive_round_j(&challenges[..j]),
+                streaming.recursive_round_j(&challenges[..j]),
+            );
+            for x in [0, 1, 41] {
+                let x = Scalar::from(x);
+                assert_eq!(g_j.evaluate(x), h_j.evaluate(x));
+            }
+        }
+    }
+
+    #[test]
+    fn test_streaming_sumcheck() {
+        let var_num = 6;
+        let g = StreamingMLE::new(gen_stream(var_num));
+        let oracle = DirectOracle::new(StreamingMLE::new(gen_stream(var_num)));
+
+        let mut sumcheck = SumCheck::with_oracle(g, oracle);
+        sumcheck.run_protocol();
+    }
+
+    #[test]
+    fn test_passes() {
+        let var_num = 6;
+        let g = StreamingMLE::new(gen_stream(var_num));
+
+        // the prover reads the stream once for the claim, and once per round.
+        g.sum_all_evals();
+        for j in 0..var_num {
+            g.partial_evaluate(&vec![7; j]);
+        }
+        assert_eq!(g.passes(), var_num + 1);
+    }
+}
@@ -0,0 +1,209 @@
+use crate::poly::MultivariatePolynomial;
+use crate::sat::formula::Formula;
+use crate::sumcheck::SumCheck;
+use bls12_381::Scalar;
+
+pub mod formula;
+
+// #SAT with sumcheck(Section 4.2 of the book).
+//
+// Arithmetise a boolean formula φ into a poly g over F, which agrees with φ on the hypercube {0,1}^n:
+//      x ∧ y  → x·y
+//      x ∨ y  → x + y − x·y
+//      ¬x     → 1 − x
+// So that the number of satisfying assignments is #SAT = ∑_{x∈{0,1}^n} g(x), which is proved by the sumcheck.
+//
+// The degree of g in x_i is at most the occurrences of x_i in φ, which is more than one in general.
+// V evaluates g(r1, ..., rn) itself in time O(|φ|).
+#[derive(Debug, Clone)]
+pub struct ArithmetizedFormula {
+    formula: Formula,
+    var_num: usize,
+    degree: usize,
+}
+
+impl ArithmetizedFormula {
+    pub fn new(formula: Formula, var_num: usize) -> Self {
+        assert!(formula.var_num() <= var_num);
+        let degree = formula
+            .occurrences(var_num)
+            .into_iter()
+            .max()
+            .unwrap_or(0)
+            .max(1);
+
+        Self {
+            formula,
+            var_num,
+            degree,
+        }
+    }
+
+    fn arithmetize(formula: &Formula, point: &[Scalar]) -> Scalar {
+        match formula {
+            Formula::Var(i) => point[*i],
+            Formula::Not(f) => Scalar::one() - Self::arithmetize(f, point),
+            Formula::And(fs) => fs
+                .iter()
+                .fold(Scalar::one(), |acc, f| acc * Self::arithmetize(f, point)),
+            Formula::Or(fs) => fs.iter().fold(Scalar::zero(), |acc, f| {
+                let y = Self::arithmetize(f, point);
+                acc + y - acc * y
+            }),
+        }
+    }
+}
+
+impl MultivariatePolynomial for ArithmetizedFormula {
+    fn var_num(&self) -> usize {
+        self.var_num
+    }
+
+    fn degree(&self) -> usize {
+        self.degree
+    }
+
+    fn evaluate(&self, domain: &[usize]) -> Scalar {
+        assert_eq!(domain.len(), self.var_num, "Domain is less than var_num");
+        let point = domain
+            .iter()
+            .map(|x| Scalar::from(*x as u64))
+            .collect::<Vec<_>>();
+        Self::arithmetize(&self.formula, &point)
+    }
+}
+
+pub struct CountingSat {
+    sumcheck: SumCheck<ArithmetizedFormula>,
+}
+
+impl CountingSat {
+    pub fn new(formula: Formula, var_num: usize) -> Self {
+        let g = ArithmetizedFormula::new(formula, var_num);
+        Self {
+            sumcheck: SumCheck::new(g),
+        }
+    }
+
+    pub fn from_dimacs(input: &str) -> Result<Self, String> {
+        let (formula, var_num) = Formula::from_dimacs(input)?;
+        Ok(Self::new(formula, var_num))
+    }
+
+    // Run the sumcheck on the claimed #SAT, return it once V accepts.
+    pub fn run_protocol(&mut self) -> Scalar {
+        self.sumcheck.run_protocol();
+        self.sumcheck.claim()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::oracle::DirectOracle;
+    use crate::utils::convert_to_binary;
+
+    const DIMACS_EXAMPLES: [&str; 4] = [
+        "c (x1 ∨ ¬x2) ∧ (x2 ∨ x3)
+p cnf 3 2
+1 -2 0
+2 3 0
+",
+        "c example with a clause across lines
+p cnf 4 3
+1 2 0
+-1 3 0
+-3 4
+-2 0
+",
+        "c unsatisfiable
+p cnf 2 4
+1 2 0
+-1 2 0
+1 -2 0
+-1 -2 0
+",
+        "p cnf 5 5
+1 -5 4 0
+-1 5 3 4 0
+-3 -4 0
+2 3 0
+-2 -5 1 0
+",
+    ];
+
+    fn brute_force(formula: &Formula, var_num: usize) -> u64 {
+        (0..1 << var_num)
+            .filter(|n| {
+                let assignment = convert_to_binary(&var_num, *n)
+                    .iter()
+                    .map(|b| *b == 1)
+                    .collect::<Vec<_>>();
+                formula.evaluate(&assignment)
+            })
+            .count() as u64
+    }
+
+    #[test]
+    fn test_arithmetize() {
+        let formula = Formula::parse("(x1 & !x2) | ~(x3 | x1)");
+        let g = ArithmetizedFormula::new(formula.clone(), 3);
+        assert_eq!(g.degree(), 2);
+
+        // g agrees with φ on the hypercube.
+        for n in 0..8 {
+            let domain = convert_to_binary(&3, n);
+            let assignment = domain.iter().map(|b| *b == 1).collect::<Vec<_>>();
+            let expected = Scalar::from(formula.evaluate(&assignment) as u64);
+            assert_eq!(g.evaluate(&domain), expected);
+        }
+    }
+
+    #[test]
+    fn test_counting_sat() {
+        for input in DIMACS_EXAMPLES {
+            let (formula, var_num) = Formula::from_dimacs(input).unwrap();
+            let expected = brute_force(&formula, var_num);
+
+            let mut protocol = CountingSat::from_dimacs(input).unwrap();
+            assert_eq!(protocol.run_protocol(), Scalar::from(expected));
+        }
+    }
+
+    #[test]
+    fn test_counting_sat_expression() {
+        let formula = Formula::parse("(x1 | x2 | !x3) & (x2 | x4) & !(x1 & x4)");
+        let expected = brute_force(&formula, 4);
+
+        let mut protocol = CountingSat::new(formula, 4);
+        assert_eq!(protocol.run_protocol(), Scalar::from(expected));
+    }
+
+    // P works on g + δ, which claims #SAT + δ·2^n.
+    #[derive(Clone)]
+    struct Shifted(ArithmetizedFormula);
+
+    impl MultivariatePolynomial for Shifted {
+        fn var_num(&self) -> usize {
+            self.0.var_num()
+        }
+
+        fn degree(&self) -> usize {
+            self.0.degree()
+        }
+
+        fn evaluate(&self, domain: &[usize]) -> Scalar {
+            self.0.evaluate(domain) + Scalar::one()
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Verifier rejected the proof")]
+    fn test_counting_sat_wrong_count() {
+        let (formula, var_num) = Formula::from_dimacs(DIMACS_EXAMPLES[0]).unwrap();
+        let g = ArithmetizedFormula::new(formula, var_num);
+
+        let mut sumcheck = SumCheck::with_oracle(Shifted(g.clone()), DirectOracle::new(g));
+        sumcheck.run_protocol();
+    }
+}
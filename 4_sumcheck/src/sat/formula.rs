@@ -0,0 +1,290 @@
+// Boolean formula over the variables x_1, ..., x_n.
+// NOTE: the variables are 1-indexed in the text forms (as DIMACS), and 0-indexed in `Var`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Formula {
+    Var(usize),
+    Not(Box<Formula>),
+    And(Vec<Formula>),
+    Or(Vec<Formula>),
+}
+
+impl Formula {
+    // Parse a CNF in DIMACS format, return the formula and the var_num in the header.
+    // eg:
+    //      c (x1 ∨ ¬x2) ∧ (x2 ∨ x3)
+    //      p cnf 3 2
+    //      1 -2 0
+    //      2 3 0
+    // NOTE: the SATLIB files end with a `%` line followed by a `0`, so the parsing stops at `%`.
+    pub fn from_dimacs(input: &str) -> Result<(Self, usize), String> {
+        let mut header = None;
+        let mut clauses = vec![];
+        let mut clause = vec![];
+
+        for line in input.lines().map(|l| l.trim()) {
+            if line.starts_with('%') {
+                break;
+            }
+            if line.is_empty() || line.starts_with('c') {
+                continue;
+            }
+            if line.starts_with('p') {
+                if header.is_some() {
+                    return Err(format!("Duplicated DIMACS header: {}", line));
+                }
+                let fields = line.split_whitespace().collect::<Vec<_>>();
+                if fields.len() != 4 || fields[0] != "p" || fields[1] != "cnf" {
+                    return Err(format!("Invalid DIMACS header: {}", line));
+                }
+                let var_num = fields[2]
+                    .parse::<usize>()
+                    .map_err(|_| format!("Invalid var num: {}", fields[2]))?;
+                let clause_num = fields[3]
+                    .parse::<usize>()
+                    .map_err(|_| format!("Invalid clause num: {}", fields[3]))?;
+                header = Some((var_num, clause_num));
+                continue;
+            }
+
+            // clauses: literals end with 0, a clause may span lines.
+            let (var_num, _) = header.ok_or("Clause before DIMACS header")?;
+            for literal in line.split_whitespace() {
+                let literal = literal
+                    .parse::<i64>()
+                    .map_err(|_| format!("Invalid literal: {}", literal))?;
+                if literal == 0 {
+                    clauses.push(Formula::Or(std::mem::take(&mut clause)));
+                    continue;
+                }
+
+                let index = literal.unsigned_abs() as usize;
+                if index > var_num {
+                    return Err(format!("Literal {} out of range", literal));
+                }
+                let var = Formula::Var(index - 1);
+                if literal > 0 {
+                    clause.push(var);
+                } else {
+                    clause.push(Formula::Not(Box::new(var)));
+                }
+            }
+        }
+        if !clause.is_empty() {
+            clauses.push(Formula::Or(clause));
+        }
+
+        let (var_num, clause_num) = header.ok_or("Missing DIMACS header")?;
+        if clauses.len() != clause_num {
+            return Err(format!(
+                "Clause num {} mismatch with header {}",
+                clauses.len(),
+                clause_num
+            ));
+        }
+        Ok((Formula::And(clauses), var_num))
+    }
+
+    // Parse an expression such as `(x1 & !x2) | x3`.
+    // The precedence from high to low: `!`(or `~`), `&`, `|`.
+    pub fn parse(expr: &str) -> Self {
+        let tokens = Self::tokenize(expr);
+        let mut pos = 0;
+        let formula = Self::parse_or(&tokens, &mut pos);
+        assert_eq!(pos, tokens.len(), "Unexpected token in {}", expr);
+        formula
+    }
+
+    // The var_num of the formula, aka. the max index of var + 1.
+    pub fn var_num(&self) -> usize {
+        match self {
+            Formula::Var(i) => i + 1,
+            Formula::Not(f) => f.var_num(),
+            Formula::And(fs) | Formula::Or(fs) => fs.iter().map(|f| f.var_num()).max().unwrap_or(0),
+        }
+    }
+
+    // The occurrences of each variable x_i.
+    pub fn occurrences(&self, var_num: usize) -> Vec<usize> {
+        let mut occurrences = vec![0; var_num];
+        self.count_occurrences(&mut occurrences);
+        occurrences
+    }
+
+    fn count_occurrences(&self, occurrences: &mut Vec<usize>) {
+        match self {
+            Formula::Var(i) => occurrences[*i] += 1,
+            Formula::Not(f) => f.count_occurrences(occurrences),
+            Formula::And(fs) | Formula::Or(fs) => {
+                fs.iter().for_each(|f| f.count_occurrences(occurrences))
+            }
+        }
+    }
+
+    pub fn evaluate(&self, assignment: &[bool]) -> bool {
+        match self {
+            Formula::Var(i) => assignment[*i],
+            Formula::Not(f) => !f.evaluate(assignment),
+            Formula::And(fs) => fs.iter().all(|f| f.evaluate(assignment)),
+            Formula::Or(fs) => fs.iter().any(|f| f.evaluate(assignment)),
+        }
+    }
+
+    fn tokenize(expr: &str) -> Vec<Token> {
+        let chars = expr.chars().collect::<Vec<_>>();
+        let mut tokens = vec![];
+        let mut i = 0;
+        while i < chars.len() {
+            match chars[i] {
+                c if c.is_whitespace() => {}
+                '!' | '~' => tokens.push(Token::Not),
+                '&' => tokens.push(Token::And),
+                '|' => tokens.push(Token::Or),
+                '(' => tokens.push(Token::LParen),
+                ')' => tokens.push(Token::RParen),
+                'x' => {
+                    let start = i + 1;
+                    while i + 1 < chars.len() && chars[i + 1].is_ascii_digit() {
+                        i += 1;
+                    }
+                    let index = chars[start..=i]
+                        .iter()
+                        .collect::<String>()
+                        .parse::<usize>()
+                        .expect("Invalid variable");
+                    assert!(index > 0, "Variables start with x1");
+                    tokens.push(Token::Var(index - 1));
+                }
+                c => panic!("Unexpected char {} in {}", c, expr),
+            }
+            i += 1;
+        }
+        tokens
+    }
+
+    // or := and ('|' and)*
+    fn parse_or(tokens: &[Token], pos: &mut usize) -> Self {
+        let mut terms = vec![Self::parse_and(tokens, pos)];
+        while tokens.get(*pos) == Some(&Token::Or) {
+            *pos += 1;
+            terms.push(Self::parse_and(tokens, pos));
+        }
+        if terms.len() == 1 {
+            terms.pop().unwrap()
+        } else {
+            Formula::Or(terms)
+        }
+    }
+
+    // and := unary ('&' unary)*
+    fn parse_and(tokens: &[Token], pos: &mut usize) -> Self {
+        let mut terms = vec![Self::parse_unary(tokens, pos)];
+        while tokens.get(*pos) == Some(&Token::And) {
+            *pos += 1;
+            terms.push(Self::parse_unary(tokens, pos));
+        }
+        if terms.len() == 1 {
+            terms.pop().unwrap()
+        } else {
+            Formula::And(terms)
+        }
+    }
+
+    // unary := '!' unary | '(' or ')' | var
+    fn parse_unary(tokens: &[Token], pos: &mut usize) -> Self {
+        let token = tokens.get(*pos).expect("Unexpected end of expression");
+        *pos += 1;
+        match token {
+            Token::Not => Formula::Not(Box::new(Self::parse_unary(tokens, pos))),
+            Token::LParen => {
+                let formula = Self::parse_or(tokens, pos);
+                assert_eq!(tokens.get(*pos), Some(&Token::RParen), "Missing `)`");
+                *pos += 1;
+                formula
+            }
+            Token::Var(i) => Formula::Var(*i),
+            _ => panic!("Unexpected token {:?}", token),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Token {
+    Var(usize),
+    Not,
+    And,
+    Or,
+    LParen,
+    RParen,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sat::formula::Formula::{And, Not, Or, Var};
+
+    #[test]
+    fn test_from_dimacs() {
+        let input = "c (x1 ∨ ¬x2) ∧ (x2 ∨ x3)\np cnf 3 2\n1 -2 0\n2 3 0\n";
+        let (formula, var_num) = Formula::from_dimacs(input).unwrap();
+
+        assert_eq!(var_num, 3);
+        assert_eq!(
+            formula,
+            And(vec![
+                Or(vec![Var(0), Not(Box::new(Var(1)))]),
+                Or(vec![Var(1), Var(2)])
+            ])
+        );
+    }
+
+    #[test]
+    fn test_from_dimacs_satlib() {
+        // the SATLIB benchmarks end with `%` and a lonely `0`.
+        let input = "c uf3-01.cnf\np  cnf  3  2\n 1 -2 0\n 2  3 0\n%\n0\n\n";
+        let (formula, var_num) = Formula::from_dimacs(input).unwrap();
+
+        assert_eq!(var_num, 3);
+        assert_eq!(
+            formula,
+            And(vec![
+                Or(vec![Var(0), Not(Box::new(Var(1)))]),
+                Or(vec![Var(1), Var(2)])
+            ])
+        );
+    }
+
+    #[test]
+    fn test_from_dimacs_invalid() {
+        let cases = [
+            ("p cnf 3\n1 -2 0\n", "Invalid DIMACS header: p cnf 3"),
+            ("p dnf 3 1\n1 -2 0\n", "Invalid DIMACS header: p dnf 3 1"),
+            ("p cnf x 1\n1 -2 0\n", "Invalid var num: x"),
+            ("p cnf 3 2\n1 -2 0\n", "Clause num 1 mismatch with header 2"),
+            ("p cnf 3 1\n1 -4 0\n", "Literal -4 out of range"),
+            ("p cnf 3 1\n1 a 0\n", "Invalid literal: a"),
+            ("1 -2 0\np cnf 3 1\n", "Clause before DIMACS header"),
+            ("c nothing\n", "Missing DIMACS header"),
+        ];
+        for (input, err) in cases {
+            assert_eq!(Formula::from_dimacs(input), Err(err.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let formula = Formula::parse("(x1 & !x2) | ~(x3 | x1)");
+        assert_eq!(
+            formula,
+            Or(vec![
+                And(vec![Var(0), Not(Box::new(Var(1)))]),
+                Not(Box::new(Or(vec![Var(2), Var(0)])))
+            ])
+        );
+        assert_eq!(formula.var_num(), 3);
+        assert_eq!(formula.occurrences(3), vec![2, 1, 1]);
+
+        assert!(formula.evaluate(&[true, false, false]));
+        assert!(formula.evaluate(&[false, true, false]));
+        assert!(!formula.evaluate(&[true, true, true]));
+    }
+}
@@ -1,67 +1,178 @@
+use crate::oracle::{DirectOracle, Oracle};
 use crate::poly::multivar_poly::MPolynomial;
 use crate::poly::univar_poly::Polynomial;
+use crate::poly::MultivariatePolynomial;
+use crate::sumcheck::encoding::Encoding;
 use crate::sumcheck::prover::Prover;
+use crate::sumcheck::session::{Message, SessionLog};
 use crate::sumcheck::verifier::Verifier;
+use crate::utils::boolean_domains;
 use bls12_381::Scalar;
+use rayon::ThreadPool;
 use std::env::var;
 use std::iter::Sum;
+use std::sync::Arc;
 
+pub mod encoding;
 pub mod prover;
+pub mod session;
 pub mod verifier;
 
-pub struct SumCheck {
+// A claim `g(point) = value` left over at the end of a sumcheck.
+// The caller is in charge of discharging it, eg: by evaluating g directly,
+// by a polynomial commitment opening or by another protocol (as GKR does).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct EvaluationClaim {
+    pub point: Vec<usize>,
+    pub value: Scalar,
+}
+
+pub struct SumCheck<P: MultivariatePolynomial = MPolynomial, O: Oracle = DirectOracle<P>> {
     v: usize,
-    prover: Prover,
+    prover: Prover<P>,
     verifier: Verifier,
+    oracle: O, // V's oracle access to g for the final check.
+    encoding: Encoding,
+    log: SessionLog, // the messages exchanged so far.
+}
+
+impl<P: MultivariatePolynomial + Clone> SumCheck<P> {
+    // V is assumed to know g, and evaluates it by itself in the final check.
+    pub fn new(g: P) -> Self {
+        let oracle = DirectOracle::new(g.clone());
+        Self::with_oracle(g, oracle)
+    }
 }
 
-impl SumCheck {
-    pub fn new(g: MPolynomial) -> Self {
-        let var_num = g.var_num;
+impl<P: MultivariatePolynomial, O: Oracle> SumCheck<P, O> {
+    pub fn with_oracle(g: P, oracle: O) -> Self {
+        let domains = boolean_domains(g.var_num());
+        Self::with_domains(g, oracle, domains)
+    }
+
+    // Sum over the product domain H_1 × ... × H_v instead of the hypercube {0,1}^v,
+    // where the check of round j becomes: g_j-1(r_j-1) = ∑g_j(h), h in H_j.
+    // eg: summing over {0, ..., k-1}^v takes fewer rounds than {0,1}^v, with higher degree round polys.
+    pub fn with_domains(g: P, oracle: O, domains: Vec<Vec<usize>>) -> Self {
+        let var_num = g.var_num();
 
-        let prover = Prover::new(g);
+        let prover = Prover::with_domains(g, domains.clone());
         let proof = prover.proof();
-        let verifier = Verifier::new(var_num, proof);
+        let verifier = Verifier::with_domains(domains.clone(), proof);
+        let encoding = Encoding::default();
 
         Self {
             v: var_num,
             prover,
             verifier,
+            oracle,
+            encoding,
+            log: SessionLog::new(domains, proof, encoding),
         }
     }
 
+    // V fixes the challenges r1, ..., rv up front instead of sampling them in each round.
+    pub fn with_challenges(g: P, oracle: O, challenges: Vec<usize>) -> Self {
+        let mut sumcheck = Self::with_oracle(g, oracle);
+        sumcheck.verifier = sumcheck.verifier.fix_challenges(challenges);
+        sumcheck
+    }
+
+    // The claimed sum C1.
+    pub fn claim(&self) -> Scalar {
+        self.prover.proof()
+    }
+
+    pub fn oracle(&self) -> &O {
+        &self.oracle
+    }
+
+    // How P sends the round polys, full coefficients by default.
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self.log.encoding = encoding;
+        self
+    }
+
+    // P computes the round polys in the given pool instead of the global one.
+    // The messages are the same for any number of threads.
+    // NOTE: C1 is computed on construction, so wrap the constructor in `pool.install` to compute it in the pool too.
+    pub fn with_thread_pool(mut self, pool: Arc<ThreadPool>) -> Self {
+        self.prover = self.prover.with_thread_pool(pool);
+        self
+    }
+
+    // The number of field elements in the round messages so far.
+    pub fn proof_size(&self) -> usize {
+        self.log.proof_size()
+    }
+
+    // The transcript of the session so far, which can be saved and replayed later.
+    pub fn session_log(&self) -> &SessionLog {
+        &self.log
+    }
+
+    // P encodes g_j, and V decodes it with its running claim.
+    fn send(&mut self, round: usize, g_j: Polynomial) -> Polynomial {
+        let message = self.encoding.encode(&g_j);
+        let g_j = self.verifier.receive(self.encoding, &message);
+        self.log.record(Message::RoundPoly { round, message });
+        g_j
+    }
+
+    // Record r_j once V has checked g_j.
+    fn record_challenge(&mut self, round: usize) {
+        let challenge = *self.verifier.challenges().last().unwrap();
+        self.log.record(Message::Challenge { round, challenge });
+    }
+
     pub fn run_protocol(&mut self) {
+        self.try_run_protocol()
+            .unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    // The same as `run_protocol`, but V stops at the first rejection and returns it, instead of panicking.
+    // The rejected message is the last one in the session log, so that the log can be replayed later,
+    // eg: as a regression fixture of a rejected session.
+    pub fn try_run_protocol(&mut self) -> Result<(), String> {
         // round 1
         let g1 = self.prover.round_1();
-        self.verifier.round_1(g1);
+        let g1 = self.send(1, g1);
+        self.verifier.try_round_1(g1)?;
+        self.record_challenge(1);
 
-        // round 2 - (v-1)
-        for j in 2..self.v {
+        // round 2 - v
+        for j in 2..=self.v {
             let challenges = self.verifier.challenges();
-            let g_j = self.prover.recursive_round_j(&challenges);
-            self.verifier.recursive_round_j(j, g_j);
-            drop(challenges);
+            let g_j = match j == self.v {
+                true => self.prover.round_v(&challenges),
+                false => self.prover.recursive_round_j(&challenges),
+            };
+            let g_j = self.send(j, g_j);
+            self.verifier.try_recursive_round_j(j, g_j)?;
+            self.record_challenge(j);
         }
 
-        // round v
-        let challenges = self.verifier.challenges();
-        let g_v = self.prover.round_v(&challenges);
-        self.verifier.round_v(g_v);
-        // drop(challenges);
-
-        // finally check
-        let challenges = self.verifier.challenges();
-        let target = self.prover.evaluate(&challenges);
-        self.verifier.check(target);
+        // finally check, V queries g(r1, ..., rv) from the oracle instead of asking P.
+        let value = self.oracle.query(&self.verifier.challenges());
+        self.log.record(Message::FinalEvaluation { value });
+        self.verifier.try_check_value(value)
     }
 }
 
 #[cfg(test)]
 mod test {
+    use crate::oracle::DirectOracle;
+    use crate::poly::lde::LowDegreeExtension;
     use crate::poly::multivar_poly::MPolynomial;
+    use crate::sumcheck::encoding::Encoding;
+    use crate::sumcheck::prover::Prover;
+    use crate::sumcheck::verifier::Verifier;
     use crate::sumcheck::SumCheck;
+    use crate::utils::thread_pool;
     use bls12_381::Scalar;
-    use ff::PrimeField;
+    use ff::{Field, PrimeField};
+    use rand_core::OsRng;
 
     fn gen_mpoly() -> MPolynomial {
         // let g(x1, x2, x3) = 9 + 2*x3 + 3*x2 + 2 * x1 * x2 + 4* x1 * x2 * x3
@@ -97,4 +208,118 @@ mod test {
 
         sumcheck.run_protocol();
     }
+
+    #[test]
+    fn test_sumcheck_thread_pool() {
+        let evals = (0..1 << 6)
+            .map(|_| Scalar::random(OsRng))
+            .collect::<Vec<_>>();
+        let g = MPolynomial::lagrange(6, &evals);
+
+        // the same claim and round polys with 1 and 4 threads.
+        let serial = Prover::new(g.clone()).with_thread_pool(thread_pool(1));
+        let parallel = Prover::new(g.clone()).with_thread_pool(thread_pool(4));
+        assert_eq!(serial.proof(), parallel.proof());
+        let challenges = [17, 23, 29, 31, 37, 41];
+        for j in 0..6 {
+            assert_eq!(
+                serial.recursive_round_j(&challenges[..j]),
+                parallel.recursive_round_j(&challenges[..j])
+            );
+        }
+
+        let pool = thread_pool(4);
+        let mut sumcheck = pool.install(|| SumCheck::new(g)).with_thread_pool(pool);
+        assert_eq!(sumcheck.claim(), evals.iter().sum());
+        sumcheck.run_protocol();
+    }
+
+    #[test]
+    fn test_sumcheck_compressed() {
+        // degree 1 and degree 2 in each variable.
+        let evals = (0..27).map(|_| Scalar::random(OsRng)).collect::<Vec<_>>();
+        let lde = LowDegreeExtension::new(3, 3, evals);
+
+        for encoding in [
+            Encoding::Coefficients,
+            Encoding::CompressedEvaluations,
+            Encoding::CompressedCoefficients,
+        ] {
+            let mut sumcheck = SumCheck::new(gen_mpoly()).with_encoding(encoding);
+            sumcheck.run_protocol();
+            let size = sumcheck.proof_size();
+
+            let mut sumcheck = SumCheck::new(lde.clone()).with_encoding(encoding);
+            sumcheck.run_protocol();
+            let size_lde = sumcheck.proof_size();
+
+            // one field element saved per round.
+            let (expected, expected_lde) = if encoding.is_compressed() {
+                (3, 6)
+            } else {
+                (6, 9)
+            };
+            assert_eq!((size, size_lde), (expected, expected_lde));
+            println!(
+                "{:?}: {} field elements for deg 1, {} for deg 2",
+                encoding, size, size_lde
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Verifier rejected the proof")]
+    fn test_sumcheck_compressed_wrong_claim() {
+        let g = gen_mpoly();
+        let claim = g.sum_all_evals() + Scalar::one();
+
+        // The round checks always pass with the derived g_j(1), so the lie is caught at the end.
+        let mut sumcheck = SumCheck::new(g).with_encoding(Encoding::CompressedEvaluations);
+        sumcheck.verifier = Verifier::with_challenges(3, claim, vec![17, 23, 29]);
+        sumcheck.run_protocol();
+    }
+
+    #[test]
+    fn test_sumcheck_over_grid() {
+        // f on {0,1,2}^3, and its low-degree extension with degree 2 in each variable.
+        let evals = (0..27).map(|_| Scalar::random(OsRng)).collect::<Vec<_>>();
+        let g = LowDegreeExtension::new(3, 3, evals.clone());
+        let domains = g.domains();
+
+        let mut sumcheck = SumCheck::with_domains(g.clone(), DirectOracle::new(g), domains);
+        assert_eq!(sumcheck.claim(), evals.iter().sum());
+        sumcheck.run_protocol();
+    }
+
+    #[test]
+    fn test_sumcheck_over_mixed_domains() {
+        // a multilinear g summed over {0,1,2} × {0,1} × {3,5,7,9}
+        let g = gen_mpoly();
+        let domains = vec![vec![0, 1, 2], vec![0, 1], vec![3, 5, 7, 9]];
+
+        let mut expected = Scalar::zero();
+        for x1 in &domains[0] {
+            for x2 in &domains[1] {
+                for x3 in &domains[2] {
+                    expected += g.evaluate(&vec![*x1, *x2, *x3]);
+                }
+            }
+        }
+
+        let mut sumcheck = SumCheck::with_domains(g.clone(), DirectOracle::new(g), domains);
+        assert_eq!(sumcheck.claim(), expected);
+        sumcheck.run_protocol();
+    }
+
+    #[test]
+    #[should_panic(expected = "No-equal in round_1")]
+    fn test_sumcheck_over_grid_wrong_claim() {
+        let g = gen_mpoly();
+        let domains = vec![vec![0, 1, 2]; 3];
+
+        let mut sumcheck = SumCheck::with_domains(g.clone(), DirectOracle::new(g), domains.clone());
+        // P claims the sum on the hypercube instead.
+        sumcheck.verifier = Verifier::with_domains(domains, gen_mpoly().sum_all_evals());
+        sumcheck.run_protocol();
+    }
 }
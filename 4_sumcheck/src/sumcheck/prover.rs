@@ -1,46 +1,86 @@
 use crate::poly::multivar_poly::MPolynomial;
 use crate::poly::univar_poly::Polynomial;
-use crate::utils::convert_to_binary;
+use crate::poly::MultivariatePolynomial;
+use crate::utils::{boolean_domains, install};
 use bls12_381::Scalar;
+use rayon::ThreadPool;
+use std::sync::Arc;
 
-pub struct Prover {
-    g: MPolynomial,
+pub struct Prover<P: MultivariatePolynomial = MPolynomial> {
+    g: P,
+    domains: Vec<Vec<usize>>,      // H_1, ..., H_v
+    pool: Option<Arc<ThreadPool>>, // where the sums over H_j+1 × ... × H_v run, the global rayon pool by default.
 }
 
-impl Prover {
-    pub fn new(mpoly: MPolynomial) -> Self {
-        Self { g: mpoly }
+impl<P: MultivariatePolynomial> Prover<P> {
+    pub fn new(mpoly: P) -> Self {
+        let domains = boolean_domains(mpoly.var_num());
+        Self::with_domains(mpoly, domains)
     }
 
-    // sum all the evaluations on hypercube of a mpoly
+    // Sum over H_1 × ... × H_v instead of the hypercube.
+    pub fn with_domains(mpoly: P, domains: Vec<Vec<usize>>) -> Self {
+        assert_eq!(mpoly.var_num(), domains.len(), "One domain per variable");
+        Self {
+            g: mpoly,
+            domains,
+            pool: None,
+        }
+    }
+
+    // Run the sums of the claim and the round polys in the given pool.
+    pub fn with_thread_pool(mut self, pool: Arc<ThreadPool>) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    // sum all the evaluations on H_1 × ... × H_v (hypercube by default) of a mpoly
     // obtain C1, which claimed equal H.
     pub fn proof(&self) -> Scalar {
-        let n = 1 << self.g.var_num;
-        (0..n)
-            .map(|i| {
-                let domain = convert_to_binary(&self.g.var_num, i);
-                self.g.evaluate(&domain)
-            })
-            .sum()
+        install(&self.pool, || {
+            if self.is_boolean(0) {
+                self.g.sum_all_evals()
+            } else {
+                self.g.sum_over(&self.domains)
+            }
+        })
     }
 
     // Return g1(X) = sum g(X, x_2, ..., x_v)
     pub fn round_1(&self) -> Polynomial {
-        self.g.partial_evaluate(&vec![])
+        self.round(&[])
     }
 
     // 1 < j < v, total v-2 rounds
     // Return g_j = (r1, ..., r_j-1, X, x_j+1, ..., x_v)
-    pub fn recursive_round_j(&self, challenges: &Vec<usize>) -> Polynomial {
-        self.g.partial_evaluate(challenges)
+    pub fn recursive_round_j(&self, challenges: &[usize]) -> Polynomial {
+        self.round(challenges)
     }
 
     // Return g_v = (r1, r2, ..., r_v-1, X_v)
-    pub fn round_v(&self, challenges: &Vec<usize>) -> Polynomial {
-        self.g.partial_evaluate(challenges)
+    pub fn round_v(&self, challenges: &[usize]) -> Polynomial {
+        self.round(challenges)
+    }
+
+    // Sum over (x_j+1, ..., x_v) in H_j+1 × ... × H_v, with the faster `partial_evaluate` on the hypercube.
+    fn round(&self, challenges: &[usize]) -> Polynomial {
+        let j = challenges.len();
+        install(&self.pool, || {
+            if self.is_boolean(j + 1) {
+                self.g.partial_evaluate(challenges)
+            } else {
+                self.g
+                    .partial_evaluate_over(challenges, &self.domains[j + 1..])
+            }
+        })
+    }
+
+    // whether H_from+1 × ... × H_v is the hypercube.
+    fn is_boolean(&self, from: usize) -> bool {
+        self.domains[from..].iter().all(|h| h == &[0, 1])
     }
 
-    pub fn evaluate(&self, challenges: &Vec<usize>) -> Scalar {
+    pub fn evaluate(&self, challenges: &[usize]) -> Scalar {
         self.g.evaluate(challenges)
     }
 }
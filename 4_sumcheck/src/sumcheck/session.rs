@@ -0,0 +1,335 @@
+use crate::oracle::Oracle;
+use crate::sumcheck::encoding::Encoding;
+use crate::sumcheck::verifier::Verifier;
+use bls12_381::Scalar;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// A message of an interactive sumcheck session, tagged with its round number.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Message {
+    // P -> V: g_j, encoded by the session's encoding.
+    RoundPoly {
+        round: usize,
+        #[serde(with = "scalars_hex")]
+        message: Vec<Scalar>,
+    },
+    // V -> P: r_j
+    Challenge {
+        round: usize,
+        challenge: usize,
+    },
+    // g(r1, ..., rv) answered by the oracle in the final check.
+    FinalEvaluation {
+        #[serde(with = "scalar_hex")]
+        value: Scalar,
+    },
+}
+
+// The transcript of an interactive sumcheck, as a audit trail or a regression fixture.
+//
+// It's stored as json, with the field elements in hex. Replaying it against a fresh verifier
+// with the recorded challenges reproduces the accept/reject decision of the session,
+// which ends with the rejected message if it's rejected.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SessionLog {
+    pub domains: Vec<Vec<usize>>, // H_1, ..., H_v
+    #[serde(with = "scalar_hex")]
+    pub claim: Scalar, // C1
+    pub encoding: Encoding,
+    pub messages: Vec<Message>,
+}
+
+impl SessionLog {
+    pub fn new(domains: Vec<Vec<usize>>, claim: Scalar, encoding: Encoding) -> Self {
+        Self {
+            domains,
+            claim,
+            encoding,
+            messages: vec![],
+        }
+    }
+
+    pub fn record(&mut self, message: Message) {
+        self.messages.push(message);
+    }
+
+    // The challenges r1, ..., rv in the session.
+    pub fn challenges(&self) -> Vec<usize> {
+        self.messages
+            .iter()
+            .filter_map(|m| match m {
+                Message::Challenge { challenge, .. } => Some(*challenge),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // The number of field elements sent by P in the rounds.
+    pub fn proof_size(&self) -> usize {
+        self.messages
+            .iter()
+            .map(|m| match m {
+                Message::RoundPoly { message, .. } => message.len(),
+                _ => 0,
+            })
+            .sum()
+    }
+
+    pub fn to_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json)
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    // g(r1, ..., rv) in the session, if it got to the final check.
+    pub fn final_evaluation(&self) -> Option<Scalar> {
+        self.messages.iter().find_map(|m| match m {
+            Message::FinalEvaluation { value } => Some(*value),
+            _ => None,
+        })
+    }
+
+    // Run a fresh verifier on the recorded messages, and return its decision,
+    // where a malformed or incomplete log is rejected too.
+    // NOTE: the final evaluation is taken as recorded, see `replay_with_oracle` to check it against g.
+    pub fn replay(&self) -> Result<(), String> {
+        let v = self.domains.len();
+        let challenges = self.challenges();
+        if challenges.len() > v {
+            return Err("Too many challenges".to_string());
+        }
+
+        let mut verifier =
+            Verifier::with_domains(self.domains.clone(), self.claim).fix_challenges(challenges);
+        let mut round = 0;
+        for (i, message) in self.messages.iter().enumerate() {
+            match message {
+                Message::RoundPoly { round: j, message } => {
+                    if *j != round + 1 || *j > v {
+                        return Err(format!("Unexpected round poly in round_{}", j));
+                    }
+                    round = *j;
+                    if message.is_empty() {
+                        return Err(format!("Empty round poly in round_{}", j));
+                    }
+                    if self.encoding.is_compressed() && self.domains[j - 1] != [0, 1] {
+                        return Err("Compressed round polys are only for the hypercube".to_string());
+                    }
+
+                    let g_j = verifier.receive(self.encoding, message);
+                    match j {
+                        1 => verifier.try_round_1(g_j)?,
+                        _ => verifier.try_recursive_round_j(*j, g_j)?,
+                    }
+                }
+                Message::Challenge { round: j, .. } => {
+                    if *j != round {
+                        return Err(format!("Unexpected challenge in round_{}", j));
+                    }
+                }
+                Message::FinalEvaluation { value } => {
+                    if round != v || i != self.messages.len() - 1 {
+                        return Err("Unexpected final evaluation".to_string());
+                    }
+                    return verifier.try_check_value(*value);
+                }
+            }
+        }
+        Err("Incomplete session".to_string())
+    }
+
+    // The same as `replay`, and check the recorded final evaluation against V's oracle access to g.
+    pub fn replay_with_oracle(&self, oracle: &mut impl Oracle) -> Result<(), String> {
+        self.replay()?;
+        let value = self.final_evaluation().expect("A complete session");
+        match oracle.query(&self.challenges()) == value {
+            true => Ok(()),
+            false => Err("The final evaluation doesn't match g".to_string()),
+        }
+    }
+}
+
+// Scalar <-> little-endian hex string
+mod scalar_hex {
+    use bls12_381::Scalar;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn to_hex(scalar: &Scalar) -> String {
+        scalar
+            .to_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    pub fn from_hex(hex: &str) -> Option<Scalar> {
+        if hex.len() != 64 || !hex.is_ascii() {
+            return None;
+        }
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok()?;
+        }
+        Option::from(Scalar::from_bytes(&bytes))
+    }
+
+    pub fn serialize<S: Serializer>(scalar: &Scalar, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&to_hex(scalar))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Scalar, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        from_hex(&hex).ok_or_else(|| D::Error::custom(format!("Invalid scalar: {}", hex)))
+    }
+}
+
+mod scalars_hex {
+    use super::scalar_hex::{from_hex, to_hex};
+    use bls12_381::Scalar;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(scalars: &[Scalar], serializer: S) -> Result<S::Ok, S::Error> {
+        scalars
+            .iter()
+            .map(to_hex)
+            .collect::<Vec<_>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Scalar>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|hex| {
+                from_hex(hex).ok_or_else(|| D::Error::custom(format!("Invalid scalar: {}", hex)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::oracle::DirectOracle;
+    use crate::poly::multivar_poly::MPolynomial;
+    use crate::sumcheck::SumCheck;
+    use ff::Field;
+    use rand_core::OsRng;
+
+    fn run_session(encoding: Encoding) -> SessionLog {
+        let evals = (0..16).map(|_| Scalar::random(OsRng)).collect::<Vec<_>>();
+        let g = MPolynomial::lagrange(4, &evals);
+
+        let mut sumcheck = SumCheck::new(g).with_encoding(encoding);
+        sumcheck.run_protocol();
+        sumcheck.session_log().clone()
+    }
+
+    #[test]
+    fn test_replay() {
+        for encoding in [Encoding::Coefficients, Encoding::CompressedEvaluations] {
+            let log = run_session(encoding);
+            assert_eq!(log.challenges().len(), 4);
+            assert_eq!(log.messages.len(), 4 * 2 + 1);
+            assert_eq!(log.replay(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_replay_over_domains() {
+        let g = MPolynomial::lagrange(
+            2,
+            &vec![
+                Scalar::from(3),
+                Scalar::from(5),
+                Scalar::from(7),
+                Scalar::from(11),
+            ],
+        );
+        let domains = vec![vec![0, 1, 2], vec![3, 5]];
+
+        let mut sumcheck = SumCheck::with_domains(g.clone(), DirectOracle::new(g), domains);
+        sumcheck.run_protocol();
+        assert_eq!(sumcheck.session_log().replay(), Ok(()));
+    }
+
+    #[test]
+    fn test_file_roundtrip() {
+        let log = run_session(Encoding::CompressedCoefficients);
+        let path =
+            std::env::temp_dir().join(format!("sumcheck_session_{}.json", std::process::id()));
+
+        log.to_file(&path).unwrap();
+        let loaded = SessionLog::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, log);
+        assert_eq!(loaded.replay(), Ok(()));
+    }
+
+    #[test]
+    fn test_replay_tampered_final_evaluation() {
+        let mut log = run_session(Encoding::Coefficients);
+        if let Some(Message::FinalEvaluation { value }) = log.messages.last_mut() {
+            *value += Scalar::one();
+        }
+        assert_eq!(log.replay(), Err("Verifier rejected the proof".to_string()));
+    }
+
+    #[test]
+    fn test_replay_tampered_round_poly() {
+        let mut log = run_session(Encoding::Coefficients);
+        // the constant term of g_2
+        if let Message::RoundPoly { message, .. } = &mut log.messages[2] {
+            message[0] += Scalar::one();
+        }
+        assert_eq!(log.replay(), Err("Not-equal in round_2".to_string()));
+
+        // truncated
+        let mut log = run_session(Encoding::Coefficients);
+        log.messages.truncate(5);
+        assert_eq!(log.replay(), Err("Incomplete session".to_string()));
+    }
+
+    #[test]
+    fn test_replay_rejected_session() {
+        let evals = (0..16).map(|_| Scalar::random(OsRng)).collect::<Vec<_>>();
+        let g = MPolynomial::lagrange(4, &evals);
+        let mut other = evals.clone();
+        other[3] += Scalar::one();
+        let h = MPolynomial::lagrange(4, &other);
+
+        // V's oracle is to h, while P works on g, so that V rejects in the final check.
+        let mut sumcheck = SumCheck::with_oracle(g.clone(), DirectOracle::new(h.clone()));
+        let err = Err("Verifier rejected the proof".to_string());
+        assert_eq!(sumcheck.try_run_protocol(), err);
+
+        // the log ends with the rejected message, and the replay rejects it again.
+        let log = sumcheck.session_log().clone();
+        assert!(matches!(
+            log.messages.last(),
+            Some(Message::FinalEvaluation { .. })
+        ));
+        assert_eq!(log.replay(), err);
+
+        // the final evaluation of an accepted session is checked against the oracle.
+        let mut sumcheck = SumCheck::new(g.clone());
+        sumcheck.run_protocol();
+        let log = sumcheck.session_log();
+        assert_eq!(log.replay_with_oracle(&mut DirectOracle::new(g)), Ok(()));
+        assert_eq!(
+            log.replay_with_oracle(&mut DirectOracle::new(h)),
+            Err("The final evaluation doesn't match g".to_string())
+        );
+    }
+}
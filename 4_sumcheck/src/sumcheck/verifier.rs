@@ -1,4 +1,7 @@
+use crate::oracle::Oracle;
 use crate::poly::univar_poly::Polynomial;
+use crate::sumcheck::encoding::Encoding;
+use crate::utils::boolean_domains;
 use bls12_381::Scalar;
 use ff::{Field, PrimeField};
 use rand_core::{OsRng, RngCore};
@@ -7,20 +10,44 @@ pub struct Verifier {
     proof: Scalar,
     // C1
     v: usize,
+    domains: Vec<Vec<usize>>, // H_1, ..., H_v
     cached_g_j: Vec<Polynomial>,
     challenges: Vec<usize>, // challenges: r1, r2, ..., rv. (In implement, r1 is a random usize, which is easy to construct a Field)
+    preset_challenges: Vec<usize>, // fixed by V before the protocol, empty if sampled on the fly.
 }
 
 impl Verifier {
     pub fn new(v: usize, proof: Scalar) -> Self {
+        Self::with_domains(boolean_domains(v), proof)
+    }
+
+    // The claim C1 is the sum over H_1 × ... × H_v instead of the hypercube.
+    pub fn with_domains(domains: Vec<Vec<usize>>, proof: Scalar) -> Self {
         Self {
             proof,
-            v,
+            v: domains.len(),
+            domains,
             cached_g_j: vec![],
             challenges: vec![],
+            preset_challenges: vec![],
         }
     }
 
+    // V chooses r1, ..., rv before the protocol starts, eg: a streaming verifier which
+    // needs r up front to evaluate g(r) while reading the input. Then r_j is revealed to P in round j.
+    // It's as sound as the fresh ones, as long as P doesn't learn r_j before sending g_j.
+    pub fn with_challenges(v: usize, proof: Scalar, challenges: Vec<usize>) -> Self {
+        Self::new(v, proof).fix_challenges(challenges)
+    }
+
+    // Use the given r1, ..., rv instead of sampling them, eg: to replay a recorded session.
+    // The challenges may stop early, eg: the session was rejected in round j, then the rest are sampled.
+    pub fn fix_challenges(mut self, challenges: Vec<usize>) -> Self {
+        assert!(challenges.len() <= self.v, "One challenge per round");
+        self.preset_challenges = challenges;
+        self
+    }
+
     pub fn challenges(&self) -> Vec<usize> {
         self.challenges.clone()
     }
@@ -34,38 +61,88 @@ impl Verifier {
         assert_eq!(self.challenges.len(), self.cached_g_j.len());
 
         self.cached_g_j.push(g_i);
-        self.challenges.push(Verifier::gen_challenge());
+        let r = match self.preset_challenges.get(self.challenges.len()) {
+            Some(r) => *r,
+            None => Verifier::gen_challenge(),
+        };
+        self.challenges.push(r);
     }
 
-    // Check: C1 = g_1(0) + g_1(1)
-    pub fn round_1(&mut self, g1: Polynomial) {
-        let actual = g1.evaluate(Scalar::one()) + g1.evaluate(Scalar::zero());
+    // Recover g_j from P's message in round j, with the running claim C_j.
+    pub fn receive(&self, encoding: Encoding, message: &[Scalar]) -> Polynomial {
+        let j = self.cached_g_j.len() + 1;
+        if encoding.is_compressed() {
+            assert_eq!(
+                self.domains[j - 1],
+                [0, 1],
+                "Compressed round polys are only for the hypercube"
+            );
+        }
 
-        assert_eq!(actual, self.proof, "No-equal in round_1");
+        // C_1, or g_j-1(r_j-1)
+        let claim = match self.cached_g_j.last() {
+            None => self.proof,
+            Some(g_j_minus_1) => {
+                let r = *self.challenges.last().unwrap() as u64;
+                g_j_minus_1.evaluate(Scalar::from(r))
+            }
+        };
+        encoding.decode(message, claim)
+    }
 
-        // prepare for next round
-        self.prepare_next_round(g1);
+    // ∑g_j(h), h in H_j. It's g_j(0) + g_j(1) on the hypercube.
+    fn sum_over_domain(&self, j: usize, g_j: &Polynomial) -> Scalar {
+        self.domains[j - 1]
+            .iter()
+            .map(|h| g_j.evaluate(Scalar::from(*h as u64)))
+            .sum()
+    }
+
+    // Check: C1 = ∑g_1(h), h in H_1
+    pub fn round_1(&mut self, g1: Polynomial) {
+        self.try_round_1(g1).unwrap_or_else(|err| panic!("{}", err));
     }
 
     // 1 < j < v, total v-2 rounds
-    // check: g_j-1(r_j-1) = g_j(0) + g_j(1)
+    // check: g_j-1(r_j-1) = ∑g_j(h), h in H_j
     pub fn recursive_round_j(&mut self, round_num: usize, g_j: Polynomial) {
-        // check
-        self.check_round_j(round_num, &g_j);
-        // prepare for next round
-        self.prepare_next_round(g_j);
+        self.try_recursive_round_j(round_num, g_j)
+            .unwrap_or_else(|err| panic!("{}", err));
     }
 
-    // Check: gv−1 (rv−1 ) = gv (0) + gv (1).
+    // Check: gv−1 (rv−1 ) = ∑gv(h), h in H_v.
     pub fn round_v(&mut self, g_v: Polynomial) {
-        self.check_round_j(self.v, &g_v);
+        self.try_recursive_round_j(self.v, g_v)
+            .unwrap_or_else(|err| panic!("{}", err));
+    }
+
+    // The same as `round_1`, but return the rejection instead of panicking, eg: to log a rejected session.
+    pub fn try_round_1(&mut self, g1: Polynomial) -> Result<(), String> {
+        let actual = self.sum_over_domain(1, &g1);
+        if actual != self.proof {
+            return Err("No-equal in round_1".to_string());
+        }
+
+        // prepare for next round
+        self.prepare_next_round(g1);
+        Ok(())
+    }
 
-        // prepare r_v and store g_v.
-        self.prepare_next_round(g_v);
+    // The same as `recursive_round_j` and `round_v`, but return the rejection instead of panicking.
+    pub fn try_recursive_round_j(
+        &mut self,
+        round_num: usize,
+        g_j: Polynomial,
+    ) -> Result<(), String> {
+        // check
+        self.check_round_j(round_num, &g_j)?;
+        // prepare for next round
+        self.prepare_next_round(g_j);
+        Ok(())
     }
 
-    // check: g_j-1(r_j-1) = g_j(0) + g_j(1),  1<j<=v
-    fn check_round_j(&mut self, j: usize, g_j: &Polynomial) {
+    // check: g_j-1(r_j-1) = ∑g_j(h), h in H_j,  1<j<=v
+    fn check_round_j(&mut self, j: usize, g_j: &Polynomial) -> Result<(), String> {
         assert_eq!(
             j - 1,
             self.cached_g_j.len(),
@@ -78,18 +155,33 @@ impl Verifier {
         );
 
         let r = self.challenges.last().unwrap().clone() as u128;
-        let actual = g_j.evaluate(Scalar::zero()) + g_j.evaluate(Scalar::one());
+        let actual = self.sum_over_domain(j, g_j);
         let target = self
             .cached_g_j
             .last()
             .unwrap()
             .evaluate(Scalar::from_u128(r));
 
-        assert_eq!(actual, target, "Not-equal in round_{}", j);
+        match actual == target {
+            true => Ok(()),
+            false => Err(format!("Not-equal in round_{}", j)),
+        }
+    }
+
+    // 2. gv (rv ) = g(r1 , . . . , rv ), where g(r1 , . . . , rv ) is queried from the oracle.
+    pub fn check(&self, oracle: &mut impl Oracle) {
+        let target = oracle.query(&self.challenges);
+        self.check_value(target);
+    }
+
+    // gv (rv ) = target, where target is claimed to be g(r1 , . . . , rv ).
+    pub fn check_value(&self, target: Scalar) {
+        self.try_check_value(target)
+            .unwrap_or_else(|err| panic!("{}", err));
     }
 
-    // 2. gv (rv ) = g(r1 , . . . , rv )
-    pub fn check(&self, target: Scalar) {
+    // The same as `check_value`, but return the rejection instead of panicking.
+    pub fn try_check_value(&self, target: Scalar) -> Result<(), String> {
         assert_eq!(
             self.v,
             self.cached_g_j.len(),
@@ -104,7 +196,10 @@ impl Verifier {
         let g_v = self.cached_g_j.last().unwrap().clone();
         let actual = g_v.evaluate(Scalar::from_u128(r_v));
 
-        assert_eq!(actual, target, "Verifier rejected the proof");
+        if actual != target {
+            return Err("Verifier rejected the proof".to_string());
+        }
         println!("Verifier accepted the proof");
+        Ok(())
     }
 }
@@ -0,0 +1,289 @@
+use crate::oracle::{DirectOracle, Oracle};
+use crate::poly::multivar_poly::MPolynomial;
+use crate::poly::MultivariatePolynomial;
+use crate::sumcheck::SumCheck;
+use bls12_381::Scalar;
+use rand_core::{OsRng, RngCore};
+
+// Counting triangles with sumcheck(Section 4.3 of the book).
+//
+// For a graph with adjacency matrix A (n×n, n = 2^m), let Ã be the MLE of A over {0,1}^m × {0,1}^m.
+//      ∑_{i,j,k ∈ {0,1}^m} Ã(i,j)·Ã(j,k)·Ã(i,k) = 6·#triangles
+// as each triangle is counted once per ordered triple.
+//
+// The summed poly g(i,j,k) = Ã(i,j)·Ã(j,k)·Ã(i,k) is a product of three MLEs over 3m variables.
+// At the end of the sumcheck, V only needs Ã(r_i,r_j), Ã(r_j,r_k) and Ã(r_i,r_k).
+#[derive(Debug, Clone)]
+pub struct Graph {
+    log_n: usize,
+    edges: Vec<(usize, usize)>,
+}
+
+impl Graph {
+    // Load an undirected graph from an edge list, one `u v` per line, lines start with `#` are comments.
+    // The vertex num is padded to a power of 2.
+    pub fn from_edge_list(input: &str) -> Self {
+        let edges = input
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(|l| {
+                let vertices = l
+                    .split_whitespace()
+                    .map(|v| v.parse::<usize>().expect("Invalid vertex"))
+                    .collect::<Vec<_>>();
+                assert_eq!(vertices.len(), 2, "Invalid edge: {}", l);
+                (vertices[0], vertices[1])
+            })
+            .collect::<Vec<_>>();
+
+        let vertex_num = edges.iter().map(|(u, v)| u.max(v) + 1).max().unwrap_or(1);
+        let log_n = vertex_num.next_power_of_two().trailing_zeros() as usize;
+        Self::new(log_n, edges)
+    }
+
+    pub fn new(log_n: usize, edges: Vec<(usize, usize)>) -> Self {
+        let n = 1 << log_n;
+        for (u, v) in edges.iter() {
+            assert!(*u < n && *v < n, "Vertex out of range");
+            assert_ne!(u, v, "Self loop is not supported");
+        }
+        Self { log_n, edges }
+    }
+
+    // G(n, 1/2) with n = 2^log_n.
+    pub fn random(log_n: usize) -> Self {
+        let n = 1 << log_n;
+        let edges = (0..n)
+            .flat_map(|u| (u + 1..n).map(move |v| (u, v)))
+            .filter(|_| OsRng.next_u32() % 2 == 1)
+            .collect();
+        Self::new(log_n, edges)
+    }
+
+    pub fn adjacency_matrix(&self) -> Vec<Vec<bool>> {
+        let n = 1 << self.log_n;
+        let mut matrix = vec![vec![false; n]; n];
+        for (u, v) in self.edges.iter() {
+            matrix[*u][*v] = true;
+            matrix[*v][*u] = true;
+        }
+        matrix
+    }
+
+    // Ã(i, j), where the row index i is the first m variables.
+    pub fn adjacency_mle(&self) -> MPolynomial {
+        let evals = self
+            .adjacency_matrix()
+            .into_iter()
+            .flatten()
+            .map(|a| Scalar::from(a as u64))
+            .collect::<Vec<_>>();
+        MPolynomial::from_evals(2 * self.log_n, &evals)
+    }
+
+    pub fn count_triangles(&self) -> u64 {
+        let n = 1 << self.log_n;
+        let a = self.adjacency_matrix();
+        let mut count = 0;
+        for i in 0..n {
+            for j in i + 1..n {
+                for k in j + 1..n {
+                    if a[i][j] && a[j][k] && a[i][k] {
+                        count += 1;
+                    }
+                }
+            }
+        }
+        count
+    }
+}
+
+// (i, j, k) -> ((i, j), (j, k), (i, k))
+fn split_point<T: Clone>(point: &[T], log_n: usize) -> (Vec<T>, Vec<T>, Vec<T>) {
+    assert_eq!(point.len(), 3 * log_n);
+    let (i, rest) = point.split_at(log_n);
+    let (j, k) = rest.split_at(log_n);
+    let concat = |a: &[T], b: &[T]| a.iter().chain(b).cloned().collect::<Vec<_>>();
+    (concat(i, j), concat(j, k), concat(i, k))
+}
+
+// g(i,j,k) = Ã(i,j)·Ã(j,k)·Ã(i,k)
+#[derive(Debug, Clone)]
+pub struct TrianglePoly {
+    log_n: usize,
+    a: Vec<Scalar>, // the evaluations of Ã on the hypercube
+}
+
+impl TrianglePoly {
+    pub fn new(graph: &Graph) -> Self {
+        Self {
+            log_n: graph.log_n,
+            a: graph.adjacency_mle().evals(),
+        }
+    }
+
+    // Ã(point), by binding the variables one at a time from x_1, which is in F, as the challenges
+    // multiply out of usize. A boolean variable only selects a half, so that it's cheap on the hypercube.
+    fn a_at(&self, point: &[usize]) -> Scalar {
+        let mut table = self.a.clone();
+        for x in point {
+            let (lo, hi) = table.split_at(table.len() / 2);
+            table = match x {
+                0 => lo.to_vec(),
+                1 => hi.to_vec(),
+                _ => {
+                    let r = Scalar::from(*x as u64);
+                    lo.iter().zip(hi).map(|(l, h)| l + r * (h - l)).collect()
+                }
+            };
+        }
+        table[0]
+    }
+}
+
+impl MultivariatePolynomial for TrianglePoly {
+    fn var_num(&self) -> usize {
+        3 * self.log_n
+    }
+
+    // The product of three MLEs, but each variable only appears in two of them.
+    fn degree(&self) -> usize {
+        2
+    }
+
+    fn evaluate(&self, domain: &[usize]) -> Scalar {
+        let (ij, jk, ik) = split_point(domain, self.log_n);
+        self.a_at(&ij) * self.a_at(&jk) * self.a_at(&ik)
+    }
+}
+
+// V's oracle for g(r_i, r_j, r_k), which is answered with three queries to the oracle of Ã.
+pub struct TriangleOracle<O: Oracle = DirectOracle> {
+    log_n: usize,
+    a: O,
+}
+
+impl<O: Oracle> TriangleOracle<O> {
+    pub fn new(log_n: usize, a: O) -> Self {
+        Self { log_n, a }
+    }
+}
+
+impl<O: Oracle> Oracle for TriangleOracle<O> {
+    fn query(&mut self, point: &[usize]) -> Scalar {
+        let (ij, jk, ik) = split_point(point, self.log_n);
+        self.a.query(&ij) * self.a.query(&jk) * self.a.query(&ik)
+    }
+}
+
+pub struct TriangleCounting<O: Oracle = DirectOracle> {
+    sumcheck: SumCheck<TrianglePoly, TriangleOracle<O>>,
+}
+
+impl TriangleCounting {
+    // V knows the graph, and evaluates Ã by itself.
+    pub fn new(graph: &Graph) -> Self {
+        let oracle = DirectOracle::new(graph.adjacency_mle());
+        Self::with_oracle(graph, oracle)
+    }
+}
+
+impl<O: Oracle> TriangleCounting<O> {
+    // `a` is V's oracle access to Ã.
+    pub fn with_oracle(graph: &Graph, a: O) -> Self {
+        let g = TrianglePoly::new(graph);
+        let oracle = TriangleOracle::new(graph.log_n, a);
+        Self {
+            sumcheck: SumCheck::with_oracle(g, oracle),
+        }
+    }
+
+    // Run the sumcheck on 6·#triangles, return #triangles once V accepts.
+    pub fn run_protocol(&mut self) -> Scalar {
+        self.sumcheck.run_protocol();
+        self.sumcheck.claim() * Scalar::from(6).invert().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::commitment::hyrax::Hyrax;
+    use crate::oracle::CommittedOracle;
+
+    #[test]
+    fn test_from_edge_list() {
+        // two triangles share the edge (1, 2).
+        let input = "# u v\n0 1\n1 2\n2 0\n1 3\n3 2\n4 0\n";
+        let graph = Graph::from_edge_list(input);
+
+        assert_eq!(graph.log_n, 3);
+        assert_eq!(graph.count_triangles(), 2);
+
+        let mut protocol = TriangleCounting::new(&graph);
+        assert_eq!(protocol.run_protocol(), Scalar::from(2));
+    }
+
+    #[test]
+    fn test_triangle_counting_random_graph() {
+        for log_n in 1..=3 {
+            let graph = Graph::random(log_n);
+            let expected = graph.count_triangles();
+
+            let mut protocol = TriangleCounting::new(&graph);
+            assert_eq!(protocol.run_protocol(), Scalar::from(expected));
+        }
+    }
+
+    #[test]
+    fn test_triangle_counting_large_graph() {
+        // 16 vertices, where Ã has 8 variables.
+        let graph = Graph::random(4);
+        let expected = graph.count_triangles();
+
+        let mut protocol = TriangleCounting::new(&graph);
+        assert_eq!(protocol.run_protocol(), Scalar::from(expected));
+    }
+
+    #[test]
+    fn test_triangle_counting_committed_adjacency() {
+        let graph = Graph::random(2);
+        let a = graph.adjacency_mle();
+        let oracle = CommittedOracle::new(Hyrax::setup(a.var_num), a);
+
+        let mut protocol = TriangleCounting::with_oracle(&graph, oracle);
+        assert_eq!(
+            protocol.run_protocol(),
+            Scalar::from(graph.count_triangles())
+        );
+    }
+
+    // P works on g + 1, which claims 6·#triangles + 2^{3m}.
+    #[derive(Clone)]
+    struct Shifted(TrianglePoly);
+
+    impl MultivariatePolynomial for Shifted {
+        fn var_num(&self) -> usize {
+            self.0.var_num()
+        }
+
+        fn degree(&self) -> usize {
+            self.0.degree()
+        }
+
+        fn evaluate(&self, domain: &[usize]) -> Scalar {
+            self.0.evaluate(domain) + Scalar::one()
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Verifier rejected the proof")]
+    fn test_triangle_counting_wrong_count() {
+        let graph = Graph::random(2);
+
+        let oracle = TriangleOracle::new(2, DirectOracle::new(graph.adjacency_mle()));
+        let mut sumcheck = SumCheck::with_oracle(Shifted(TrianglePoly::new(&graph)), oracle);
+        sumcheck.run_protocol();
+    }
+}
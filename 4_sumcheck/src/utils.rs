@@ -1,4 +1,6 @@
 use bls12_381::Scalar;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::sync::Arc;
 
 // convert a num into its binary form
 // eg: 8 -> 1000, will output [1, 0, 0, 0]
@@ -17,6 +19,69 @@ pub fn convert_from_binary(num: &Vec<usize>) -> usize {
     num.iter().rev().enumerate().map(|(i, n)| n << i).sum()
 }
 
+// The hypercube {0,1}^v, as the product domain H_1 × ... × H_v with H_i = {0,1}.
+pub fn boolean_domains(var_num: usize) -> Vec<Vec<usize>> {
+    vec![vec![0, 1]; var_num]
+}
+
+// |H_1 × ... × H_v|
+pub fn domain_size(domains: &[Vec<usize>]) -> usize {
+    domains.iter().map(|h| h.len()).product()
+}
+
+// convert a num into a point of H_1 × ... × H_v, by the mixed radix (|H_1|, ..., |H_v|) with x_v as the lowest digit.
+// eg: H = {0,1,2} × {0,1}, 3 -> [1, 1]
+// It's the same as `convert_to_binary` on the hypercube.
+pub fn convert_to_domain(domains: &[Vec<usize>], num: usize) -> Vec<usize> {
+    assert!(num < domain_size(domains));
+    let mut num = num;
+    let mut point = domains
+        .iter()
+        .rev()
+        .map(|h| {
+            let x = h[num % h.len()];
+            num /= h.len();
+            x
+        })
+        .collect::<Vec<_>>();
+    point.reverse();
+    point
+}
+
+// The evaluations of the multilinear lagrange basis at point r:
+//      χ_w(r) = ∏(r_i·w_i + (1−r_i)(1−w_i)),  w in {0,1}^v
+// The result is indexed by w (with x_1 as the highest bit, the same as `convert_to_binary`),
+// so that the MLE of f at r is ∑f(w)·χ_w(r). It takes O(2^v) field operations.
+pub fn eq_table(point: &[Scalar]) -> Vec<Scalar> {
+    let mut table = vec![Scalar::one()];
+    for r in point {
+        table = table
+            .iter()
+            .flat_map(|t| [t * (Scalar::one() - r), t * r])
+            .collect();
+    }
+    table
+}
+
+// A rayon pool with `num_threads` workers, to be shared by the provers.
+pub fn thread_pool(num_threads: usize) -> Arc<ThreadPool> {
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .expect("Failed to build the thread pool");
+    Arc::new(pool)
+}
+
+// Run op in the given pool, or in the current rayon pool (the global one by default) if none.
+// The parallel code only splits the sums over the hypercube, and the field addition is exact,
+// so the results are the same for any number of threads.
+pub fn install<R: Send>(pool: &Option<Arc<ThreadPool>>, op: impl FnOnce() -> R + Send) -> R {
+    match pool {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }
+}
+
 // try to expand factorization form to coeffs form for `uni-variable poly`
 // For now, we'll only support two factorizations to a coeffs.
 // eg: (4x^2 + 1)(x + 4) = 4x^3 + 4x^2 + x + 4
@@ -55,8 +120,10 @@ pub fn expand_factor_for_mpoly(var_num: usize, lhs: Vec<Scalar>, rhs: Vec<Scalar
 
 #[cfg(test)]
 mod test {
+    use crate::poly::multivar_poly::MPolynomial;
     use crate::utils::{
-        convert_from_binary, convert_to_binary, expand_factor_for_mpoly, expand_factor_for_upoly,
+        boolean_domains, convert_from_binary, convert_to_binary, convert_to_domain, domain_size,
+        eq_table, expand_factor_for_mpoly, expand_factor_for_upoly,
     };
     use bls12_381::Scalar;
     use ff::PrimeField;
@@ -106,6 +173,46 @@ mod test {
         println!("{:?}", actual);
     }
 
+    #[test]
+    fn test_eq_table() {
+        let var_num = 3;
+        let evals = (0..1 << var_num)
+            .map(|i| Scalar::from_u128(i * i + 1))
+            .collect::<Vec<_>>();
+        let poly = MPolynomial::lagrange(var_num, &evals);
+
+        let point = vec![7, 11, 13];
+        let table = eq_table(
+            &point
+                .iter()
+                .map(|r| Scalar::from(*r as u64))
+                .collect::<Vec<_>>(),
+        );
+
+        // on the hypercube, χ_w(w') = 1 iff w = w'
+        assert_eq!(eq_table(&[Scalar::one(), Scalar::zero()])[2], Scalar::one());
+        // MLE: f(r) = ∑f(w)·χ_w(r)
+        let actual: Scalar = evals.iter().zip(table).map(|(f, chi)| f * chi).sum();
+        assert_eq!(actual, poly.evaluate(&point));
+    }
+
+    #[test]
+    fn test_convert_to_domain() {
+        let domains = vec![vec![0, 1, 2], vec![0, 1], vec![5, 7]];
+        assert_eq!(domain_size(&domains), 12);
+        assert_eq!(convert_to_domain(&domains, 0), vec![0, 0, 5]);
+        assert_eq!(convert_to_domain(&domains, 7), vec![1, 1, 7]);
+        assert_eq!(convert_to_domain(&domains, 11), vec![2, 1, 7]);
+
+        // the same as binary on hypercube
+        for n in 0..8 {
+            assert_eq!(
+                convert_to_domain(&boolean_domains(3), n),
+                convert_to_binary(&3, n)
+            );
+        }
+    }
+
     #[test]
     fn test_convert() {
         let raw: Vec<usize> = vec![1, 2, 3, 4, 8, 17];
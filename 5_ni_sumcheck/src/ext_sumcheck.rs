@@ -0,0 +1,85 @@
+use crate::ext_sumcheck::prover::Prover;
+use crate::ext_sumcheck::verifier::Verifier;
+use sumcheck::field::{ExtensionField, Field};
+use sumcheck::poly::ext_poly::{MultilinearPoly, RoundPoly};
+
+mod prover;
+mod verifier;
+
+// The non-interactive sumcheck over a small base field F, with the challenges and round polys in K ⊇ F.
+// g = f_1·f_2·...·f_d is a product of MLEs over F, and the challenges in K come from the transcript,
+// with one 64-bit hash per coordinate.
+pub struct ExtProofs<F: Field, K: ExtensionField<F>> {
+    statement: F,               // C1
+    target: K,                  // g(r1, ..., rv)
+    g_i_vec: Vec<RoundPoly<K>>, // g_1, ..., g_v
+}
+
+pub struct ExtSumCheck<F: Field, K: ExtensionField<F>> {
+    prover: Prover<F, K>,
+    verifier: Verifier<F, K>,
+}
+
+impl<F: Field, K: ExtensionField<F>> ExtSumCheck<F, K> {
+    pub fn new(mles: Vec<MultilinearPoly<F>>) -> Self {
+        let v = mles[0].var_num;
+        let degree = mles.len();
+        let prover = Prover::new(mles);
+        let statement = prover.statement();
+
+        Self {
+            prover,
+            verifier: Verifier::new(v, degree, statement),
+        }
+    }
+
+    pub fn run_protocol(&mut self) {
+        let proofs = self.prover.prove();
+        self.verifier.verify(proofs);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand_core::OsRng;
+    use sumcheck::field::extension::{GoldilocksExt2, GoldilocksExt4};
+    use sumcheck::field::goldilocks::Goldilocks;
+
+    fn gen_mles(var_num: usize, d: usize) -> Vec<MultilinearPoly<Goldilocks>> {
+        (0..d)
+            .map(|_| {
+                let evals = (0..1 << var_num)
+                    .map(|_| Goldilocks::random(OsRng))
+                    .collect();
+                MultilinearPoly::new(var_num, evals)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ext_sumcheck() {
+        let mles = gen_mles(5, 3);
+        ExtSumCheck::<Goldilocks, Goldilocks>::new(mles.clone()).run_protocol();
+        ExtSumCheck::<Goldilocks, GoldilocksExt2>::new(mles.clone()).run_protocol();
+        ExtSumCheck::<Goldilocks, GoldilocksExt4>::new(mles).run_protocol();
+    }
+
+    #[test]
+    #[should_panic(expected = "Verifier rejected the proof")]
+    fn test_ext_sumcheck_wrong_target() {
+        let mut sumcheck = ExtSumCheck::<Goldilocks, GoldilocksExt4>::new(gen_mles(4, 2));
+        let mut proofs = sumcheck.prover.prove();
+        proofs.target = proofs.target + GoldilocksExt4::one();
+        sumcheck.verifier.verify(proofs);
+    }
+
+    #[test]
+    #[should_panic(expected = "No-equal in round_1")]
+    fn test_ext_sumcheck_wrong_statement() {
+        let mut sumcheck = ExtSumCheck::<Goldilocks, GoldilocksExt2>::new(gen_mles(4, 2));
+        let mut proofs = sumcheck.prover.prove();
+        proofs.statement = proofs.statement + Goldilocks::one();
+        sumcheck.verifier.verify(proofs);
+    }
+}
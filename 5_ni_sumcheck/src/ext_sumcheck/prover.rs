@@ -0,0 +1,87 @@
+use crate::ext_sumcheck::ExtProofs;
+use crate::transcript::default::Keccak256Transcript;
+use crate::transcript::{round_poly_to_bytes, Transcript};
+use sumcheck::field::{ExtensionField, Field};
+use sumcheck::poly::ext_poly::{MultilinearPoly, RoundPoly};
+
+pub struct Prover<F: Field, K: ExtensionField<F>> {
+    mles: Vec<MultilinearPoly<F>>, // f_1, ..., f_d over F
+    challenges: Vec<K>,            // challenges: r1, r2, ..., rv in K.
+}
+
+impl<F: Field, K: ExtensionField<F>> Prover<F, K> {
+    pub fn new(mles: Vec<MultilinearPoly<F>>) -> Self {
+        assert!(!mles.is_empty());
+        assert!(
+            mles.iter().all(|f| f.var_num == mles[0].var_num),
+            "All the MLEs should have the same var_num"
+        );
+        Self {
+            mles,
+            challenges: vec![],
+        }
+    }
+
+    // C1 = ∑∏f_i(b), b in {0,1}^v
+    pub fn statement(&self) -> F {
+        (0..self.mles[0].evals.len())
+            .map(|b| self.mles.iter().fold(F::one(), |acc, f| acc * f.evals[b]))
+            .sum()
+    }
+
+    pub fn prove(&mut self) -> ExtProofs<F, K> {
+        let statement = self.statement();
+        let mut transcript = Keccak256Transcript::default();
+        transcript.append(&statement.to_bytes());
+        let mut g_i_vec = vec![];
+
+        // round 1, in F.
+        let g1 = Self::round_evals(&self.mles);
+        let g1 = RoundPoly::new(g1.into_iter().map(K::from).collect());
+        transcript.append(&round_poly_to_bytes(&g1));
+        self.challenges.push(transcript.challenge_ext::<F, K>());
+        g_i_vec.push(g1);
+
+        // round 2 - v, in K, after folding with r_j-1 (K × F in round 2).
+        let mut folded = self
+            .mles
+            .iter()
+            .map(|f| f.fold(self.challenges[0]))
+            .collect::<Vec<_>>();
+        for _ in 2..=self.mles[0].var_num {
+            let g_j = RoundPoly::new(Self::round_evals(&folded));
+            transcript.append(&round_poly_to_bytes(&g_j));
+            let r_j = transcript.challenge_ext::<F, K>();
+            self.challenges.push(r_j);
+            g_i_vec.push(g_j);
+
+            folded = folded.iter().map(|f| f.fold(r_j)).collect();
+        }
+
+        // g(r1, ..., rv), once all the variables are folded.
+        let target = folded.iter().fold(K::one(), |acc, f| acc * f.evals[0]);
+
+        ExtProofs {
+            statement,
+            target,
+            g_i_vec,
+        }
+    }
+
+    // ∑∏(f_i(0, b) + X·(f_i(1, b) - f_i(0, b))), b in {0,1}^(v-j), on X = 0, 1, ..., d.
+    fn round_evals<E: Field>(mles: &[MultilinearPoly<E>]) -> Vec<E> {
+        let halves = mles.iter().map(|f| f.halves()).collect::<Vec<_>>();
+        (0..=mles.len() as u64)
+            .map(|x| {
+                let x = E::from_u64(x);
+                (0..halves[0].0.len())
+                    .map(|b| {
+                        halves.iter().fold(E::one(), |acc, (lo, hi)| {
+                            acc * (lo[b] + x * (hi[b] - lo[b]))
+                        })
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+}
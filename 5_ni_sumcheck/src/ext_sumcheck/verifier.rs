@@ -0,0 +1,55 @@
+use crate::ext_sumcheck::ExtProofs;
+use crate::transcript::default::Keccak256Transcript;
+use crate::transcript::{round_poly_to_bytes, Transcript};
+use sumcheck::field::{ExtensionField, Field};
+
+pub struct Verifier<F: Field, K: ExtensionField<F>> {
+    statement: F, // The C1
+    v: usize,
+    degree: usize,      // the max degree of the round polys.
+    challenges: Vec<K>, // challenges: r1, r2, ..., rv in K.
+}
+
+impl<F: Field, K: ExtensionField<F>> Verifier<F, K> {
+    pub fn new(v: usize, degree: usize, statement: F) -> Self {
+        Self {
+            statement,
+            v,
+            degree,
+            challenges: vec![],
+        }
+    }
+
+    pub fn verify(&mut self, proofs: ExtProofs<F, K>) {
+        assert_eq!(proofs.g_i_vec.len(), self.v);
+        assert_eq!(proofs.statement, self.statement, "No-equal in round_1");
+
+        let mut transcript = Keccak256Transcript::default();
+        transcript.append(&self.statement.to_bytes());
+
+        // C_j: C1 lifted to K, or g_j-1(r_j-1).
+        let mut claim = K::from(self.statement);
+        for (j, g_j) in proofs.g_i_vec.iter().enumerate().map(|(j, g)| (j + 1, g)) {
+            // check: C_j = g_j(0) + g_j(1)
+            assert!(
+                g_j.degree() <= self.degree,
+                "Degree too high in round_{}",
+                j
+            );
+            match j {
+                1 => assert_eq!(g_j.sum_over_hypercube(), claim, "No-equal in round_1"),
+                _ => assert_eq!(g_j.sum_over_hypercube(), claim, "Not-equal in round_{}", j),
+            }
+
+            // generate r_j
+            transcript.append(&round_poly_to_bytes(g_j));
+            let r_j = transcript.challenge_ext::<F, K>();
+            self.challenges.push(r_j);
+            claim = g_j.evaluate::<F>(r_j);
+        }
+
+        // finally check: gv(rv) = g(r1, ..., rv)
+        assert_eq!(claim, proofs.target, "Verifier rejected the proof");
+        println!("Verifier accepted the proof");
+    }
+}
@@ -1,5 +1,6 @@
 #![allow(non_snake_case)]
 
+pub mod ext_sumcheck;
 mod poly;
 mod sumcheck;
 mod transcript;
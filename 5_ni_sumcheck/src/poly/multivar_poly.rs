@@ -1,5 +1,7 @@
 use crate::poly::univar_poly::Polynomial;
-use crate::utils::{convert_to_binary, expand_factor_for_mpoly};
+use crate::utils::{
+    boolean_domains, convert_to_binary, convert_to_domain, domain_size, expand_factor_for_mpoly,
+};
 use bls12_381::Scalar;
 use ff::Field;
 use log::{debug, log};
@@ -157,20 +159,32 @@ impl MPolynomial {
     //
     // This is useful in sum-check protocol when obtaining g_i(X)
     pub fn partial_evaluate(&self, challenge_domain: &Vec<usize>) -> Polynomial {
+        let extra_var_num = self.var_num - challenge_domain.len() - 1;
+        self.partial_evaluate_over(challenge_domain, &boolean_domains(extra_var_num))
+    }
+
+    // The same as `partial_evaluate`, but (x_j+1, ..., x_v) in H_j+1 × ... × H_v instead of hypercube,
+    // where `domains` are the H_j+1, ..., H_v.
+    pub fn partial_evaluate_over(
+        &self,
+        challenge_domain: &Vec<usize>,
+        domains: &[Vec<usize>],
+    ) -> Polynomial {
         // the X = x_j, others has values.
         // Note here, x start with x_0, as the array index start with 0.
         let j = challenge_domain.len();
         assert!(j < self.var_num);
+        assert_eq!(j + 1 + domains.len(), self.var_num);
 
         // <k,v>: k is the exp of X, v is the coeff, aka. <exp, coeff>
         let mut map: HashMap<usize, Scalar> = HashMap::new();
 
         // var_num = challenger_len + 1 + extra_len
         let extra_var_num = self.var_num - j - 1;
-        let extra_n = 1 << extra_var_num;
+        let extra_n = domain_size(domains);
         let extra_domain = (0..extra_n)
             .into_iter()
-            .map(|n| convert_to_binary(&extra_var_num, n))
+            .map(|n| convert_to_domain(domains, n))
             .collect::<Vec<_>>();
         debug!(
             "extra domain {:?}, j {:?}, var_num:{:?}, extra_var_num: {:?}, extra_n: {:?}",
@@ -11,6 +11,34 @@ pub struct Polynomial {
 }
 
 impl Polynomial {
+    // Interpolate p from p(x_i) = y_i:
+    //      p(X) = ∑y_i·L_i(X),  L_i(X) = ∏_{k≠i}(X - x_k)/(x_i - x_k)
+    pub fn lagrange_interpolate(domains: Vec<Scalar>, evals: Vec<Scalar>) -> Self {
+        assert_eq!(domains.len(), evals.len());
+
+        let mut coeffs = vec![Scalar::zero(); domains.len()];
+        for (i, (x_i, y_i)) in domains.iter().zip(evals.iter()).enumerate() {
+            // ∏_{k≠i}(X - x_k), and ∏_{k≠i}(x_i - x_k)
+            let mut basis = vec![Scalar::one()];
+            let mut divisor = Scalar::one();
+            for (_, x_k) in domains.iter().enumerate().filter(|(k, _)| *k != i) {
+                let mut next = vec![Scalar::zero(); basis.len() + 1];
+                for (n, b) in basis.iter().enumerate() {
+                    next[n] -= b * x_k;
+                    next[n + 1] += b;
+                }
+                basis = next;
+                divisor *= x_i - x_k;
+            }
+
+            let scale = y_i * divisor.invert().unwrap();
+            for (c, b) in coeffs.iter_mut().zip(basis) {
+                *c += b * scale;
+            }
+        }
+        Self { coeffs }
+    }
+
     // This evaluates a polynomial (in coefficient form) at `x`.
     pub fn evaluate(&self, x: Scalar) -> Scalar {
         let coeffs = self.coeffs.clone();
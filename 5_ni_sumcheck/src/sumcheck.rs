@@ -1,41 +1,71 @@
 use crate::poly::multivar_poly::MPolynomial;
-use crate::poly::univar_poly::Polynomial;
+use crate::sumcheck::encoding::Encoding;
 use crate::sumcheck::prover::Prover;
 use crate::sumcheck::verifier::Verifier;
 use crate::transcript::default::Keccak256Transcript;
-use crate::transcript::{poly_to_bytes, Transcript};
+use crate::utils::boolean_domains;
 use bls12_381::Scalar;
 use std::env::var;
 use std::iter::Sum;
 
+mod encoding;
 mod prover;
 mod verifier;
 
 #[derive(Default)]
 pub struct Proofs {
     target: Scalar,
-    g_i_vec: Vec<Polynomial>,
+    encoding: Encoding,        // how the round polys are encoded.
+    g_i_vec: Vec<Vec<Scalar>>, // the encoded g_1, ..., g_v
+}
+
+impl Proofs {
+    // The number of field elements in the proof.
+    pub fn size(&self) -> usize {
+        self.g_i_vec.iter().map(|m| m.len()).sum::<usize>() + 1
+    }
 }
 
 pub struct SumCheck {
     prover: Prover,
     verifier: Verifier,
+    encoding: Encoding,
 }
 
 impl SumCheck {
     pub fn new(g: MPolynomial) -> Self {
-        let var_num = g.var_num;
+        let domains = boolean_domains(g.var_num);
+        Self::with_domains(g, domains)
+    }
 
-        let prover = Prover::new(var_num, g);
+    // Sum over the product domain H_1 × ... × H_v instead of the hypercube {0,1}^v,
+    // where the check of round j becomes: g_j-1(r_j-1) = ∑g_j(h), h in H_j.
+    pub fn with_domains(g: MPolynomial, domains: Vec<Vec<usize>>) -> Self {
+        let prover = Prover::with_domains(g, domains.clone());
         let statement = prover.statement();
 
-        let verifier = Verifier::new(var_num, statement);
+        let verifier = Verifier::with_domains(domains, statement);
+
+        Self {
+            prover,
+            verifier,
+            encoding: Encoding::default(),
+        }
+    }
 
-        Self { prover, verifier }
+    // How the round polys are encoded in the proof, full coefficients by default.
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
     }
 
     pub fn run_protocol(&mut self) {
-        let proofs = self.prover.prove();
+        let proofs = self.prover.prove_with(self.encoding);
+        println!(
+            "Proof size: {} field elements ({:?})",
+            proofs.size(),
+            proofs.encoding
+        );
 
         self.verifier.verify(proofs);
     }
@@ -44,6 +74,8 @@ impl SumCheck {
 #[cfg(test)]
 mod test {
     use crate::poly::multivar_poly::MPolynomial;
+    use crate::sumcheck::encoding::Encoding;
+    use crate::sumcheck::verifier::Verifier;
     use crate::sumcheck::SumCheck;
     use bls12_381::Scalar;
     use ff::PrimeField;
@@ -82,4 +114,62 @@ mod test {
 
         sumcheck.run_protocol();
     }
+
+    #[test]
+    fn test_sumcheck_compressed() {
+        let var_num = 3;
+        let full_size = SumCheck::new(gen_mpoly()).prover.prove().size();
+
+        for encoding in [
+            Encoding::CompressedEvaluations,
+            Encoding::CompressedCoefficients,
+        ] {
+            let mut sumcheck = SumCheck::new(gen_mpoly()).with_encoding(encoding);
+            let proofs = sumcheck.prover.prove_with(encoding);
+            // one field element saved per round.
+            assert_eq!(proofs.size(), full_size - var_num);
+            sumcheck.verifier.verify(proofs);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Verifier rejected the proof")]
+    fn test_sumcheck_compressed_wrong_target() {
+        let mut sumcheck = SumCheck::new(gen_mpoly());
+        let mut proofs = sumcheck.prover.prove_with(Encoding::CompressedCoefficients);
+        proofs.target += Scalar::one();
+        sumcheck.verifier.verify(proofs);
+    }
+
+    #[test]
+    fn test_sumcheck_over_mixed_domains() {
+        // g summed over {0,1,2} × {0,1} × {3,5,7,9}
+        let g = gen_mpoly();
+        let domains = vec![vec![0, 1, 2], vec![0, 1], vec![3, 5, 7, 9]];
+
+        let mut expected = Scalar::zero();
+        for x1 in &domains[0] {
+            for x2 in &domains[1] {
+                for x3 in &domains[2] {
+                    expected += g.evaluate(&vec![*x1, *x2, *x3]);
+                }
+            }
+        }
+
+        let mut sumcheck = SumCheck::with_domains(g, domains);
+        assert_eq!(sumcheck.prover.statement(), expected);
+        sumcheck.run_protocol();
+    }
+
+    #[test]
+    #[should_panic(expected = "No-equal in round_1")]
+    fn test_sumcheck_over_grid_wrong_statement() {
+        let domains = vec![vec![0, 1, 2]; 3];
+        let mut sumcheck = SumCheck::with_domains(gen_mpoly(), domains.clone());
+
+        // P claims the sum on the hypercube instead.
+        let statement = SumCheck::new(gen_mpoly()).prover.statement();
+        sumcheck.verifier = Verifier::with_domains(domains, statement);
+        sumcheck.run_protocol();
+    }
 }
@@ -0,0 +1,94 @@
+use crate::poly::univar_poly::Polynomial;
+use bls12_381::Scalar;
+
+// How P encodes the round poly g_j in the proof.
+//
+// V knows the running claim C_j (C_1 for round 1, g_j-1(r_j-1) for round j) and checks g_j(0) + g_j(1) = C_j,
+// so one field element of g_j is redundant and can be derived by V:
+//      CompressedEvaluations:  g_j(0), g_j(2), ..., g_j(d),  with g_j(1) = C_j - g_j(0)
+//      CompressedCoefficients: c_0, c_2, ..., c_d,  with c_1 = C_j - 2c_0 - c_2 - ... - c_d
+// Both save one field element per round. The round check then holds by construction,
+// and a wrong g_j is caught by the next round or the final check instead.
+//
+// The compressed encodings only work on the hypercube.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum Encoding {
+    #[default]
+    Coefficients,
+    CompressedEvaluations,
+    CompressedCoefficients,
+}
+
+impl Encoding {
+    pub fn is_compressed(&self) -> bool {
+        *self != Encoding::Coefficients
+    }
+
+    // The message in the proof.
+    pub fn encode(&self, g_j: &Polynomial) -> Vec<Scalar> {
+        let mut coeffs = g_j.coeffs.clone();
+        // at least linear, so that there's something to drop.
+        if coeffs.len() < 2 {
+            coeffs.resize(2, Scalar::zero());
+        }
+
+        match self {
+            Encoding::Coefficients => g_j.coeffs.clone(),
+            Encoding::CompressedEvaluations => (0..coeffs.len())
+                .filter(|x| *x != 1)
+                .map(|x| g_j.evaluate(Scalar::from(x as u64)))
+                .collect(),
+            Encoding::CompressedCoefficients => {
+                coeffs.remove(1);
+                coeffs
+            }
+        }
+    }
+
+    // V recovers g_j from the message and the running claim C_j.
+    pub fn decode(&self, message: &[Scalar], claim: Scalar) -> Polynomial {
+        assert!(!message.is_empty(), "Empty round message");
+        match self {
+            Encoding::Coefficients => Polynomial {
+                coeffs: message.to_vec(),
+            },
+            Encoding::CompressedEvaluations => {
+                let mut evals = message.to_vec();
+                evals.insert(1, claim - message[0]);
+                let domains = (0..evals.len()).map(|x| Scalar::from(x as u64)).collect();
+                Polynomial::lagrange_interpolate(domains, evals)
+            }
+            Encoding::CompressedCoefficients => {
+                let c_1 = claim - message[0].double() - message[1..].iter().sum::<Scalar>();
+                let mut coeffs = message.to_vec();
+                coeffs.insert(1, c_1);
+                Polynomial { coeffs }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ff::PrimeField;
+
+    #[test]
+    fn test_encoding() {
+        // g(X) = 3 + 5X + 7X^2 + 2X^3, C = g(0) + g(1) = 20
+        let g = Polynomial {
+            coeffs: [3, 5, 7, 2].iter().map(|c| Scalar::from_u128(*c)).collect(),
+        };
+        let claim = Scalar::from_u128(20);
+
+        for encoding in [
+            Encoding::Coefficients,
+            Encoding::CompressedEvaluations,
+            Encoding::CompressedCoefficients,
+        ] {
+            let message = encoding.encode(&g);
+            let decoded = encoding.decode(&message, claim);
+            assert_eq!(decoded, g);
+        }
+    }
+}
@@ -1,33 +1,41 @@
 use crate::poly::multivar_poly::MPolynomial;
 use crate::poly::univar_poly::Polynomial;
+use crate::sumcheck::encoding::Encoding;
 use crate::sumcheck::Proofs;
 use crate::transcript::default::Keccak256Transcript;
-use crate::transcript::{poly_to_bytes, Transcript};
-use crate::utils::convert_to_binary;
+use crate::transcript::{message_to_bytes, Transcript};
+use crate::utils::{boolean_domains, convert_to_domain, domain_size};
 use bls12_381::Scalar;
 
 pub struct Prover {
     g: MPolynomial,
     v: usize,
+    domains: Vec<Vec<usize>>, // H_1, ..., H_v
     challenges: Vec<usize>, // challenges: r1, r2, ..., rv. (In implement, r1 is a random usize, which is easy to construct a Field)
 }
 
 impl Prover {
     pub fn new(v: usize, mpoly: MPolynomial) -> Self {
+        Self::with_domains(mpoly, boolean_domains(v))
+    }
+
+    // Sum over H_1 × ... × H_v instead of the hypercube.
+    pub fn with_domains(mpoly: MPolynomial, domains: Vec<Vec<usize>>) -> Self {
+        assert_eq!(mpoly.var_num, domains.len(), "One domain per variable");
         Self {
             g: mpoly,
-            v,
+            v: domains.len(),
+            domains,
             challenges: vec![],
         }
     }
 
-    // sum all the evaluations on hypercube of a mpoly
+    // sum all the evaluations on H_1 × ... × H_v (hypercube by default) of a mpoly
     // obtain C1, which claimed equal H.
     pub fn statement(&self) -> Scalar {
-        let n = 1 << self.g.var_num;
-        (0..n)
+        (0..domain_size(&self.domains))
             .map(|i| {
-                let domain = convert_to_binary(&self.g.var_num, i);
+                let domain = convert_to_domain(&self.domains, i);
                 self.g.evaluate(&domain)
             })
             .sum()
@@ -40,17 +48,32 @@ impl Prover {
         transcript: &mut Keccak256Transcript,
     ) {
         assert_eq!(self.challenges.len(), proofs.g_i_vec.len());
+        let message = proofs.encoding.encode(g_i);
 
         // generate r1, r2, ..., rv
-        transcript.append(&poly_to_bytes(g_i));
+        transcript.append(&message_to_bytes(&message));
         self.challenges.push(transcript.challenge());
 
         // cache g_i
-        proofs.g_i_vec.push(g_i.clone());
+        proofs.g_i_vec.push(message);
     }
 
     pub fn prove(&mut self) -> Proofs {
-        let mut proofs = Proofs::default();
+        self.prove_with(Encoding::default())
+    }
+
+    // Prove with the round polys encoded by `encoding`.
+    pub fn prove_with(&mut self, encoding: Encoding) -> Proofs {
+        if encoding.is_compressed() {
+            assert!(
+                self.domains.iter().all(|h| h == &[0, 1]),
+                "Compressed round polys are only for the hypercube"
+            );
+        }
+        let mut proofs = Proofs {
+            encoding,
+            ..Proofs::default()
+        };
         let mut transcript = Keccak256Transcript::default();
 
         // round 1
@@ -77,18 +100,25 @@ impl Prover {
 
     // Return g1(X) = sum g(X, x_2, ..., x_v)
     fn round_1(&self) -> Polynomial {
-        self.g.partial_evaluate(&vec![])
+        self.round()
     }
 
     // 1 < j < v, total v-2 rounds
     // Return g_j = (r1, ..., r_j-1, X, x_j+1, ..., x_v)
     fn recursive_round_j(&self) -> Polynomial {
-        self.g.partial_evaluate(&self.challenges)
+        self.round()
     }
 
     // Return g_v = (r1, r2, ..., r_v-1, X_v)
     fn round_v(&self) -> Polynomial {
-        self.g.partial_evaluate(&self.challenges)
+        self.round()
+    }
+
+    // Sum over (x_j+1, ..., x_v) in H_j+1 × ... × H_v, with (r1, ..., r_j-1) from the challenges so far.
+    fn round(&self) -> Polynomial {
+        let j = self.challenges.len();
+        self.g
+            .partial_evaluate_over(&self.challenges, &self.domains[j + 1..])
     }
 
     fn evaluate(&self) -> Scalar {
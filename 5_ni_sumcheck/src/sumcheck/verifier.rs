@@ -1,7 +1,9 @@
 use crate::poly::univar_poly::Polynomial;
+use crate::sumcheck::encoding::Encoding;
 use crate::sumcheck::Proofs;
 use crate::transcript::default::Keccak256Transcript;
-use crate::transcript::{poly_to_bytes, Transcript};
+use crate::transcript::{message_to_bytes, Transcript};
+use crate::utils::boolean_domains;
 use bls12_381::Scalar;
 use ff::{Field, PrimeField};
 
@@ -9,28 +11,35 @@ pub struct Verifier {
     // The C1
     statement: Scalar,
     v: usize,
+    domains: Vec<Vec<usize>>, // H_1, ..., H_v
     challenges: Vec<usize>, // challenges: r1, r2, ..., rv. (In implement, r1 is a random usize, which is easy to construct a Field)
 }
 
 impl Verifier {
     pub fn new(v: usize, statement: Scalar) -> Self {
+        Self::with_domains(boolean_domains(v), statement)
+    }
+
+    // The statement C1 is the sum over H_1 × ... × H_v instead of the hypercube.
+    pub fn with_domains(domains: Vec<Vec<usize>>, statement: Scalar) -> Self {
         Self {
             statement,
-            v,
+            v: domains.len(),
+            domains,
             challenges: vec![],
         }
     }
     fn prepare_for_next_round(
         &mut self,
         j: usize,
-        g_j: &Polynomial,
+        message: &[Scalar],
         transcript: &mut Keccak256Transcript,
     ) {
         assert!(j >= 1);
         assert_eq!(self.challenges.len(), j - 1);
 
         // generate r1, r2, ..., rv
-        transcript.append(&poly_to_bytes(g_j));
+        transcript.append(&message_to_bytes(message));
         self.challenges.push(transcript.challenge());
     }
 
@@ -39,47 +48,82 @@ impl Verifier {
 
         let mut transcript = Keccak256Transcript::default();
 
-        let g_i_vec = proofs.g_i_vec;
+        let encoding = proofs.encoding;
+        let messages = proofs.g_i_vec;
 
         // round 1
-        let g1 = &g_i_vec[0];
+        let g1 = self.receive(encoding, &messages[0], None);
         self.round_1(&g1);
-        self.prepare_for_next_round(1, &g1, &mut transcript);
+        self.prepare_for_next_round(1, &messages[0], &mut transcript);
 
         // round 2 - (v-1)
+        let mut g_j_minus_1 = g1;
         for j in 2..self.v {
-            let g_j = &g_i_vec[j - 1];
-            let g_j_minus_1 = &g_i_vec[j - 2];
-            self.check_round_j(j, g_j_minus_1, g_j);
+            let g_j = self.receive(encoding, &messages[j - 1], Some(&g_j_minus_1));
+            self.check_round_j(j, &g_j_minus_1, &g_j);
 
-            self.prepare_for_next_round(j, &g_j, &mut transcript);
+            self.prepare_for_next_round(j, &messages[j - 1], &mut transcript);
+            g_j_minus_1 = g_j;
         }
 
         // round v
-        let g_v = &g_i_vec[self.v - 1];
-        let g_v_minus_1 = &g_i_vec[self.v - 2];
-        self.round_v(g_v_minus_1, g_v);
+        let g_v = self.receive(encoding, &messages[self.v - 1], Some(&g_j_minus_1));
+        self.round_v(&g_j_minus_1, &g_v);
         // prepare r_v and store g_v.
-        self.prepare_for_next_round(self.v, &g_v, &mut transcript);
+        self.prepare_for_next_round(self.v, &messages[self.v - 1], &mut transcript);
 
         // finally check.
         self.check(proofs.target, &g_v);
     }
 
-    // Check: C1 = g_1(0) + g_1(1)
+    // Recover g_j from the message, with the running claim C_j: C_1, or g_j-1(r_j-1).
+    fn receive(
+        &self,
+        encoding: Encoding,
+        message: &[Scalar],
+        g_j_minus_1: Option<&Polynomial>,
+    ) -> Polynomial {
+        let j = self.challenges.len() + 1;
+        if encoding.is_compressed() {
+            assert_eq!(
+                self.domains[j - 1],
+                [0, 1],
+                "Compressed round polys are only for the hypercube"
+            );
+        }
+
+        let claim = match g_j_minus_1 {
+            None => self.statement,
+            Some(g) => {
+                let r = *self.challenges.last().unwrap() as u64;
+                g.evaluate(Scalar::from(r))
+            }
+        };
+        encoding.decode(message, claim)
+    }
+
+    // ∑g_j(h), h in H_j. It's g_j(0) + g_j(1) on the hypercube.
+    fn sum_over_domain(&self, j: usize, g_j: &Polynomial) -> Scalar {
+        self.domains[j - 1]
+            .iter()
+            .map(|h| g_j.evaluate(Scalar::from(*h as u64)))
+            .sum()
+    }
+
+    // Check: C1 = ∑g_1(h), h in H_1
     fn round_1(&mut self, g1: &Polynomial) {
-        let actual = g1.evaluate(Scalar::one()) + g1.evaluate(Scalar::zero());
+        let actual = self.sum_over_domain(1, g1);
 
         assert_eq!(actual, self.statement, "No-equal in round_1");
     }
 
-    // Check: gv−1 (rv−1 ) = gv (0) + gv (1).
+    // Check: gv−1 (rv−1 ) = ∑gv(h), h in H_v.
     fn round_v(&mut self, g_v_minus_1: &Polynomial, g_v: &Polynomial) {
         self.check_round_j(self.v, g_v_minus_1, g_v);
     }
 
     // 1 < j < v, total v-2 rounds
-    // check: g_j-1(r_j-1) = g_j(0) + g_j(1)
+    // check: g_j-1(r_j-1) = ∑g_j(h), h in H_j
     fn check_round_j(&mut self, j: usize, g_j_minus_1: &Polynomial, g_j: &Polynomial) {
         assert_eq!(
             j - 1,
@@ -89,8 +133,8 @@ impl Verifier {
 
         // r_j-1
         let r_j_minus_1 = self.challenges.last().unwrap().clone() as u128;
-        // g_j(0) + g_j(1)
-        let actual = g_j.evaluate(Scalar::zero()) + g_j.evaluate(Scalar::one());
+        // ∑g_j(h), h in H_j
+        let actual = self.sum_over_domain(j, g_j);
         let target = g_j_minus_1.evaluate(Scalar::from_u128(r_j_minus_1));
 
         assert_eq!(actual, target, "Not-equal in round_{}", j);
@@ -1,8 +1,9 @@
 #![allow(clippy::map_flatten)]
 #![allow(clippy::ptr_arg)]
 use bls12_381::Scalar;
+use sumcheck::field::{ExtensionField, Field};
+use sumcheck::poly::ext_poly::RoundPoly;
 
-use crate::poly::univar_poly::Polynomial;
 pub mod default;
 
 pub trait Transcript {
@@ -10,10 +11,27 @@ pub trait Transcript {
 
     // generate r1, r2, ..., rv
     fn challenge(&mut self) -> usize;
+
+    // A 64-bit challenge, for the small fields.
+    fn challenge_u64(&mut self) -> u64;
+
+    // A challenge in the extension K, with one base field element per coordinate.
+    fn challenge_ext<F: Field, K: ExtensionField<F>>(&mut self) -> K {
+        let coords = (0..K::DEGREE)
+            .map(|_| F::from_u64(self.challenge_u64()))
+            .collect::<Vec<_>>();
+        K::from_base_slice(&coords)
+    }
+}
+
+// The bytes of a round message, which are the coeffs of g_j with the default encoding.
+pub(crate) fn message_to_bytes(message: &[Scalar]) -> Vec<u8> {
+    coeffs_to_bytes(&message.to_vec())
 }
 
-pub(crate) fn poly_to_bytes(poly: &Polynomial) -> Vec<u8> {
-    coeffs_to_bytes(&poly.coeffs)
+// The bytes of a round poly over a small field, by its evaluations.
+pub(crate) fn round_poly_to_bytes<K: Field>(g: &RoundPoly<K>) -> Vec<u8> {
+    g.evals.iter().flat_map(|e| e.to_bytes()).collect()
 }
 
 fn coeffs_to_bytes(coeffs: &Vec<Scalar>) -> Vec<u8> {
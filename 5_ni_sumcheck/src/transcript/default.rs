@@ -21,6 +21,13 @@ impl Transcript for Keccak256Transcript {
         let sum = result_hash.to_vec().iter().map(|&b| b as usize).sum();
         sum
     }
+
+    fn challenge_u64(&mut self) -> u64 {
+        let mut result_hash = [0_u8; 32];
+        result_hash.copy_from_slice(&self.hasher.finalize_reset());
+        self.hasher.update(result_hash);
+        u64::from_le_bytes(result_hash[..8].try_into().unwrap())
+    }
 }
 
 impl Default for Keccak256Transcript {
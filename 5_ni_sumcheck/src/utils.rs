@@ -17,6 +17,35 @@ pub fn convert_from_binary(num: &Vec<usize>) -> usize {
     num.iter().rev().enumerate().map(|(i, n)| n << i).sum()
 }
 
+// The hypercube {0,1}^v, as the product domain H_1 × ... × H_v with H_i = {0,1}.
+pub fn boolean_domains(var_num: usize) -> Vec<Vec<usize>> {
+    vec![vec![0, 1]; var_num]
+}
+
+// |H_1 × ... × H_v|
+pub fn domain_size(domains: &[Vec<usize>]) -> usize {
+    domains.iter().map(|h| h.len()).product()
+}
+
+// convert a num into a point of H_1 × ... × H_v, by the mixed radix (|H_1|, ..., |H_v|) with x_v as the lowest digit.
+// eg: H = {0,1,2} × {0,1}, 3 -> [1, 1]
+// It's the same as `convert_to_binary` on the hypercube.
+pub fn convert_to_domain(domains: &[Vec<usize>], num: usize) -> Vec<usize> {
+    assert!(num < domain_size(domains));
+    let mut num = num;
+    let mut point = domains
+        .iter()
+        .rev()
+        .map(|h| {
+            let x = h[num % h.len()];
+            num /= h.len();
+            x
+        })
+        .collect::<Vec<_>>();
+    point.reverse();
+    point
+}
+
 // try to expand factorization form to coeffs form for `uni-variable poly`
 // For now, we'll only support two factorizations to a coeffs.
 // eg: (4x^2 + 1)(x + 4) = 4x^3 + 4x^2 + x + 4